@@ -1,49 +1,81 @@
 use time::OffsetDateTime;
 
 use super::{
-  bill::SessionBill, chargeable_energy::ChargeableEnergy, errors::SessionValueError, grace_period::GracePeriod,
-  kwh_milli::KwhMilli, rate::RateYenPerKwh, session_id::SessionId, timeline::SessionTimeline,
+  active_session::ActiveSession,
+  amendment::{AmendmentReason, BillAmendment},
+  bill::SessionBill,
+  billing_policy::BillingPolicy,
+  closed_session::ClosedSession,
+  errors::SessionValueError,
+  grace_rule::GraceRule,
+  kwh_milli::KwhMilli,
+  rate::RateYenPerKwh,
+  session_id::SessionId,
+  tariff::Tariff,
 };
 
 /// 充電セッションのライフサイクルを表す列挙体。
+///
+/// 保存や一覧など状態を問わず扱う場面のための型消去ラッパー。状態ごとの操作は
+/// `ActiveSession` / `ClosedSession` を直接使うと、誤った状態での呼び出しがコンパイル時に弾かれる。
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Session {
   /// 課金進行中の状態。
-  Active {
-    /// セッションID。
-    id:         SessionId,
-    /// セッション開始時刻。
-    started_at: OffsetDateTime,
-    /// 単価（円/kWh）。
-    rate:       RateYenPerKwh,
-  },
+  Active(ActiveSession),
   /// 停止済みで請求が確定した状態。
-  Closed {
-    /// セッションID。
-    id:         SessionId,
-    /// セッション開始時刻。
-    started_at: OffsetDateTime,
-    /// 終了時刻。
-    ended_at:   OffsetDateTime,
-    /// 単価（円/kWh）。
-    rate:       RateYenPerKwh,
-    /// 確定した請求。
-    bill:       SessionBill,
-  },
+  Closed(ClosedSession),
 }
 
 impl Session {
-  /// アクティブ状態のセッションを生成する。
+  /// アクティブ状態のセッションを生成する（無料ルールは開始から5分）。
   pub fn new_active(id: SessionId, started_at: OffsetDateTime, rate: RateYenPerKwh) -> Self {
-      todo!("AIに実装させる")
+    todo!("AIに実装させる")
+  }
+
+  /// 無料ルールを指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_grace(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    rate: RateYenPerKwh,
+    grace: GraceRule,
+  ) -> Self {
+    todo!("AIに実装させる")
+  }
+
+  /// 課金方針を指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_policy(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    rate: RateYenPerKwh,
+    policy: BillingPolicy,
+  ) -> Self {
+    todo!("AIに実装させる")
+  }
+
+  /// 料金表と課金方針を指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_tariff(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    tariff: Tariff,
+    policy: BillingPolicy,
+  ) -> Self {
+    todo!("AIに実装させる")
   }
 
   /// セッションを停止し、請求を確定させる。
+  ///
+  /// # Errors
+  /// 停止済みの場合は `SessionValueError::AlreadyClosed`、タイムラインや金額が不正な場合は対応する
+  /// `SessionValueError` を返します。
   pub fn stop(self, ended_at: OffsetDateTime, total_energy: KwhMilli) -> Result<Self, SessionValueError> {
-      todo!("AIに実装させる")
+    todo!("AIに実装させる")
   }
 
   /// 指定時点での課金スナップショットを取得する。
+  ///
+  /// # Errors
+  /// 停止済みの場合は `SessionValueError::AlreadyClosed`、タイムラインや金額が不正な場合は対応する
+  /// `SessionValueError` を返します。
   pub fn bill_snapshot(
     &self,
     ended_at: OffsetDateTime,
@@ -53,14 +85,59 @@ impl Session {
   }
 
   /// 停止後の追加課金要求に応答する。
+  ///
+  /// # Errors
+  /// - 停止済みセッションでは常に `SessionValueError::AlreadyClosed` を返します。
+  /// - アクティブなセッションでは `SessionValueError::NotClosed` を返します（`bill_snapshot`
+  ///   を使うこと）。
   pub fn bill_after_stop(
     &self,
-    ended_at: OffsetDateTime,
-    total_energy: KwhMilli,
+    _ended_at: OffsetDateTime,
+    _total_energy: KwhMilli,
   ) -> Result<SessionBill, SessionValueError> {
     todo!("AIに実装させる")
   }
 
+  /// 停止後に届いた確定エネルギー量で請求を訂正する。
+  ///
+  /// 通常の停止後課金（`bill_after_stop`）とは異なり、訂正前の請求と理由を履歴に残した上で
+  /// 請求を再計算する。
+  ///
+  /// # Errors
+  /// - 停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - 再計算に失敗した場合、対応する `SessionValueError` を返します。
+  pub fn amend(self, corrected_energy: KwhMilli, reason: AmendmentReason) -> Result<Self, SessionValueError> {
+    todo!("AIに実装させる")
+  }
+
+  /// アクティブなセッションを取り出す。
+  ///
+  /// # Errors
+  /// 停止済みの場合、`SessionValueError::AlreadyClosed` を返します。
+  pub fn into_active(self) -> Result<ActiveSession, SessionValueError> {
+    todo!("AIに実装させる")
+  }
+
+  /// 停止済みのセッションを取り出す。
+  ///
+  /// # Errors
+  /// 停止していない場合、`SessionValueError::NotClosed` を返します。
+  pub fn into_closed(self) -> Result<ClosedSession, SessionValueError> {
+    todo!("AIに実装させる")
+  }
+
+  /// 停止済みであれば参照を返す。
+  #[must_use]
+  pub fn as_closed(&self) -> Option<&ClosedSession> {
+    todo!("AIに実装させる")
+  }
+
+  /// 請求訂正の履歴を返す（アクティブ状態や未訂正の場合は空）。
+  #[must_use]
+  pub fn amendments(&self) -> &[BillAmendment] {
+    todo!("AIに実装させる")
+  }
+
   /// セッションを識別する。
   #[must_use]
   pub fn identity(&self) -> SessionId {
@@ -72,8 +149,16 @@ impl Session {
   pub fn statement(&self) -> Option<&SessionBill> {
     todo!("AIに実装させる")
   }
+}
+
+impl From<ActiveSession> for Session {
+  fn from(value: ActiveSession) -> Self {
+    todo!("AIに実装させる")
+  }
+}
 
-  fn grace_period() -> GracePeriod {
+impl From<ClosedSession> for Session {
+  fn from(value: ClosedSession) -> Self {
     todo!("AIに実装させる")
   }
 }
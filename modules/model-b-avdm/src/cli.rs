@@ -0,0 +1,83 @@
+mod args;
+mod batch;
+mod explain;
+mod input;
+
+use std::{
+  fs,
+  io::{self, Read, Write},
+  process::ExitCode,
+};
+
+pub(crate) use args::Cli;
+use args::{BatchArgs, BillArgs, Command};
+use batch::run_batch;
+use explain::explain;
+use input::SessionInput;
+use model_b_avdm::session::{IdGenerator, Locale, SequentialIdGenerator};
+
+/// サブコマンドを実行し、終了コードを返す。
+pub(crate) fn run(cli: Cli) -> ExitCode {
+  let result = match cli.command {
+    | Command::Bill(args) => run_bill(&args),
+    | Command::Batch(args) => run_batch_command(&args),
+  };
+  match result {
+    | Ok(true) => ExitCode::SUCCESS,
+    | Ok(false) => ExitCode::FAILURE,
+    | Err(error) => {
+      eprintln!("error: {error}");
+      ExitCode::FAILURE
+    },
+  }
+}
+
+/// 1 件のセッションを請求し、結果を標準出力に書く。請求できなかった場合は `Ok(false)` を返す。
+fn run_bill(args: &BillArgs) -> io::Result<bool> {
+  let billed = SessionInput::parse(&args.start, &args.end, &args.energy, &args.rate)
+    .and_then(|input| input.bill(SequentialIdGenerator::new().next_id(), args.pricing.policy()));
+  let closed = match billed {
+    | Ok(closed) => closed,
+    | Err(error) => {
+      eprintln!("error: {}: {}", error.code(), error.message());
+      return Ok(false);
+    },
+  };
+
+  let bill = closed.bill();
+  let mut stdout = io::stdout().lock();
+  writeln!(stdout, "amount: {}", bill.amount_due().to_locale_string(Locale::EnUs))?;
+  writeln!(
+    stdout,
+    "energy: {} billable of {}",
+    bill.billable_energy().to_locale_string(Locale::EnUs),
+    bill.total_energy().to_locale_string(Locale::EnUs)
+  )?;
+  if args.pricing.explain {
+    writeln!(stdout)?;
+    for line in explain(&closed) {
+      writeln!(stdout, "{line}")?;
+    }
+  }
+  Ok(true)
+}
+
+/// ファイルまたは標準入力のセッションをまとめて請求する。1 行でも失敗すれば `Ok(false)` を返す。
+fn run_batch_command(args: &BatchArgs) -> io::Result<bool> {
+  let input = match args.input.as_deref() {
+    | Some(path) if path.as_os_str() != "-" => fs::read_to_string(path)?,
+    | _ => {
+      let mut input = String::new();
+      io::stdin().read_to_string(&mut input)?;
+      input
+    },
+  };
+  let failures = run_batch(&input, args.format, &args.pricing, &mut io::stdout().lock())?;
+  if failures > 0 {
+    eprintln!("{failures} session(s) could not be billed");
+  }
+  Ok(failures == 0)
+}
+
+#[cfg(test)]
+mod tests;
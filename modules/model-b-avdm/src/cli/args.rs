@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use model_b_avdm::session::{BillingPolicy, GracePeriod, GraceRule};
+
+/// コマンドライン引数。
+#[derive(Debug, Parser)]
+#[command(name = "model-b-avdm", about = "Bill EV charging sessions with the model-b domain model")]
+pub(crate) struct Cli {
+  #[command(subcommand)]
+  pub(crate) command: Command,
+}
+
+/// サブコマンド。
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+  /// Bill a single session.
+  Bill(BillArgs),
+  /// Bill sessions read from a CSV or JSON Lines file (or stdin), one result per row.
+  Batch(BatchArgs),
+}
+
+/// `bill` の引数。
+#[derive(Debug, Args)]
+pub(crate) struct BillArgs {
+  /// Start of the session (RFC 3339, e.g. 2025-04-01T10:00:00Z).
+  #[arg(long)]
+  pub(crate) start:   String,
+  /// End of the session (RFC 3339).
+  #[arg(long)]
+  pub(crate) end:     String,
+  /// Delivered energy in kWh with up to 3 decimals (e.g. 12.5), or with a unit (e.g. "12500 Wh").
+  #[arg(long)]
+  pub(crate) energy:  String,
+  /// Rate in yen per kWh (e.g. 50).
+  #[arg(long)]
+  pub(crate) rate:    String,
+  #[command(flatten)]
+  pub(crate) pricing: PricingArgs,
+}
+
+/// `batch` の引数。
+#[derive(Debug, Args)]
+pub(crate) struct BatchArgs {
+  /// Input file with columns start, end, energy, rate and an optional id. Reads stdin when omitted
+  /// or "-".
+  pub(crate) input:   Option<PathBuf>,
+  /// Input format. Detected from the first non-blank character when omitted.
+  #[arg(long, value_enum)]
+  pub(crate) format:  Option<InputFormat>,
+  #[command(flatten)]
+  pub(crate) pricing: PricingArgs,
+}
+
+/// `bill` と `batch` に共通する課金の指定。
+#[derive(Debug, Clone, Copy, Args)]
+pub(crate) struct PricingArgs {
+  /// Free minutes from the start of each session.
+  #[arg(long, default_value_t = 5)]
+  pub(crate) grace_minutes: u32,
+  /// Explain how the grace period and floor rounding produced the amount.
+  #[arg(long)]
+  pub(crate) explain:       bool,
+}
+
+impl PricingArgs {
+  /// 無料時間だけを指定した課金方針を返す。
+  pub(crate) fn policy(self) -> BillingPolicy {
+    BillingPolicy::with_grace(GraceRule::Time(GracePeriod::from_minutes(u128::from(self.grace_minutes))))
+  }
+}
+
+/// `batch` の入力形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum InputFormat {
+  /// Comma-separated values with a header row.
+  Csv,
+  /// One JSON object per line.
+  Jsonl,
+}
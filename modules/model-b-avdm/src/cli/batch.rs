@@ -0,0 +1,123 @@
+use std::{
+  collections::BTreeMap,
+  io::{self, Write},
+};
+
+use csv::{Position, ReaderBuilder, Trim};
+use model_b_avdm::session::{BillingPolicy, ClosedSession, IdGenerator, SequentialIdGenerator, SessionId};
+use serde_json::{Value, json};
+
+use super::{
+  args::{InputFormat, PricingArgs},
+  explain::explain,
+  input::{RowError, SessionInput},
+};
+
+/// 入力の 1 件。`line` は入力の行番号（1 始まり）。
+struct Row {
+  line:   u64,
+  fields: Result<BTreeMap<String, String>, RowError>,
+}
+
+/// 入力のセッションを 1 件ずつ請求し、結果を JSON Lines で書き出す。
+///
+/// 請求できなかった行もエラーコードとメッセージを書き出して処理を続け、失敗した件数を返す。
+pub(crate) fn run_batch(
+  input: &str,
+  format: Option<InputFormat>,
+  pricing: &PricingArgs,
+  out: &mut impl Write,
+) -> io::Result<usize> {
+  let rows = match format.unwrap_or_else(|| detect_format(input)) {
+    | InputFormat::Csv => csv_rows(input),
+    | InputFormat::Jsonl => jsonl_rows(input),
+  };
+
+  let mut ids = SequentialIdGenerator::new();
+  let mut failures = 0;
+  for row in rows {
+    let mut result = json!({ "line": row.line });
+    if let Some(id) = row.fields.as_ref().ok().and_then(|fields| fields.get("id")) {
+      result["id"] = json!(id);
+    }
+    match row.fields.and_then(|fields| bill_row(&fields, ids.next_id(), pricing.policy())) {
+      | Ok(closed) => {
+        let bill = closed.bill();
+        result["amount_yen"] = json!(u64::from(bill.amount_due()));
+        result["billable_wh"] = json!(u64::from(bill.billable_energy()));
+        result["total_wh"] = json!(u64::from(bill.total_energy()));
+        if pricing.explain {
+          result["explanation"] = json!(explain(&closed));
+        }
+      },
+      | Err(error) => {
+        failures += 1;
+        result["error"] = json!({ "code": error.code(), "message": error.message() });
+      },
+    }
+    writeln!(out, "{result}")?;
+  }
+  Ok(failures)
+}
+
+/// 最初の空白以外の文字が `{` なら JSON Lines、それ以外は CSV とみなす。
+fn detect_format(input: &str) -> InputFormat {
+  if input.trim_start().starts_with('{') { InputFormat::Jsonl } else { InputFormat::Csv }
+}
+
+fn bill_row(
+  fields: &BTreeMap<String, String>,
+  id: SessionId,
+  policy: BillingPolicy,
+) -> Result<ClosedSession, RowError> {
+  let field = |name: &'static str| {
+    fields.get(name).map(String::as_str).filter(|value| !value.is_empty()).ok_or(RowError::MissingField(name))
+  };
+  SessionInput::parse(field("start")?, field("end")?, field("energy")?, field("rate")?)?.bill(id, policy)
+}
+
+fn csv_rows(input: &str) -> Vec<Row> {
+  let mut reader = ReaderBuilder::new().trim(Trim::All).from_reader(input.as_bytes());
+  let headers = match reader.headers() {
+    | Ok(headers) => headers.clone(),
+    | Err(error) => return vec![Row { line: 1, fields: Err(RowError::Malformed(error.to_string())) }],
+  };
+  reader
+    .records()
+    .map(|record| match record {
+      | Ok(record) => Row {
+        line:   record.position().map_or(0, Position::line),
+        fields: Ok(headers.iter().zip(&record).map(|(name, value)| (name.to_owned(), value.to_owned())).collect()),
+      },
+      | Err(error) => {
+        Row { line: error.position().map_or(0, Position::line), fields: Err(RowError::Malformed(error.to_string())) }
+      },
+    })
+    .collect()
+}
+
+fn jsonl_rows(input: &str) -> Vec<Row> {
+  input
+    .lines()
+    .zip(1..)
+    .filter(|(line, _)| !line.trim().is_empty())
+    .map(|(line, number)| Row { line: number, fields: json_fields(line) })
+    .collect()
+}
+
+/// JSON オブジェクトの文字列・数値の項目を取り出す。`null` の項目は省略とみなす。
+fn json_fields(line: &str) -> Result<BTreeMap<String, String>, RowError> {
+  let value: Value = serde_json::from_str(line).map_err(|error| RowError::Malformed(error.to_string()))?;
+  let Value::Object(object) = value else {
+    return Err(RowError::Malformed("expected a JSON object".to_owned()));
+  };
+  object
+    .into_iter()
+    .filter_map(|(name, value)| match value {
+      | Value::Null => None,
+      | Value::String(value) => Some(Ok((name, value))),
+      | Value::Number(value) => Some(Ok((name, value.to_string()))),
+      | _ => Some(Err(RowError::Malformed(format!("the {name} field must be a string or a number")))),
+    })
+    .collect()
+}
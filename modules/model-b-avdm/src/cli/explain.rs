@@ -0,0 +1,99 @@
+use model_b_avdm::session::{ClosedSession, Locale, MoneyYen};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// 請求額に至った計算の過程を、無料時間・エネルギーの按分・丸め・最低料金と上限料金の順に説明する。
+///
+/// CLI の課金方針はエネルギーが一様に供給されたとみなすため、課金対象エネルギーは
+/// 総エネルギーを課金対象時間の割合で按分し、1 Wh 未満を切り捨てた値になる。
+pub(crate) fn explain(closed: &ClosedSession) -> Vec<String> {
+  let bill = closed.bill();
+  let elapsed = u128::try_from((closed.ended_at() - closed.started_at()).whole_milliseconds()).unwrap_or(0);
+  let grace = closed.policy().grace().grace_period().millis();
+  let chargeable = elapsed.saturating_sub(grace);
+  let total = bill.total_energy().to_locale_string(Locale::EnUs);
+  let billable = bill.billable_energy().to_locale_string(Locale::EnUs);
+
+  let mut lines = vec![format!(
+    "session: {} -> {} ({})",
+    timestamp(closed.started_at()),
+    timestamp(closed.ended_at()),
+    duration(elapsed)
+  )];
+  if grace == 0 {
+    lines.push(format!("grace period: none; all {} is chargeable", duration(elapsed)));
+    lines.push(format!("billable energy: all {total}"));
+  } else if chargeable == 0 {
+    lines.push(format!(
+      "grace period: the session ends within the first {} free, so nothing is billable",
+      duration(grace)
+    ));
+    lines.push(format!("billable energy: {billable} of {total}"));
+  } else {
+    lines.push(format!(
+      "grace period: the first {} are free; {} of {} is chargeable",
+      duration(grace),
+      duration(chargeable),
+      duration(elapsed)
+    ));
+    lines.push(format!(
+      "billable energy: {total} x {} / {} = {billable} (delivered uniformly, floored to 1 Wh)",
+      duration(chargeable),
+      duration(elapsed)
+    ));
+  }
+
+  let mut subtotal = 0;
+  for line in bill.lines() {
+    subtotal += line.amount_milli_yen();
+    lines.push(format!(
+      "charge: {} x {} = {}",
+      line.energy().to_locale_string(Locale::EnUs),
+      line.rate().to_locale_string(Locale::EnUs),
+      milli_yen(line.amount_milli_yen())
+    ));
+  }
+  let amount_due = bill.amount_due().to_locale_string(Locale::EnUs);
+  let limit = if bill.minimum_fee_applied() {
+    Some("raised to the minimum fee")
+  } else if bill.price_cap_applied() {
+    Some("capped at the price cap")
+  } else {
+    None
+  };
+  match limit {
+    | Some(limit) => {
+      let floored = whole_yen(subtotal);
+      lines.push(format!("subtotal: {} floored to whole yen = {floored}", milli_yen(subtotal)));
+      lines.push(format!("amount due: {floored} {limit} = {amount_due}"));
+    },
+    | None => lines.push(format!("amount due: {} floored to whole yen = {amount_due}", milli_yen(subtotal))),
+  }
+  lines
+}
+
+fn timestamp(at: OffsetDateTime) -> String {
+  at.format(&Rfc3339).unwrap_or_else(|_| at.to_string())
+}
+
+/// ミリ秒を `1h 5m 0s` の形で表す。1 秒未満があれば `0.250s` のように小数で添える。
+fn duration(millis: u128) -> String {
+  let seconds = millis / 1_000;
+  let (hours, minutes, seconds, millis) = (seconds / 3_600, seconds / 60 % 60, seconds % 60, millis % 1_000);
+  let seconds = if millis == 0 { format!("{seconds}s") } else { format!("{seconds}.{millis:03}s") };
+  match (hours, minutes) {
+    | (0, 0) => seconds,
+    | (0, _) => format!("{minutes}m {seconds}"),
+    | _ => format!("{hours}h {minutes}m {seconds}"),
+  }
+}
+
+/// ミリ円を `¥1,234.567` の形で表す。
+fn milli_yen(milli: u128) -> String {
+  format!("{}.{:03}", whole_yen(milli), milli % 1_000)
+}
+
+/// ミリ円を 1 円未満を切り捨てて `¥1,234` の形で表す。
+fn whole_yen(milli: u128) -> String {
+  let yen = u64::try_from(milli / 1_000).unwrap_or(u64::MAX);
+  MoneyYen::try_new(yen).map_or_else(|_| format!("¥{yen}"), |yen| yen.to_locale_string(Locale::EnUs))
+}
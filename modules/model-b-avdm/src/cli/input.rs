@@ -0,0 +1,98 @@
+use model_b_avdm::session::{
+  ActiveSession, BillingPolicy, ClosedSession, KwhMilli, Locale, RateYenPerKwh, SessionId, SessionValueError, Tariff,
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// 1 件のセッションの請求に必要な入力。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SessionInput {
+  started_at: OffsetDateTime,
+  ended_at:   OffsetDateTime,
+  energy:     KwhMilli,
+  rate:       RateYenPerKwh,
+}
+
+/// 入力の 1 件を請求できなかった理由。
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RowError {
+  /// 行を解析できなかった。
+  Malformed(String),
+  /// 必須の列がなかった。
+  MissingField(&'static str),
+  /// 時刻が RFC 3339 ではなかった。
+  InvalidTimestamp {
+    /// 列名。
+    field: &'static str,
+    /// 入力された値。
+    value: String,
+  },
+  /// ドメインルールに違反した。
+  Domain(SessionValueError),
+}
+
+impl RowError {
+  /// 機械処理向けのエラーコードを返す。ドメインエラーは `SessionValueError::code` をそのまま使う。
+  pub(crate) fn code(&self) -> &'static str {
+    match self {
+      | Self::Malformed(_) => "MALFORMED_ROW",
+      | Self::MissingField(_) => "MISSING_FIELD",
+      | Self::InvalidTimestamp { .. } => "INVALID_TIMESTAMP",
+      | Self::Domain(error) => error.code(),
+    }
+  }
+
+  /// 英語のメッセージを返す。
+  pub(crate) fn message(&self) -> String {
+    match self {
+      | Self::Malformed(reason) => format!("the row could not be parsed: {reason}"),
+      | Self::MissingField(field) => format!("the {field} field is missing"),
+      | Self::InvalidTimestamp { field, value } => {
+        format!("the {field} field must be an RFC 3339 timestamp (got {value:?})")
+      },
+      | Self::Domain(error) => error.message(Locale::EnUs),
+    }
+  }
+}
+
+impl From<SessionValueError> for RowError {
+  fn from(error: SessionValueError) -> Self {
+    Self::Domain(error)
+  }
+}
+
+impl SessionInput {
+  /// 開始・終了時刻、エネルギー量、単価の文字列を解釈する。
+  ///
+  /// エネルギー量は単位なしなら kWh（小数点以下 3 桁まで）、`12500 Wh` のように単位付きでもよい。
+  /// 単価は単位なしなら円/kWh、`50円/kWh` のように単位付きでもよい。
+  pub(crate) fn parse(start: &str, end: &str, energy: &str, rate: &str) -> Result<Self, RowError> {
+    Ok(Self {
+      started_at: timestamp("start", start)?,
+      ended_at:   timestamp("end", end)?,
+      energy:     parse_energy(energy)?,
+      rate:       parse_rate(rate)?,
+    })
+  }
+
+  /// 単価を均一料金として課金方針とともに適用し、請求を確定する。
+  pub(crate) fn bill(&self, id: SessionId, policy: BillingPolicy) -> Result<ClosedSession, RowError> {
+    Ok(ActiveSession::new(id, self.started_at, Tariff::flat(self.rate), policy).stop(self.ended_at, self.energy)?)
+  }
+}
+
+fn timestamp(field: &'static str, value: &str) -> Result<OffsetDateTime, RowError> {
+  OffsetDateTime::parse(value.trim(), &Rfc3339)
+    .map_err(|_| RowError::InvalidTimestamp { field, value: value.to_owned() })
+}
+
+fn parse_energy(value: &str) -> Result<KwhMilli, SessionValueError> {
+  let value = value.trim();
+  if value.ends_with("Wh") {
+    return value.parse();
+  }
+  format!("{value} kWh").parse()
+}
+
+fn parse_rate(value: &str) -> Result<RateYenPerKwh, SessionValueError> {
+  value.trim().parse::<u32>().map_or_else(|_| value.parse(), RateYenPerKwh::try_new)
+}
@@ -0,0 +1,166 @@
+use model_b_avdm::session::{
+  ActiveSession, IdGenerator, KwhMilli, MoneyYen, RateYenPerKwh, SequentialIdGenerator, SessionValueError, Tariff,
+};
+use serde_json::{Value, json};
+use time::{Duration, macros::datetime};
+
+use super::{
+  args::{InputFormat, PricingArgs},
+  batch::run_batch,
+  explain::explain,
+  input::{RowError, SessionInput},
+};
+
+const PRICING: PricingArgs = PricingArgs { grace_minutes: 5, explain: false };
+
+fn batch(input: &str, format: Option<InputFormat>, pricing: PricingArgs) -> (usize, Vec<Value>) {
+  let mut out = Vec::new();
+  let failures = run_batch(input, format, &pricing, &mut out).unwrap();
+  let results = String::from_utf8(out).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+  (failures, results)
+}
+
+// ========================================
+// 入力の解釈
+// ========================================
+
+#[test]
+fn energy_and_rate_accept_bare_numbers_and_units() {
+  let bare = SessionInput::parse("2025-04-01T10:00:00Z", "2025-04-01T10:30:00Z", "12.345", "50").unwrap();
+  let with_units =
+    SessionInput::parse("2025-04-01T19:00:00+09:00", "2025-04-01T19:30:00+09:00", "12345 Wh", "50円/kWh").unwrap();
+
+  assert_eq!(bare, with_units);
+  let closed = bare.bill(SequentialIdGenerator::new().next_id(), PRICING.policy()).unwrap();
+  assert_eq!(closed.bill().total_energy(), KwhMilli::try_new(12_345).unwrap());
+}
+
+#[test]
+fn invalid_inputs_report_codes_and_messages() {
+  let timestamp = SessionInput::parse("2025-04-01 10:00", "2025-04-01T10:30:00Z", "12", "50").unwrap_err();
+  assert_eq!(timestamp, RowError::InvalidTimestamp { field: "start", value: "2025-04-01 10:00".to_owned() });
+  assert_eq!(timestamp.code(), "INVALID_TIMESTAMP");
+
+  let rate = SessionInput::parse("2025-04-01T10:00:00Z", "2025-04-01T10:30:00Z", "12", "0").unwrap_err();
+  assert_eq!(rate, RowError::Domain(SessionValueError::NonPositiveRate));
+  assert_eq!(rate.code(), "RATE_NOT_POSITIVE");
+  assert_eq!(rate.message(), "Rate must be at least 1 yen/kWh");
+
+  // kWh は Wh 単位（小数点以下 3 桁）までしか表せない
+  let energy = SessionInput::parse("2025-04-01T10:00:00Z", "2025-04-01T10:30:00Z", "12.3456", "50").unwrap_err();
+  assert_eq!(energy.code(), "VALUE_INVALID_FORMAT");
+}
+
+// ========================================
+// 一括請求
+// ========================================
+
+#[test]
+fn csv_rows_are_billed_with_per_row_errors() {
+  let input = "\
+id,start,end,energy,rate
+A,2025-04-01T10:00:00Z,2025-04-01T10:30:00Z,12,50
+B,2025-04-01T10:00:00Z,2025-04-01T09:30:00Z,12,50
+C,2025-04-01T10:00:00Z,,12,50
+D,2025-04-01T10:00:00Z
+";
+
+  let (failures, results) = batch(input, None, PRICING);
+
+  assert_eq!(failures, 3);
+  assert_eq!(results[0], json!({ "line": 2, "id": "A", "amount_yen": 500, "billable_wh": 10_000, "total_wh": 12_000 }));
+  assert_eq!(results[1]["id"], "B");
+  assert_eq!(results[1]["error"]["code"], "SESSION_INVALID_TIMELINE");
+  assert_eq!(results[2]["error"], json!({ "code": "MISSING_FIELD", "message": "the end field is missing" }));
+  assert_eq!(results[3]["line"], 5);
+  assert_eq!(results[3]["error"]["code"], "MALFORMED_ROW");
+}
+
+#[test]
+fn json_lines_accept_numbers_and_skip_blank_lines() {
+  let input = r#"{"start":"2025-04-01T10:00:00Z","end":"2025-04-01T11:00:00Z","energy":20.5,"rate":40}
+
+[1, 2]
+{"start":"2025-04-01T10:00:00Z","end":"2025-04-01T11:00:00Z","energy":true,"rate":40}
+"#;
+
+  let (failures, results) = batch(input, None, PricingArgs { grace_minutes: 0, ..PRICING });
+
+  assert_eq!(failures, 2);
+  assert_eq!(results.len(), 3);
+  assert_eq!(results[0], json!({ "line": 1, "amount_yen": 820, "billable_wh": 20_500, "total_wh": 20_500 }));
+  assert_eq!(results[1]["line"], 3);
+  assert_eq!(results[1]["error"]["code"], "MALFORMED_ROW");
+  assert_eq!(
+    results[2]["error"]["message"],
+    "the row could not be parsed: the energy field must be a string or a number"
+  );
+}
+
+#[test]
+fn explicit_format_overrides_detection() {
+  let (failures, results) = batch("{\"start\":\"x\"}\n", Some(InputFormat::Csv), PRICING);
+
+  assert_eq!(failures, 0);
+  assert!(results.is_empty());
+}
+
+// ========================================
+// 計算過程の説明
+// ========================================
+
+#[test]
+fn explanation_walks_through_grace_period_and_rounding() {
+  let closed = SessionInput::parse("2025-04-01T10:00:00Z", "2025-04-01T10:30:00Z", "12.345", "50")
+    .unwrap()
+    .bill(SequentialIdGenerator::new().next_id(), PRICING.policy())
+    .unwrap();
+
+  assert_eq!(closed.bill().amount_due(), MoneyYen::try_new(514).unwrap());
+  assert_eq!(explain(&closed), vec![
+    "session: 2025-04-01T10:00:00Z -> 2025-04-01T10:30:00Z (30m 0s)",
+    "grace period: the first 5m 0s are free; 25m 0s of 30m 0s is chargeable",
+    "billable energy: 12.345 kWh x 25m 0s / 30m 0s = 10.287 kWh (delivered uniformly, floored to 1 Wh)",
+    "charge: 10.287 kWh x ¥50/kWh = ¥514.350",
+    "amount due: ¥514.350 floored to whole yen = ¥514",
+  ]);
+}
+
+#[test]
+fn explanation_covers_sessions_within_the_grace_period() {
+  let input = "start,end,energy,rate\n2025-04-01T10:00:00Z,2025-04-01T10:03:30.250Z,0.8,50\n";
+
+  let (failures, results) = batch(input, None, PricingArgs { explain: true, ..PRICING });
+
+  assert_eq!(failures, 0);
+  assert_eq!(results[0]["amount_yen"], 0);
+  assert_eq!(
+    results[0]["explanation"][1],
+    "grace period: the session ends within the first 5m 0s free, so nothing is billable"
+  );
+  assert_eq!(results[0]["explanation"][0], "session: 2025-04-01T10:00:00Z -> 2025-04-01T10:03:30.25Z (3m 30.250s)");
+}
+
+#[test]
+fn explanation_shows_the_floored_subtotal_before_price_limits() {
+  let explain_with = |tariff: Tariff, energy: u64| {
+    let started_at = datetime!(2025-04-01 10:00 UTC);
+    let closed = ActiveSession::new(SequentialIdGenerator::new().next_id(), started_at, tariff, PRICING.policy())
+      .stop(started_at + Duration::minutes(30), KwhMilli::try_new(energy).unwrap())
+      .unwrap();
+    explain(&closed)
+  };
+  let rate = RateYenPerKwh::try_new(50).unwrap();
+
+  let minimum = explain_with(Tariff::flat(rate).with_minimum_fee(MoneyYen::try_new(300).unwrap()).unwrap(), 1_200);
+  assert_eq!(minimum[minimum.len() - 2..], [
+    "subtotal: ¥50.000 floored to whole yen = ¥50",
+    "amount due: ¥50 raised to the minimum fee = ¥300",
+  ]);
+
+  let capped = explain_with(Tariff::flat(rate).with_price_cap(MoneyYen::try_new(400).unwrap()).unwrap(), 12_000);
+  assert_eq!(capped[capped.len() - 2..], [
+    "subtotal: ¥500.000 floored to whole yen = ¥500",
+    "amount due: ¥500 capped at the price cap = ¥400",
+  ]);
+}
@@ -0,0 +1,10 @@
+mod bill_ledger;
+mod errors;
+mod verifier;
+
+pub use bill_ledger::{BillLedger, LedgerAnchor, LedgerEntry, LedgerHash};
+pub use errors::{LedgerError, LedgerViolation};
+pub use verifier::{verify_anchored, verify_chain};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,280 @@
+use std::{collections::HashMap, fmt};
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use time::format_description::well_known::Rfc3339;
+
+use super::{
+  errors::{LedgerError, LedgerViolation},
+  verifier::verify_chain,
+};
+use crate::session::{BillAmendment, ClosedSession, Session, SessionBill, SessionId, SessionValueError};
+
+/// SHA-256 ハッシュ値。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LedgerHash([u8; 32]);
+
+impl LedgerHash {
+  /// 先頭エントリの直前ハッシュとして使う、すべて 0 のハッシュ。
+  pub const GENESIS: Self = Self([0; 32]);
+
+  /// バイト列からハッシュ値を生成する。
+  #[must_use]
+  pub fn from_bytes(bytes: [u8; 32]) -> Self {
+    Self(bytes)
+  }
+
+  /// バイト列を返す。
+  #[must_use]
+  pub fn as_bytes(&self) -> &[u8; 32] {
+    &self.0
+  }
+
+  /// 直前のハッシュと正規化済みの内容からエントリのハッシュを計算する。
+  pub(crate) fn chain(previous: Self, canonical: &str) -> Self {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.0);
+    hasher.update(canonical.as_bytes());
+    Self(hasher.finalize().into())
+  }
+}
+
+/// 小文字 16 進で表示する。
+impl fmt::Display for LedgerHash {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+  }
+}
+
+impl fmt::Debug for LedgerHash {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "LedgerHash({self})")
+  }
+}
+
+/// 台帳の 1 エントリ（停止済みセッション 1 件分、または記録済みセッションへの請求訂正 1 件分）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+  sequence:      u64,
+  canonical:     String,
+  previous_hash: LedgerHash,
+  hash:          LedgerHash,
+}
+
+impl LedgerEntry {
+  /// 保存先から読み込んだ値でエントリを復元する。内容の検証は `verify_chain` で行う。
+  #[must_use]
+  pub fn from_parts(sequence: u64, canonical: String, previous_hash: LedgerHash, hash: LedgerHash) -> Self {
+    Self { sequence, canonical, previous_hash, hash }
+  }
+
+  /// 1 始まりの連番を返す。
+  #[must_use]
+  pub fn sequence(&self) -> u64 {
+    self.sequence
+  }
+
+  /// 記録内容を正規化した JSON（キー順固定・空白なし）を返す。
+  ///
+  /// `kind` が `"session"` なら停止済みセッションと請求、`"amendment"` なら請求訂正を表す。
+  #[must_use]
+  pub fn canonical(&self) -> &str {
+    &self.canonical
+  }
+
+  /// 直前のエントリのハッシュを返す。
+  #[must_use]
+  pub fn previous_hash(&self) -> LedgerHash {
+    self.previous_hash
+  }
+
+  /// このエントリのハッシュを返す。
+  #[must_use]
+  pub fn hash(&self) -> LedgerHash {
+    self.hash
+  }
+}
+
+/// 監査人に公開する台帳の件数と末尾ハッシュ。
+///
+/// ハッシュ連鎖だけでは末尾の削除を検出できないため、公開済みのアンカーと照合する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerAnchor {
+  /// 公開時点の件数。
+  pub length: u64,
+  /// 公開時点の末尾ハッシュ。
+  pub head:   LedgerHash,
+}
+
+/// 停止済みセッションの請求を追記のみで記録する、改ざん検出可能な台帳。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BillLedger {
+  entries:      Vec<LedgerEntry>,
+  /// セッションごとに最後に記録した請求（セッションの請求か、最新の訂正後の請求）。
+  latest_bills: HashMap<SessionId, Value>,
+}
+
+impl BillLedger {
+  /// 空の台帳を生成する。
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 保存先から読み込んだエントリで台帳を復元する。
+  ///
+  /// ハッシュ連鎖を検証したうえで、記録済みセッションの一覧を内容から組み立て直す。
+  /// 末尾の削除は検出できないため、必要に応じて `verify_anchored` を併用する。
+  ///
+  /// # Errors
+  /// - ハッシュ連鎖に改ざんの痕跡があれば `verify_chain` と同じ `LedgerViolation` を返します。
+  /// - 内容を台帳の記録として解釈できない場合（未知の種別、同じセッションの重複、
+  ///   未記録のセッションへの訂正、直前の請求と一致しない訂正など）、
+  ///   `LedgerViolation::MalformedEntry` を返します。
+  pub fn from_entries(entries: Vec<LedgerEntry>) -> Result<Self, LedgerViolation> {
+    verify_chain(&entries)?;
+    let mut latest_bills = HashMap::new();
+    for (position, entry) in entries.iter().enumerate() {
+      let malformed = || LedgerViolation::MalformedEntry { position };
+      let mut value: Value = serde_json::from_str(entry.canonical()).map_err(|_| malformed())?;
+      let session_id: SessionId =
+        value.get("session_id").and_then(Value::as_str).and_then(|id| id.parse().ok()).ok_or_else(malformed)?;
+      let recorded = match value.get("kind").and_then(Value::as_str) {
+        | Some(SESSION_KIND) => latest_bills.insert(session_id, value["bill"].take()).is_none(),
+        | Some(AMENDMENT_KIND) => match latest_bills.get_mut(&session_id) {
+          | Some(latest) if *latest == value["original"] => {
+            *latest = value["amended"].take();
+            true
+          },
+          | _ => false,
+        },
+        | _ => false,
+      };
+      if !recorded {
+        return Err(malformed());
+      }
+    }
+    Ok(Self { entries, latest_bills })
+  }
+
+  /// 停止済みセッションとその請求を台帳に追記する。
+  ///
+  /// # Errors
+  /// - セッションが停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - 同じセッションが記録済みの場合、`LedgerError::DuplicateSession` を返します。
+  ///   記録後の請求訂正は `append_amendment` で追記してください。
+  /// - 時刻が RFC 3339 で表現できない場合、`LedgerError::UnrepresentableTimestamp` を返します。
+  pub fn append(&mut self, session: &Session) -> Result<&LedgerEntry, LedgerError> {
+    let session_id = session.identity();
+    let closed = session.as_closed().ok_or(SessionValueError::NotClosed { session_id })?;
+    if self.latest_bills.contains_key(&session_id) {
+      return Err(LedgerError::DuplicateSession { session_id });
+    }
+
+    let canonical = canonicalize(self.next_sequence(), closed)?;
+    self.latest_bills.insert(session_id, bill_json(closed.bill()));
+    Ok(self.push(canonical))
+  }
+
+  /// 記録済みセッションへの請求訂正を台帳に追記する。
+  ///
+  /// セッションのエントリは書き換えず、訂正を別エントリとして連鎖に加える。
+  /// 訂正前の請求は、そのセッションについて最後に記録した請求と一致しなければならない。
+  ///
+  /// # Errors
+  /// - セッションが台帳に記録されていない場合、`LedgerError::UnknownSession` を返します。
+  /// - 訂正前の請求が最後に記録した請求と一致しない場合、`LedgerError::StaleAmendment` を返します。
+  pub fn append_amendment(
+    &mut self,
+    session_id: SessionId,
+    amendment: &BillAmendment,
+  ) -> Result<&LedgerEntry, LedgerError> {
+    let Some(latest) = self.latest_bills.get_mut(&session_id) else {
+      return Err(LedgerError::UnknownSession { session_id });
+    };
+    if *latest != bill_json(amendment.original()) {
+      return Err(LedgerError::StaleAmendment { session_id });
+    }
+    *latest = bill_json(amendment.amended());
+    let mut value = amendment_json(amendment);
+    value["kind"] = json!(AMENDMENT_KIND);
+    value["sequence"] = json!(self.next_sequence());
+    value["session_id"] = json!(session_id.to_string());
+    Ok(self.push(value.to_string()))
+  }
+
+  /// 記録順のエントリを返す。
+  #[must_use]
+  pub fn entries(&self) -> &[LedgerEntry] {
+    &self.entries
+  }
+
+  /// 末尾のハッシュを返す（空なら `LedgerHash::GENESIS`）。
+  #[must_use]
+  pub fn head(&self) -> LedgerHash {
+    self.entries.last().map_or(LedgerHash::GENESIS, LedgerEntry::hash)
+  }
+
+  /// 公開用のアンカーを返す。
+  #[must_use]
+  pub fn anchor(&self) -> LedgerAnchor {
+    LedgerAnchor { length: self.entries.len() as u64, head: self.head() }
+  }
+
+  fn next_sequence(&self) -> u64 {
+    self.entries.len() as u64 + 1
+  }
+
+  fn push(&mut self, canonical: String) -> &LedgerEntry {
+    let sequence = self.next_sequence();
+    let previous_hash = self.head();
+    let hash = LedgerHash::chain(previous_hash, &canonical);
+    self.entries.push(LedgerEntry { sequence, canonical, previous_hash, hash });
+    &self.entries[self.entries.len() - 1]
+  }
+}
+
+const SESSION_KIND: &str = "session";
+const AMENDMENT_KIND: &str = "amendment";
+
+/// 停止済みセッションを正規化した JSON にする。
+///
+/// `serde_json::Map` はキー順に並ぶため、同じ内容からは常に同じ文字列が得られる。
+fn canonicalize(sequence: u64, closed: &ClosedSession) -> Result<String, LedgerError> {
+  let unrepresentable = || LedgerError::UnrepresentableTimestamp { session_id: closed.identity() };
+  let amendments: Vec<Value> = closed.amendments().iter().map(amendment_json).collect();
+  let value = json!({
+    "kind": SESSION_KIND,
+    "sequence": sequence,
+    "session_id": closed.identity().to_string(),
+    "started_at": closed.started_at().format(&Rfc3339).map_err(|_| unrepresentable())?,
+    "ended_at": closed.ended_at().format(&Rfc3339).map_err(|_| unrepresentable())?,
+    "bill": bill_json(closed.bill()),
+    "amendments": amendments,
+  });
+  Ok(value.to_string())
+}
+
+fn amendment_json(amendment: &BillAmendment) -> Value {
+  json!({
+    "reason": amendment.reason().as_str(),
+    "original": bill_json(amendment.original()),
+    "amended": bill_json(amendment.amended()),
+  })
+}
+
+fn bill_json(bill: &SessionBill) -> Value {
+  let lines: Vec<Value> = bill
+    .lines()
+    .iter()
+    .map(|line| json!({ "energy_wh": u64::from(line.energy()), "rate_yen_per_kwh": u32::from(line.rate()) }))
+    .collect();
+  json!({
+    "total_energy_wh": u64::from(bill.total_energy()),
+    "billable_energy_wh": u64::from(bill.billable_energy()),
+    "amount_due_yen": u64::from(bill.amount_due()),
+    "minimum_fee_applied": bill.minimum_fee_applied(),
+    "price_cap_applied": bill.price_cap_applied(),
+    "lines": lines,
+  })
+}
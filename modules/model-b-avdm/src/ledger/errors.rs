@@ -0,0 +1,99 @@
+use thiserror::Error;
+
+use crate::session::{SessionId, SessionValueError};
+
+/// 台帳への追記で発生し得るエラー。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LedgerError {
+  /// 同じセッションが既に台帳に記録されていた。
+  #[error("セッション {session_id} は既に台帳に記録されています")]
+  DuplicateSession {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 訂正対象のセッションが台帳に記録されていなかった。
+  #[error("セッション {session_id} は台帳に記録されていません")]
+  UnknownSession {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 訂正前の請求が、台帳に最後に記録した請求と一致しなかった。
+  #[error("セッション {session_id} の訂正前の請求が台帳に最後に記録した請求と一致しません")]
+  StaleAmendment {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 時刻が RFC 3339 で表現できなかった。
+  #[error("セッション {session_id} の時刻を RFC 3339 で表現できません")]
+  UnrepresentableTimestamp {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// ドメインルールに違反した（停止していないセッションなど）。
+  #[error(transparent)]
+  Domain(#[from] SessionValueError),
+}
+
+/// 台帳の検証で見つかった改ざんの痕跡。
+///
+/// `position` は検証対象の並びにおける 0 始まりの位置を表す。
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum LedgerViolation {
+  /// エントリの内容が記録時のハッシュと一致しない（内容の改ざん）。
+  #[error("{position} 番目のエントリの内容がハッシュと一致しません")]
+  ModifiedEntry {
+    /// 位置。
+    position: usize,
+  },
+  /// 直前のエントリのハッシュと連結していない（並べ替え・削除・差し替え）。
+  #[error("{position} 番目のエントリが直前のエントリと連結していません")]
+  BrokenLink {
+    /// 位置。
+    position: usize,
+  },
+  /// 連番が位置と一致しない（並べ替え・削除）。
+  #[error("{position} 番目のエントリの連番が {found} です (期待値: {expected})")]
+  SequenceMismatch {
+    /// 位置。
+    position: usize,
+    /// 期待した連番。
+    expected: u64,
+    /// 記録されていた連番。
+    found:    u64,
+  },
+  /// 内容を台帳の記録として解釈できない（台帳の復元時のみ）。
+  #[error("{position} 番目のエントリの内容を台帳の記録として解釈できません")]
+  MalformedEntry {
+    /// 位置。
+    position: usize,
+  },
+  /// 公開済みのアンカーより短い（末尾の削除）。
+  #[error("台帳が {found} 件しかありません (アンカー: {expected} 件)")]
+  Truncated {
+    /// アンカーの件数。
+    expected: u64,
+    /// 実際の件数。
+    found:    u64,
+  },
+  /// 公開済みのアンカーと末尾のハッシュが一致しない。
+  #[error("{position} 番目のエントリのハッシュがアンカーと一致しません")]
+  AnchorMismatch {
+    /// アンカーが指す位置。
+    position: usize,
+  },
+}
+
+impl LedgerViolation {
+  /// 問題の見つかった位置を返す。末尾の削除では最初に欠けている位置を返す。
+  #[must_use]
+  pub fn position(&self) -> usize {
+    match self {
+      | Self::ModifiedEntry { position }
+      | Self::BrokenLink { position }
+      | Self::SequenceMismatch { position, .. }
+      | Self::MalformedEntry { position }
+      | Self::AnchorMismatch { position } => *position,
+      | Self::Truncated { found, .. } => usize::try_from(*found).unwrap_or(usize::MAX),
+    }
+  }
+}
@@ -0,0 +1,227 @@
+use time::{OffsetDateTime, macros::datetime};
+
+use super::{BillLedger, LedgerEntry, LedgerError, LedgerHash, LedgerViolation, verify_anchored, verify_chain};
+use crate::session::{
+  AmendmentReason, KwhMilli, RateYenPerKwh, Session, SessionValueError, test_support::closed_session,
+};
+
+fn closed_sessions(count: u64) -> Vec<Session> {
+  let started_at = datetime!(2025-04-01 10:00 +09:00);
+  (1..=count).map(|index| closed_session(u128::from(index), started_at, 30, 1_000 * index)).collect()
+}
+
+fn ledger_of(count: u64) -> BillLedger {
+  let mut ledger = BillLedger::new();
+  for session in closed_sessions(count) {
+    ledger.append(&session).unwrap();
+  }
+  ledger
+}
+
+// ========================================
+// 追記
+// ========================================
+
+#[test]
+fn appended_entries_are_chained_by_hash() {
+  let ledger = ledger_of(3);
+  let entries = ledger.entries();
+
+  assert_eq!(entries[0].previous_hash(), LedgerHash::GENESIS);
+  assert_eq!(entries[1].previous_hash(), entries[0].hash());
+  assert_eq!(ledger.head(), entries[2].hash());
+  assert_eq!(ledger.head().to_string().len(), 64);
+  assert!(entries[0].canonical().contains(r#""total_energy_wh":1000"#));
+  assert_eq!(verify_chain(entries), Ok(()));
+}
+
+#[test]
+fn canonical_form_is_deterministic() {
+  assert_eq!(ledger_of(2), ledger_of(2));
+}
+
+#[test]
+fn only_closed_sessions_are_recorded_once() {
+  let mut ledger = BillLedger::new();
+  let session = closed_sessions(1).remove(0);
+  let active = Session::new_active(session.identity(), OffsetDateTime::now_utc(), RateYenPerKwh::try_new(30).unwrap());
+
+  assert_eq!(
+    ledger.append(&active).unwrap_err(),
+    LedgerError::Domain(SessionValueError::NotClosed { session_id: session.identity() })
+  );
+  ledger.append(&session).unwrap();
+  assert_eq!(ledger.append(&session).unwrap_err(), LedgerError::DuplicateSession { session_id: session.identity() });
+}
+
+#[test]
+fn amendments_are_chained_after_the_recorded_session() {
+  let mut ledger = BillLedger::new();
+  let session = closed_sessions(1).remove(0);
+  ledger.append(&session).unwrap();
+  let amended = session
+    .clone()
+    .amend(KwhMilli::try_new(1_500).unwrap(), AmendmentReason::try_new("確定値の受信").unwrap())
+    .unwrap();
+  let amendment = &amended.amendments()[0];
+
+  let entry = ledger.append_amendment(session.identity(), amendment).unwrap();
+  assert_eq!(entry.sequence(), 2);
+  assert!(entry.canonical().contains(r#""kind":"amendment""#));
+  assert!(entry.canonical().contains(r#""reason":"確定値の受信""#));
+  assert_eq!(entry.previous_hash(), ledger.entries()[0].hash());
+  assert_eq!(verify_chain(ledger.entries()), Ok(()));
+
+  let unknown = closed_sessions(2).remove(1);
+  assert_eq!(ledger.append_amendment(unknown.identity(), amendment).unwrap_err(), LedgerError::UnknownSession {
+    session_id: unknown.identity(),
+  });
+}
+
+#[test]
+fn amendments_must_start_from_the_last_recorded_bill() {
+  let mut ledger = BillLedger::new();
+  let session = closed_sessions(1).remove(0);
+  ledger.append(&session).unwrap();
+  let reason = || AmendmentReason::try_new("確定値の受信").unwrap();
+  let first = session.clone().amend(KwhMilli::try_new(1_500).unwrap(), reason()).unwrap();
+  ledger.append_amendment(session.identity(), &first.amendments()[0]).unwrap();
+
+  // 同じ訂正の再追記や、訂正前の請求からの別の訂正は最新の請求と一致しない
+  let stale = LedgerError::StaleAmendment { session_id: session.identity() };
+  assert_eq!(ledger.append_amendment(session.identity(), &first.amendments()[0]).unwrap_err(), stale);
+  let forked = session.clone().amend(KwhMilli::try_new(2_000).unwrap(), reason()).unwrap();
+  assert_eq!(ledger.append_amendment(session.identity(), &forked.amendments()[0]).unwrap_err(), stale);
+  assert_eq!(ledger.entries().len(), 2);
+
+  let second = first.amend(KwhMilli::try_new(2_000).unwrap(), reason()).unwrap();
+  assert_eq!(ledger.append_amendment(session.identity(), &second.amendments()[1]).unwrap().sequence(), 3);
+  assert_eq!(BillLedger::from_entries(ledger.entries().to_vec()), Ok(ledger));
+}
+
+#[test]
+fn restored_ledger_keeps_rejecting_recorded_sessions() {
+  let sessions = closed_sessions(2);
+  let mut original = BillLedger::new();
+  original.append(&sessions[0]).unwrap();
+  let amended = sessions[0]
+    .clone()
+    .amend(KwhMilli::try_new(500).unwrap(), AmendmentReason::try_new("確定値の受信").unwrap())
+    .unwrap();
+  original.append_amendment(sessions[0].identity(), &amended.amendments()[0]).unwrap();
+
+  let mut restored = BillLedger::from_entries(original.entries().to_vec()).unwrap();
+  assert_eq!(restored, original);
+  assert_eq!(restored.append(&sessions[0]).unwrap_err(), LedgerError::DuplicateSession {
+    session_id: sessions[0].identity(),
+  });
+  assert_eq!(restored.append(&sessions[1]).unwrap().sequence(), 3);
+  assert_eq!(verify_chain(restored.entries()), Ok(()));
+}
+
+#[test]
+fn restoring_rejects_tampered_or_malformed_entries() {
+  let mut tampered = ledger_of(2).entries().to_vec();
+  tampered.swap(0, 1);
+  assert!(BillLedger::from_entries(tampered).is_err());
+
+  // 連鎖は正しくても、同じセッションを二度記録したエントリは受け付けない
+  let entry = ledger_of(1).entries()[0].clone();
+  let canonical = entry.canonical().replace(r#""sequence":1"#, r#""sequence":2"#);
+  let duplicate =
+    LedgerEntry::from_parts(2, canonical.clone(), entry.hash(), LedgerHash::chain(entry.hash(), &canonical));
+  assert_eq!(BillLedger::from_entries(vec![entry, duplicate]), Err(LedgerViolation::MalformedEntry { position: 1 }));
+
+  // 同じ訂正を繰り返したエントリは、訂正前の請求が直前の訂正後の請求と一致しない
+  let mut ledger = BillLedger::new();
+  let session = closed_sessions(1).remove(0);
+  ledger.append(&session).unwrap();
+  let amended =
+    session.amend(KwhMilli::try_new(1_500).unwrap(), AmendmentReason::try_new("確定値の受信").unwrap()).unwrap();
+  let amendment = ledger.append_amendment(amended.identity(), &amended.amendments()[0]).unwrap().clone();
+  let canonical = amendment.canonical().replace(r#""sequence":2"#, r#""sequence":3"#);
+  let repeated =
+    LedgerEntry::from_parts(3, canonical.clone(), amendment.hash(), LedgerHash::chain(amendment.hash(), &canonical));
+  let mut entries = ledger.entries().to_vec();
+  entries.push(repeated);
+  assert_eq!(BillLedger::from_entries(entries), Err(LedgerViolation::MalformedEntry { position: 2 }));
+}
+
+// ========================================
+// 改ざん検出
+// ========================================
+
+#[test]
+fn modified_entry_is_reported_at_its_position() {
+  let mut entries = ledger_of(3).entries().to_vec();
+  let target = &entries[1];
+  entries[1] = LedgerEntry::from_parts(
+    target.sequence(),
+    target.canonical().replace(r#""total_energy_wh":2000"#, r#""total_energy_wh":200"#),
+    target.previous_hash(),
+    target.hash(),
+  );
+
+  assert_eq!(verify_chain(&entries), Err(LedgerViolation::ModifiedEntry { position: 1 }));
+}
+
+#[test]
+fn rehashed_modification_breaks_the_next_link() {
+  let mut entries = ledger_of(3).entries().to_vec();
+  let target = &entries[1];
+  let canonical = target.canonical().replace("+09:00", "+00:00");
+  let hash = LedgerHash::chain(target.previous_hash(), &canonical);
+  entries[1] = LedgerEntry::from_parts(target.sequence(), canonical, target.previous_hash(), hash);
+
+  assert_eq!(verify_chain(&entries), Err(LedgerViolation::BrokenLink { position: 2 }));
+}
+
+#[test]
+fn reordered_and_deleted_entries_are_reported() {
+  let entries = ledger_of(4).entries().to_vec();
+
+  let mut reordered = entries.clone();
+  reordered.swap(1, 2);
+  assert_eq!(verify_chain(&reordered).unwrap_err().position(), 1);
+
+  let mut deleted = entries.clone();
+  deleted.remove(2);
+  assert_eq!(verify_chain(&deleted), Err(LedgerViolation::BrokenLink { position: 2 }));
+
+  let mut relabelled = entries;
+  relabelled[0] = LedgerEntry::from_parts(
+    7,
+    relabelled[0].canonical().to_owned(),
+    relabelled[0].previous_hash(),
+    relabelled[0].hash(),
+  );
+  assert_eq!(verify_chain(&relabelled), Err(LedgerViolation::SequenceMismatch { position: 0, expected: 1, found: 7 }));
+}
+
+#[test]
+fn truncated_tail_is_detected_against_the_anchor() {
+  let ledger = ledger_of(3);
+  let anchor = ledger.anchor();
+
+  let truncated = &ledger.entries()[..2];
+  assert_eq!(verify_chain(truncated), Ok(()));
+  assert_eq!(verify_anchored(truncated, &anchor), Err(LedgerViolation::Truncated { expected: 3, found: 2 }));
+  assert_eq!(verify_anchored(truncated, &anchor).unwrap_err().position(), 2);
+  assert_eq!(verify_anchored(ledger.entries(), &anchor), Ok(()));
+
+  // アンカー公開後の追記は許容する
+  assert_eq!(verify_anchored(ledger_of(4).entries(), &anchor), Ok(()));
+  assert_eq!(verify_anchored(&[], &anchor), Err(LedgerViolation::Truncated { expected: 3, found: 0 }));
+}
+
+#[test]
+fn replaced_history_is_detected_against_the_anchor() {
+  let anchor = ledger_of(2).anchor();
+  let mut replaced = BillLedger::new();
+  for session in closed_sessions(3).iter().skip(1) {
+    replaced.append(session).unwrap();
+  }
+
+  assert_eq!(verify_chain(replaced.entries()), Ok(()));
+  assert_eq!(verify_anchored(replaced.entries(), &anchor), Err(LedgerViolation::AnchorMismatch { position: 1 }));
+}
@@ -0,0 +1,60 @@
+use super::{
+  bill_ledger::{LedgerAnchor, LedgerEntry, LedgerHash},
+  errors::LedgerViolation,
+};
+
+/// ハッシュ連鎖を先頭から検証し、最初に見つかった改ざんの痕跡を返す。
+///
+/// 各エントリについて、内容とハッシュの一致、直前エントリとの連結、連番の順に確認する。
+/// 末尾の削除はこの検証では検出できないため、`verify_anchored` を併用する。
+///
+/// # Errors
+/// 改ざんの痕跡が見つかった場合、その位置を含む `LedgerViolation` を返します。
+pub fn verify_chain(entries: &[LedgerEntry]) -> Result<(), LedgerViolation> {
+  let mut previous = LedgerHash::GENESIS;
+  for (position, entry) in entries.iter().enumerate() {
+    if LedgerHash::chain(entry.previous_hash(), entry.canonical()) != entry.hash() {
+      return Err(LedgerViolation::ModifiedEntry { position });
+    }
+    if entry.previous_hash() != previous {
+      return Err(LedgerViolation::BrokenLink { position });
+    }
+    let expected = position as u64 + 1;
+    if entry.sequence() != expected || !declares_sequence(entry.canonical(), expected) {
+      return Err(LedgerViolation::SequenceMismatch { position, expected, found: entry.sequence() });
+    }
+    previous = entry.hash();
+  }
+  Ok(())
+}
+
+/// 公開済みのアンカーと照合したうえでハッシュ連鎖を検証する。
+///
+/// アンカー公開後に追記されたエントリは許容する。
+///
+/// # Errors
+/// - ハッシュ連鎖に改ざんの痕跡があれば `verify_chain` と同じ `LedgerViolation` を返します。
+/// - アンカーより件数が少ない場合、`LedgerViolation::Truncated` を返します。
+/// - アンカー位置のハッシュが一致しない場合、`LedgerViolation::AnchorMismatch` を返します。
+pub fn verify_anchored(entries: &[LedgerEntry], anchor: &LedgerAnchor) -> Result<(), LedgerViolation> {
+  verify_chain(entries)?;
+  let found = entries.len() as u64;
+  if found < anchor.length {
+    return Err(LedgerViolation::Truncated { expected: anchor.length, found });
+  }
+  let Some(position) = usize::try_from(anchor.length).ok().and_then(|length| length.checked_sub(1)) else {
+    return Ok(());
+  };
+  if entries[position].hash() != anchor.head {
+    return Err(LedgerViolation::AnchorMismatch { position });
+  }
+  Ok(())
+}
+
+/// 正規化済みの内容に記録された連番が期待値と一致するかを確認する。
+fn declares_sequence(canonical: &str, expected: u64) -> bool {
+  serde_json::from_str::<serde_json::Value>(canonical)
+    .ok()
+    .and_then(|value| value.get("sequence").and_then(serde_json::Value::as_u64))
+    == Some(expected)
+}
@@ -3,5 +3,17 @@
 #![deny(clippy::missing_errors_doc)]
 #![deny(clippy::missing_panics_doc)]
 #![deny(clippy::missing_safety_doc)]
+/// 停止済みセッションの請求を記録する改ざん検出可能な台帳をまとめたモジュール。
+pub mod ledger;
+/// 署名付き計量データ（OCMF）の解析と署名検証をまとめたモジュール。
+pub mod ocmf;
+/// OCPI 2.2 のローミング連携（CDR の出力と Tariff の取り込み）をまとめたモジュール。
+pub mod ocpi;
+/// OCPP 1.6J の中央システムをまとめたモジュール。
+pub mod ocpp16;
+/// OCPP 2.0.1 の TransactionEvent ログの取り込みをまとめたモジュール。
+pub mod ocpp201;
+/// セッションイベントのアウトボックスと Webhook 配送をまとめたモジュール。
+pub mod outbox;
 /// 充電セッションのドメイン型と振る舞いをまとめたモジュール。
 pub mod session;
@@ -1,3 +1,10 @@
-fn main() {
-  println!("model-b-avdm: run domain-specific experiments via library APIs");
+//! model-b の請求計算をコマンドラインから試すためのツール。
+mod cli;
+
+use std::process::ExitCode;
+
+use clap::Parser;
+
+fn main() -> ExitCode {
+  cli::run(cli::Cli::parse())
 }
@@ -0,0 +1,12 @@
+mod document;
+mod errors;
+mod verified;
+mod verifier;
+
+pub use document::OcmfDocument;
+pub use errors::OcmfError;
+pub use verified::{VerifiedMeterData, VerifiedReading};
+pub use verifier::OcmfVerifier;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,113 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Number;
+
+use super::errors::OcmfError;
+
+/// 署名対象の生のペイロードと、その解析結果を保持する OCMF データ。
+///
+/// 署名はペイロードセクションの文字列そのものに対して計算されるため、再シリアライズせず保持する。
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcmfDocument {
+  raw_payload: String,
+  payload:     Payload,
+  signature:   Signature,
+}
+
+/// OCMF のペイロードセクション（課金に使う項目のみ）。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct Payload {
+  /// 計器のシリアル番号。
+  #[serde(rename = "GS", default)]
+  pub(crate) meter_serial: String,
+  /// 読み取り値の一覧。
+  #[serde(rename = "RD", default)]
+  pub(crate) readings:     Vec<Reading>,
+}
+
+/// OCMF の読み取り値 1 件。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct Reading {
+  /// 時刻と同期状態（例: `2018-07-24T13:22:04,000+0200 S`）。
+  #[serde(rename = "TM")]
+  pub(crate) time:        String,
+  /// 取引種別（`B` 開始 / `T` 途中 / `E` 終了など）。
+  #[serde(rename = "TX", default)]
+  pub(crate) transaction: Option<String>,
+  /// 読み取り値。
+  #[serde(rename = "RV")]
+  pub(crate) value:       Number,
+  /// 計量レジスタの識別子（OBIS コード）。
+  #[serde(rename = "RI", default)]
+  pub(crate) register:    Option<String>,
+  /// 単位。
+  #[serde(rename = "RU")]
+  pub(crate) unit:        String,
+  /// 計器の状態。
+  #[serde(rename = "ST", default)]
+  pub(crate) status:      Option<String>,
+}
+
+/// OCMF の署名セクション。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Signature {
+  /// 署名方式。省略時は `ECDSA-secp256r1-SHA256`。
+  #[serde(rename = "SA", default)]
+  pub(crate) algorithm: Option<String>,
+  /// 署名データのエンコーディング。省略時は `hex`。
+  #[serde(rename = "SE", default)]
+  pub(crate) encoding:  Option<String>,
+  /// 署名データの形式。省略時は `application/x-der`。
+  #[serde(rename = "SM", default)]
+  pub(crate) mime:      Option<String>,
+  /// 署名データ。
+  #[serde(rename = "SD")]
+  pub(crate) data:      String,
+}
+
+impl OcmfDocument {
+  /// 署名対象となるペイロードセクションの文字列を返す。
+  #[must_use]
+  pub fn raw_payload(&self) -> &str {
+    &self.raw_payload
+  }
+
+  /// 計器のシリアル番号を返す。
+  #[must_use]
+  pub fn meter_serial(&self) -> &str {
+    &self.payload.meter_serial
+  }
+
+  pub(crate) fn payload(&self) -> &Payload {
+    &self.payload
+  }
+
+  pub(crate) fn signature(&self) -> &Signature {
+    &self.signature
+  }
+}
+
+/// `OCMF|<payload>|<signature>` 形式の文字列を解析する。署名の検証は `OcmfVerifier` で行う。
+impl FromStr for OcmfDocument {
+  type Err = OcmfError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let body = s.trim().strip_prefix("OCMF|").ok_or(OcmfError::MalformedEnvelope)?;
+    let Some((raw_payload, raw_signature)) = body.rsplit_once('|') else {
+      return Err(if body.trim_start().starts_with('{') { OcmfError::Unsigned } else { OcmfError::MalformedEnvelope });
+    };
+    if raw_signature.trim().is_empty() {
+      return Err(OcmfError::Unsigned);
+    }
+    Ok(Self {
+      raw_payload: raw_payload.to_owned(),
+      payload:     section("payload", raw_payload)?,
+      signature:   section("signature", raw_signature)?,
+    })
+  }
+}
+
+fn section<T: DeserializeOwned>(name: &'static str, raw: &str) -> Result<T, OcmfError> {
+  serde_json::from_str(raw).map_err(|error| OcmfError::MalformedSection { section: name, reason: error.to_string() })
+}
@@ -0,0 +1,114 @@
+use thiserror::Error;
+use time::OffsetDateTime;
+
+use crate::session::{SessionId, SessionValueError};
+
+/// OCMF データの解析・検証で発生し得るエラー。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OcmfError {
+  /// `OCMF|<payload>|<signature>` の形式ではなかった。
+  #[error("OCMF の形式ではありません (OCMF|<payload>|<signature> を期待)")]
+  MalformedEnvelope,
+  /// 署名セクションがなかった。
+  #[error("OCMF データに署名がありません")]
+  Unsigned,
+  /// ペイロードまたは署名セクションの JSON が不正だった。
+  #[error("OCMF の {section} セクションを解析できません: {reason}")]
+  MalformedSection {
+    /// `payload` または `signature`。
+    section: &'static str,
+    /// 解析エラーの内容。
+    reason:  String,
+  },
+  /// 対応していない署名方式だった。
+  #[error("署名方式 {algorithm} には対応していません (ECDSA-secp256r1-SHA256 のみ)")]
+  UnsupportedAlgorithm {
+    /// `SA` の値。
+    algorithm: String,
+  },
+  /// 対応していない署名のエンコーディングだった。
+  #[error("署名のエンコーディング {encoding} には対応していません (hex / application/x-der のみ)")]
+  UnsupportedEncoding {
+    /// `SE` または `SM` の値。
+    encoding: String,
+  },
+  /// 公開鍵が secp256r1 の鍵として解釈できなかった。
+  #[error("計器の公開鍵を解釈できません")]
+  InvalidPublicKey,
+  /// 署名が公開鍵とペイロードに一致しなかった。
+  #[error("OCMF の署名が一致しません")]
+  InvalidSignature,
+  /// 開始（`B`）または終了（`E`）の読み取り値がなかった。
+  #[error("取引種別 {transaction} の読み取り値がありません")]
+  MissingReading {
+    /// `TX` の値。
+    transaction: &'static str,
+  },
+  /// 読み取り値の状態が正常（`G`）ではなかった。
+  #[error("{index} 番目の読み取り値の状態が {status} です (G のみ課金可能)")]
+  UnverifiedReading {
+    /// 読み取り値の位置。
+    index:  usize,
+    /// `ST` の値。
+    status: String,
+  },
+  /// 読み取り値の状態（`ST`）がなかった。
+  #[error("{index} 番目の読み取り値に状態がありません (G のみ課金可能)")]
+  MissingStatus {
+    /// 読み取り値の位置。
+    index: usize,
+  },
+  /// 読み取り時刻が同期済み（`S`）ではなかった。
+  #[error("{index} 番目の読み取り時刻が同期されていません ({flag})")]
+  UnsynchronizedClock {
+    /// 読み取り値の位置。
+    index: usize,
+    /// 時刻の状態フラグ。
+    flag:  String,
+  },
+  /// 読み取り時刻を解釈できなかった。
+  #[error("{index} 番目の読み取り時刻 {value:?} を解釈できません")]
+  InvalidTimestamp {
+    /// 読み取り値の位置。
+    index: usize,
+    /// `TM` の値。
+    value: String,
+  },
+  /// 読み取り値が非負の数値ではなかった。
+  #[error("{index} 番目の読み取り値 {value} を解釈できません")]
+  InvalidValue {
+    /// 読み取り値の位置。
+    index: usize,
+    /// `RV` の値。
+    value: String,
+  },
+  /// 対応していない単位だった。
+  #[error("{index} 番目の読み取り値の単位 {unit} には対応していません (kWh / Wh のみ)")]
+  UnsupportedUnit {
+    /// 読み取り値の位置。
+    index: usize,
+    /// `RU` の値。
+    unit:  String,
+  },
+  /// 開始と終了で異なる計量レジスタが使われていた。
+  #[error("開始と終了の読み取り値の識別子が異なります ({begin} / {end})")]
+  MixedRegisters {
+    /// 開始の `RI`。
+    begin: String,
+    /// 終了の `RI`。
+    end:   String,
+  },
+  /// 開始の読み取り時刻が停止対象セッションの開始時刻より前だった。
+  #[error("セッション {session_id} の開始 ({started_at}) より前の読み取り値 ({begin_at}) は使えません")]
+  ReadingBeforeSessionStart {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// セッション開始時刻。
+    started_at: OffsetDateTime,
+    /// 開始の読み取り時刻。
+    begin_at:   OffsetDateTime,
+  },
+  /// 終了時刻が開始時刻より前だった、または読み取り値が減少していた。
+  #[error(transparent)]
+  Domain(#[from] SessionValueError),
+}
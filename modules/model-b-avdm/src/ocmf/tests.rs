@@ -0,0 +1,161 @@
+use p256::{
+  ecdsa::{Signature, SigningKey, signature::Signer},
+  pkcs8::EncodePublicKey,
+};
+use time::macros::datetime;
+
+use super::{OcmfDocument, OcmfError, OcmfVerifier};
+use crate::session::{
+  IdGenerator, KwhMilli, MeterReading, RateYenPerKwh, SequentialIdGenerator, Session, SessionValueError,
+};
+
+fn signing_key() -> SigningKey {
+  SigningKey::from_slice(&[7; 32]).unwrap()
+}
+
+fn verifier() -> OcmfVerifier {
+  let der = signing_key().verifying_key().to_public_key_der().unwrap();
+  OcmfVerifier::from_public_key_hex(&hex::encode(der.as_bytes())).unwrap()
+}
+
+fn payload(begin: (&str, &str), end: (&str, &str)) -> String {
+  format!(
+    r#"{{"FV":"1.0","GI":"ACME Meter","GS":"0901454D4800007F9F3E","PG":"T12","RD":[{{"TM":"{}","TX":"B","RV":{},"RI":"1-b:1.8.0","RU":"kWh","ST":"G"}},{{"TM":"{}","TX":"E","RV":{},"RI":"1-b:1.8.0","RU":"kWh","ST":"G"}}]}}"#,
+    begin.0, begin.1, end.0, end.1
+  )
+}
+
+fn valid_payload() -> String {
+  payload(("2025-04-01T10:00:00,000+0200 S", "2935.6"), ("2025-04-01T10:45:30,500+0200 S", "2947.8125"))
+}
+
+fn sign(payload: &str) -> String {
+  let signature: Signature = signing_key().sign(payload.as_bytes());
+  format!(
+    r#"OCMF|{payload}|{{"SA":"ECDSA-secp256r1-SHA256","SE":"hex","SD":"{}"}}"#,
+    hex::encode(signature.to_der().as_bytes())
+  )
+}
+
+// ========================================
+// 署名検証
+// ========================================
+
+#[test]
+fn verified_readings_yield_energy_and_timestamps() {
+  let verified = verifier().verify_str(&sign(&valid_payload())).unwrap();
+
+  assert_eq!(verified.meter_serial(), "0901454D4800007F9F3E");
+  assert_eq!(verified.begin().at(), datetime!(2025-04-01 10:00 +02:00));
+  assert_eq!(verified.end().at(), datetime!(2025-04-01 10:45:30.5 +02:00));
+  assert_eq!(verified.begin().register(), MeterReading::new(2_935_600));
+  // 2947.8125 kWh は 1 Wh 未満を切り捨てる
+  assert_eq!(verified.energy(), KwhMilli::try_new(12_212).unwrap());
+
+  let session = Session::new_active(
+    SequentialIdGenerator::new().next_id(),
+    verified.begin().at(),
+    RateYenPerKwh::try_new(40).unwrap(),
+  );
+  let closed = verified.stop_session(session).unwrap();
+  assert_eq!(closed.as_closed().unwrap().ended_at(), verified.end().at());
+  assert_eq!(closed.statement().unwrap().total_energy(), verified.energy());
+}
+
+#[test]
+fn readings_before_the_session_start_are_not_billed() {
+  let verified = verifier().verify_str(&sign(&valid_payload())).unwrap();
+  let session_id = SequentialIdGenerator::new().next_id();
+  let started_at = datetime!(2025-04-01 10:05 +02:00);
+  let session = Session::new_active(session_id, started_at, RateYenPerKwh::try_new(40).unwrap());
+
+  assert_eq!(
+    verified.stop_session(session),
+    Err(OcmfError::ReadingBeforeSessionStart { session_id, started_at, begin_at: verified.begin().at() })
+  );
+}
+
+#[test]
+fn sec1_public_keys_are_accepted() {
+  let sec1 = signing_key().verifying_key().to_encoded_point(false);
+  let verifier = OcmfVerifier::from_public_key_hex(&hex::encode(sec1.as_bytes())).unwrap();
+
+  assert!(verifier.verify_str(&sign(&valid_payload())).is_ok());
+  assert_eq!(OcmfVerifier::from_public_key_hex("00ff"), Err(OcmfError::InvalidPublicKey));
+}
+
+#[test]
+fn tampered_payload_fails_verification() {
+  let signed = sign(&valid_payload());
+  let tampered = signed.replace("2947.8125", "2999.8125");
+
+  assert_eq!(verifier().verify_str(&tampered), Err(OcmfError::InvalidSignature));
+}
+
+#[test]
+fn foreign_key_fails_verification() {
+  let other = SigningKey::from_slice(&[9; 32]).unwrap();
+  let verifier = OcmfVerifier::new(*other.verifying_key());
+
+  assert_eq!(verifier.verify_str(&sign(&valid_payload())), Err(OcmfError::InvalidSignature));
+}
+
+#[test]
+fn unsigned_or_malformed_data_is_rejected() {
+  let payload = valid_payload();
+
+  assert_eq!(format!("OCMF|{payload}").parse::<OcmfDocument>(), Err(OcmfError::Unsigned));
+  assert_eq!(format!("OCMF|{payload}|").parse::<OcmfDocument>(), Err(OcmfError::Unsigned));
+  assert_eq!(payload.parse::<OcmfDocument>(), Err(OcmfError::MalformedEnvelope));
+  assert!(matches!(
+    "OCMF|{not json}|{}".parse::<OcmfDocument>(),
+    Err(OcmfError::MalformedSection { section: "payload", .. })
+  ));
+  let rsa = sign(&payload).replace("ECDSA-secp256r1-SHA256", "RSA-2048-SHA256");
+  assert_eq!(
+    verifier().verify_str(&rsa),
+    Err(OcmfError::UnsupportedAlgorithm { algorithm: "RSA-2048-SHA256".to_owned() })
+  );
+}
+
+// ========================================
+// 読み取り値の検証
+// ========================================
+
+#[test]
+fn readings_must_be_good_and_synchronized() {
+  let error_status = sign(&valid_payload().replacen(r#""ST":"G""#, r#""ST":"E""#, 1));
+  assert_eq!(
+    verifier().verify_str(&error_status),
+    Err(OcmfError::UnverifiedReading { index: 0, status: "E".to_owned() })
+  );
+
+  let missing_status = sign(&valid_payload().replacen(r#","ST":"G""#, "", 1));
+  assert_eq!(verifier().verify_str(&missing_status), Err(OcmfError::MissingStatus { index: 0 }));
+
+  let unsynchronized = sign(&valid_payload().replace("+0200 S", "+0200 U"));
+  assert_eq!(
+    verifier().verify_str(&unsynchronized),
+    Err(OcmfError::UnsynchronizedClock { index: 0, flag: "U".to_owned() })
+  );
+
+  let missing_end = sign(&valid_payload().replace(r#""TX":"E""#, r#""TX":"T""#));
+  assert_eq!(verifier().verify_str(&missing_end), Err(OcmfError::MissingReading { transaction: "E" }));
+}
+
+#[test]
+fn decreasing_or_reversed_readings_are_rejected() {
+  let decreasing =
+    sign(&payload(("2025-04-01T10:00:00,000+0200 S", "2935.6"), ("2025-04-01T10:45:30,500+0200 S", "2935.5")));
+  assert_eq!(
+    verifier().verify_str(&decreasing),
+    Err(OcmfError::Domain(SessionValueError::MeterRegisterDecreased { start: 2_935_600, stop: 2_935_500 }))
+  );
+
+  let reversed =
+    sign(&payload(("2025-04-01T11:00:00,000+0200 S", "2935.6"), ("2025-04-01T10:45:30,500+0200 S", "2947.8")));
+  assert!(matches!(
+    verifier().verify_str(&reversed),
+    Err(OcmfError::Domain(SessionValueError::InvalidTimeline { .. }))
+  ));
+}
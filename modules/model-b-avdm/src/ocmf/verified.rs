@@ -0,0 +1,139 @@
+use time::{OffsetDateTime, macros::format_description};
+
+use super::{
+  document::{Payload, Reading},
+  errors::OcmfError,
+};
+use crate::session::{KwhMilli, MeterReading, MeterRegister, Session, SessionValueError};
+
+/// 署名検証済みの読み取り値 1 件。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedReading {
+  at:       OffsetDateTime,
+  register: MeterReading,
+}
+
+impl VerifiedReading {
+  /// 読み取り時刻を返す。
+  #[must_use]
+  pub fn at(&self) -> OffsetDateTime {
+    self.at
+  }
+
+  /// 計器の指示値（Wh、1 Wh 未満は切り捨て）を返す。
+  #[must_use]
+  pub fn register(&self) -> MeterReading {
+    self.register
+  }
+}
+
+/// 署名検証済みの OCMF データから取り出した、1 セッション分の課金に使う値。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedMeterData {
+  meter_serial: String,
+  begin:        VerifiedReading,
+  end:          VerifiedReading,
+  energy:       KwhMilli,
+}
+
+impl VerifiedMeterData {
+  /// ペイロードの開始（`B`）・終了（`E`）の読み取り値を検証して取り出す。
+  pub(crate) fn from_payload(payload: &Payload) -> Result<Self, OcmfError> {
+    let find = |transaction: &'static str| {
+      payload
+        .readings
+        .iter()
+        .enumerate()
+        .rfind(|(_, reading)| reading.transaction.as_deref() == Some(transaction))
+        .ok_or(OcmfError::MissingReading { transaction })
+    };
+    let (begin_index, begin) = find("B")?;
+    let (end_index, end) = find("E")?;
+    if begin.register != end.register {
+      return Err(OcmfError::MixedRegisters {
+        begin: begin.register.clone().unwrap_or_default(),
+        end:   end.register.clone().unwrap_or_default(),
+      });
+    }
+
+    let begin = verified_reading(begin_index, begin)?;
+    let end = verified_reading(end_index, end)?;
+    if end.at <= begin.at {
+      return Err(SessionValueError::InvalidTimeline { started_at: begin.at, ended_at: end.at }.into());
+    }
+    let energy = MeterRegister::monotonic().energy_between(begin.register, end.register)?;
+    Ok(Self { meter_serial: payload.meter_serial.clone(), begin, end, energy })
+  }
+
+  /// 計器のシリアル番号を返す。
+  #[must_use]
+  pub fn meter_serial(&self) -> &str {
+    &self.meter_serial
+  }
+
+  /// 開始時の読み取り値を返す。
+  #[must_use]
+  pub fn begin(&self) -> VerifiedReading {
+    self.begin
+  }
+
+  /// 終了時の読み取り値を返す。
+  #[must_use]
+  pub fn end(&self) -> VerifiedReading {
+    self.end
+  }
+
+  /// 開始から終了までのエネルギー量を返す。
+  #[must_use]
+  pub fn energy(&self) -> KwhMilli {
+    self.energy
+  }
+
+  /// 検証済みの終了時刻とエネルギー量でセッションを停止する。
+  ///
+  /// 開始の読み取り値がセッション開始より前なら、別の取引の計量が混ざっているとみなして停止しない。
+  ///
+  /// # Errors
+  /// - 開始の読み取り時刻がセッション開始時刻より前の場合、`OcmfError::ReadingBeforeSessionStart`
+  ///   を返します。
+  /// - 停止処理が失敗した場合、その `SessionValueError` を `OcmfError::Domain` として返します。
+  pub fn stop_session(&self, session: Session) -> Result<Session, OcmfError> {
+    if let Session::Active(active) = &session
+      && self.begin.at < active.started_at()
+    {
+      return Err(OcmfError::ReadingBeforeSessionStart {
+        session_id: active.identity(),
+        started_at: active.started_at(),
+        begin_at:   self.begin.at,
+      });
+    }
+    Ok(session.stop(self.end.at, self.energy)?)
+  }
+}
+
+fn verified_reading(index: usize, reading: &Reading) -> Result<VerifiedReading, OcmfError> {
+  match reading.status.as_deref() {
+    | Some("G") => {},
+    | Some(status) => return Err(OcmfError::UnverifiedReading { index, status: status.to_owned() }),
+    | None => return Err(OcmfError::MissingStatus { index }),
+  }
+  let invalid_time = || OcmfError::InvalidTimestamp { index, value: reading.time.clone() };
+  let (time, flag) = reading.time.rsplit_once(' ').ok_or_else(invalid_time)?;
+  if flag != "S" {
+    return Err(OcmfError::UnsynchronizedClock { index, flag: flag.to_owned() });
+  }
+  let at = OffsetDateTime::parse(
+    time,
+    format_description!(
+      "[year]-[month]-[day]T[hour]:[minute]:[second],[subsecond digits:3][offset_hour sign:mandatory][offset_minute]"
+    ),
+  )
+  .map_err(|_| invalid_time())?;
+
+  if !matches!(reading.unit.as_str(), "kWh" | "Wh") {
+    return Err(OcmfError::UnsupportedUnit { index, unit: reading.unit.clone() });
+  }
+  let value = reading.value.to_string();
+  let register = MeterReading::parse_in(&value, &reading.unit).map_err(|_| OcmfError::InvalidValue { index, value })?;
+  Ok(VerifiedReading { at, register })
+}
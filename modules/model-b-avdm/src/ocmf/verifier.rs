@@ -0,0 +1,73 @@
+use p256::{
+  ecdsa::{Signature, VerifyingKey, signature::Verifier},
+  pkcs8::DecodePublicKey,
+};
+
+use super::{document::OcmfDocument, errors::OcmfError, verified::VerifiedMeterData};
+
+const SUPPORTED_ALGORITHM: &str = "ECDSA-secp256r1-SHA256";
+
+/// 計器の公開鍵で OCMF データの署名を検証する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcmfVerifier {
+  key: VerifyingKey,
+}
+
+impl OcmfVerifier {
+  /// 16 進表記の公開鍵（DER の SubjectPublicKeyInfo または SEC1 形式）から生成する。
+  ///
+  /// # Errors
+  /// secp256r1 の公開鍵として解釈できない場合、`OcmfError::InvalidPublicKey` を返します。
+  pub fn from_public_key_hex(public_key: &str) -> Result<Self, OcmfError> {
+    let bytes = hex::decode(public_key.trim()).map_err(|_| OcmfError::InvalidPublicKey)?;
+    VerifyingKey::from_public_key_der(&bytes)
+      .or_else(|_| VerifyingKey::from_sec1_bytes(&bytes))
+      .map(|key| Self { key })
+      .map_err(|_| OcmfError::InvalidPublicKey)
+  }
+
+  /// 公開鍵から生成する。
+  #[must_use]
+  pub fn new(key: VerifyingKey) -> Self {
+    Self { key }
+  }
+
+  /// OCMF 文字列を解析し、署名を検証したうえで課金に使う読み取り値を取り出す。
+  ///
+  /// # Errors
+  /// - 形式が不正な場合、`OcmfError::MalformedEnvelope` / `OcmfError::MalformedSection`
+  ///   を返します。
+  /// - 署名がない場合、`OcmfError::Unsigned` を返します。
+  /// - 署名が一致しない場合、`OcmfError::InvalidSignature` を返します。
+  /// - 読み取り値が課金に使えない場合、その理由を表す `OcmfError` を返します。
+  pub fn verify_str(&self, ocmf: &str) -> Result<VerifiedMeterData, OcmfError> {
+    self.verify(&ocmf.parse()?)
+  }
+
+  /// 解析済みの OCMF データの署名を検証し、課金に使う読み取り値を取り出す。
+  ///
+  /// 署名の検証に成功するまで、ペイロードの内容は一切使わない。
+  ///
+  /// # Errors
+  /// - 署名方式やエンコーディングが対応外の場合、`OcmfError::UnsupportedAlgorithm` /
+  ///   `OcmfError::UnsupportedEncoding` を返します。
+  /// - 署名が一致しない場合、`OcmfError::InvalidSignature` を返します。
+  /// - 読み取り値が課金に使えない場合、その理由を表す `OcmfError` を返します。
+  pub fn verify(&self, document: &OcmfDocument) -> Result<VerifiedMeterData, OcmfError> {
+    let signature = document.signature();
+    if let Some(algorithm) = signature.algorithm.as_deref().filter(|algorithm| *algorithm != SUPPORTED_ALGORITHM) {
+      return Err(OcmfError::UnsupportedAlgorithm { algorithm: algorithm.to_owned() });
+    }
+    for encoding in [signature.encoding.as_deref(), signature.mime.as_deref()].into_iter().flatten() {
+      if !matches!(encoding, "hex" | "application/x-der") {
+        return Err(OcmfError::UnsupportedEncoding { encoding: encoding.to_owned() });
+      }
+    }
+
+    let der = hex::decode(signature.data.trim()).map_err(|_| OcmfError::InvalidSignature)?;
+    let parsed = Signature::from_der(&der).map_err(|_| OcmfError::InvalidSignature)?;
+    self.key.verify(document.raw_payload().as_bytes(), &parsed).map_err(|_| OcmfError::InvalidSignature)?;
+
+    VerifiedMeterData::from_payload(document.payload())
+  }
+}
@@ -0,0 +1,13 @@
+mod cdr;
+mod errors;
+mod imported_tariff;
+mod party;
+mod tariff;
+
+pub use cdr::{AuthMethod, CdrExporter, CdrLocation, CdrToken, GeoLocation, TokenType};
+pub use errors::OcpiError;
+pub use imported_tariff::ImportedTariff;
+pub use party::OcpiParty;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,265 @@
+use serde::Serialize;
+use serde_json::Value;
+use time::{Duration, OffsetDateTime, UtcOffset, format_description::well_known::Rfc3339};
+
+use super::{
+  errors::OcpiError,
+  party::OcpiParty,
+  tariff::{CURRENCY, OcpiTariff, Price, kwh, session_tariff},
+};
+use crate::session::{GraceRule, KwhMilli, Session, SessionBill, SessionValueError};
+
+/// 1 時間のミリ秒数。
+const MILLISECONDS_IN_HOUR: f64 = 3_600_000.0;
+
+/// 充電を認可した方法（OCPI の AuthMethod）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuthMethod {
+  /// eMSP への問い合わせで認可した。
+  AuthRequest,
+  /// eMSP からのコマンドで開始した。
+  Command,
+  /// 事前に受け取ったホワイトリストで認可した。
+  Whitelist,
+}
+
+/// 認可に使われたトークンの種別（OCPI の TokenType）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenType {
+  /// その場限りの利用者。
+  AdHocUser,
+  /// アプリの利用者。
+  AppUser,
+  /// その他。
+  Other,
+  /// RFID カード。
+  Rfid,
+}
+
+/// 充電を認可したトークン。CDR の `cdr_token` と `auth_method` になる。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdrToken {
+  /// トークンを発行した eMSP。
+  pub issuer:      OcpiParty,
+  /// トークンの識別子（RFID の UID など）。
+  pub uid:         String,
+  /// トークンの種別。
+  pub token_type:  TokenType,
+  /// 契約ID（eMAID）。
+  pub contract_id: String,
+  /// 認可の方法。
+  pub auth_method: AuthMethod,
+}
+
+/// 緯度・経度（OCPI の GeoLocation。10 進表記の文字列）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GeoLocation {
+  /// 緯度。
+  pub latitude:  String,
+  /// 経度。
+  pub longitude: String,
+}
+
+/// 充電した場所とコネクタ（OCPI の CdrLocation）。
+///
+/// `connector_standard` などの列挙値は OCPI の表記（`CHADEMO`、`IEC_62196_T2_COMBO`
+/// など）で指定する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CdrLocation {
+  /// ロケーションID。
+  pub id:                   String,
+  /// ロケーション名。
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name:                 Option<String>,
+  /// 住所。
+  pub address:              String,
+  /// 市区町村。
+  pub city:                 String,
+  /// 郵便番号。
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub postal_code:          Option<String>,
+  /// ISO 3166-1 alpha-3 の国コード。
+  pub country:              String,
+  /// 座標。
+  pub coordinates:          GeoLocation,
+  /// EVSE の UID。
+  pub evse_uid:             String,
+  /// EVSE ID。
+  pub evse_id:              String,
+  /// コネクタID。
+  pub connector_id:         String,
+  /// コネクタの規格。
+  pub connector_standard:   String,
+  /// コネクタの形状（`CABLE` または `SOCKET`）。
+  pub connector_format:     String,
+  /// 電源の種類（`DC`、`AC_3_PHASE` など）。
+  pub connector_power_type: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CdrDocument<'a> {
+  country_code:     &'a str,
+  party_id:         &'a str,
+  id:               String,
+  start_date_time:  String,
+  end_date_time:    String,
+  session_id:       String,
+  cdr_token:        CdrTokenDocument<'a>,
+  auth_method:      AuthMethod,
+  cdr_location:     &'a CdrLocation,
+  currency:         &'static str,
+  tariffs:          Vec<OcpiTariff>,
+  charging_periods: Vec<ChargingPeriod>,
+  total_cost:       Price,
+  total_energy:     f64,
+  total_time:       f64,
+  last_updated:     String,
+}
+
+#[derive(Debug, Serialize)]
+struct CdrTokenDocument<'a> {
+  country_code: &'a str,
+  party_id:     &'a str,
+  uid:          &'a str,
+  #[serde(rename = "type")]
+  token_type:   TokenType,
+  contract_id:  &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChargingPeriod {
+  start_date_time: String,
+  dimensions:      Vec<CdrDimension>,
+  tariff_id:       String,
+}
+
+#[derive(Debug, Serialize)]
+struct CdrDimension {
+  #[serde(rename = "type")]
+  dimension: CdrDimensionType,
+  volume:    f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum CdrDimensionType {
+  Energy,
+  Time,
+}
+
+/// 停止済みセッションとその請求を OCPI 2.2 の CDR（Charge Detail Record）に変換する。
+///
+/// - `tariffs` にはセッションに適用した料金表を 1 件含め、各充電期間の `tariff_id` から参照する。
+///   無料時間と段階料金を併用する場合だけ、料金表のIDにセッションIDを付けてセッション専用にする。
+/// - 無料ルールで無料になった部分は、料金表の 0 円の要素が適用される最初の充電期間として表す。
+///   時間の無料ルールでは無料時間、
+///   無料エネルギーでは総エネルギーに占める割合で按分した時間を長さとする。
+/// - `total_cost` は最低料金・上限料金を適用した請求額（税抜）とする。
+/// - CDR の ID はセッションIDとし、最終更新時刻にはセッションの終了時刻を使う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdrExporter {
+  party:     OcpiParty,
+  tariff_id: String,
+  location:  CdrLocation,
+}
+
+impl CdrExporter {
+  /// CDR を発行する CPO、料金表のID、充電した場所を指定して生成する。
+  #[must_use]
+  pub fn new(party: OcpiParty, tariff_id: impl Into<String>, location: CdrLocation) -> Self {
+    Self { party, tariff_id: tariff_id.into(), location }
+  }
+
+  /// 停止済みセッションを CDR の JSON 文書に変換する。
+  ///
+  /// # Errors
+  /// 停止していないセッションの場合は `OcpiError::Domain(SessionValueError::NotClosed)`、
+  /// 時刻が RFC 3339 で表現できない場合は `OcpiError::UnrepresentableTimestamp`、
+  /// JSON に変換できない場合は `OcpiError::UnserializableDocument` を返します。
+  pub fn export(&self, session: &Session, token: &CdrToken) -> Result<Value, OcpiError> {
+    let session_id = session.identity();
+    let closed = session.as_closed().ok_or(SessionValueError::NotClosed { session_id })?;
+    let unrepresentable = || OcpiError::UnrepresentableTimestamp { session_id };
+    let bill = closed.bill();
+
+    let elapsed_millis = u128::try_from((closed.ended_at() - closed.started_at()).whole_milliseconds()).unwrap_or(0);
+    let free_energy = KwhMilli::try_new(u64::from(bill.total_energy()) - u64::from(bill.billable_energy()))?;
+    let free_millis = free_window_millis(closed.policy().grace(), elapsed_millis, bill);
+    let billed_from = i64::try_from(free_millis)
+      .ok()
+      .and_then(|millis| closed.started_at().checked_add(Duration::milliseconds(millis)))
+      .ok_or_else(unrepresentable)?;
+
+    let last_updated = timestamp(closed.ended_at()).ok_or_else(unrepresentable)?;
+    let tariff = session_tariff(&self.party, &self.tariff_id, closed, free_energy, last_updated.clone());
+    let mut charging_periods = Vec::new();
+    if free_millis > 0 || free_energy > KwhMilli::zero() {
+      charging_periods
+        .push(period(&tariff.id, closed.started_at(), free_energy, free_millis).ok_or_else(unrepresentable)?);
+    }
+    let billed_millis = elapsed_millis - free_millis;
+    if billed_millis > 0 || bill.billable_energy() > KwhMilli::zero() || charging_periods.is_empty() {
+      charging_periods
+        .push(period(&tariff.id, billed_from, bill.billable_energy(), billed_millis).ok_or_else(unrepresentable)?);
+    }
+
+    let document = CdrDocument {
+      country_code: self.party.country_code(),
+      party_id: self.party.party_id(),
+      id: session_id.to_string(),
+      start_date_time: timestamp(closed.started_at()).ok_or_else(unrepresentable)?,
+      end_date_time: timestamp(closed.ended_at()).ok_or_else(unrepresentable)?,
+      session_id: session_id.to_string(),
+      cdr_token: CdrTokenDocument {
+        country_code: token.issuer.country_code(),
+        party_id:     token.issuer.party_id(),
+        uid:          &token.uid,
+        token_type:   token.token_type,
+        contract_id:  &token.contract_id,
+      },
+      auth_method: token.auth_method,
+      cdr_location: &self.location,
+      currency: CURRENCY,
+      tariffs: vec![tariff],
+      charging_periods,
+      total_cost: Price::yen(bill.amount_due()),
+      total_energy: kwh(u64::from(bill.total_energy())),
+      total_time: hours(elapsed_millis),
+      last_updated,
+    };
+    serde_json::to_value(&document)
+      .map_err(|error| OcpiError::UnserializableDocument { session_id, reason: error.to_string() })
+  }
+}
+
+fn period(tariff_id: &str, starts_at: OffsetDateTime, energy: KwhMilli, millis: u128) -> Option<ChargingPeriod> {
+  Some(ChargingPeriod {
+    start_date_time: timestamp(starts_at)?,
+    dimensions:      vec![
+      CdrDimension { dimension: CdrDimensionType::Energy, volume: kwh(u64::from(energy)) },
+      CdrDimension { dimension: CdrDimensionType::Time, volume: hours(millis) },
+    ],
+    tariff_id:       tariff_id.to_owned(),
+  })
+}
+
+/// 無料になった部分の長さ（ミリ秒）を求める。
+///
+/// 無料時間はそのまま、無料エネルギーは総エネルギーに占める割合で按分し、長い方を採る。
+fn free_window_millis(grace: GraceRule, elapsed_millis: u128, bill: &SessionBill) -> u128 {
+  let by_time = grace.grace_period().millis();
+  let total = u128::from(u64::from(bill.total_energy()));
+  let free = total - u128::from(u64::from(bill.billable_energy()));
+  let by_energy = if grace.free_energy() > KwhMilli::zero() && total > 0 { elapsed_millis * free / total } else { 0 };
+  by_time.max(by_energy).min(elapsed_millis)
+}
+
+fn timestamp(at: OffsetDateTime) -> Option<String> {
+  at.to_offset(UtcOffset::UTC).format(&Rfc3339).ok()
+}
+
+fn hours(millis: u128) -> f64 {
+  millis as f64 / MILLISECONDS_IN_HOUR
+}
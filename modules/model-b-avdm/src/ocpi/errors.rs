@@ -0,0 +1,90 @@
+use thiserror::Error;
+
+use crate::session::{SessionId, SessionValueError};
+
+/// OCPI 文書の生成・取り込みで発生し得るエラー。
+///
+/// 取り込みのエラーの `path` は `elements[1].restrictions.start_time` のように問題の箇所を指す。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OcpiError {
+  /// 事業者の識別子が OCPI の形式ではなかった。
+  #[error("事業者の識別子 {country_code}/{party_id} が不正です (国コード2文字・事業者ID3文字の英大文字と数字)")]
+  InvalidParty {
+    /// 入力された国コード。
+    country_code: String,
+    /// 入力された事業者ID。
+    party_id:     String,
+  },
+  /// 時刻が RFC 3339 で表現できなかった。
+  #[error("セッション {session_id} の時刻を RFC 3339 で表現できません")]
+  UnrepresentableTimestamp {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// CDR を JSON に変換できなかった。
+  #[error("セッション {session_id} の CDR を JSON に変換できません: {reason}")]
+  UnserializableDocument {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// 変換エラーの内容。
+    reason:     String,
+  },
+  /// Tariff の JSON を解析できなかった。
+  #[error("OCPI の Tariff を解析できません: {reason}")]
+  MalformedTariff {
+    /// 解析エラーの内容。
+    reason: String,
+  },
+  /// 円以外の通貨だった。
+  #[error("通貨 {currency} には対応していません (JPY のみ)")]
+  UnsupportedCurrency {
+    /// Tariff の通貨。
+    currency: String,
+  },
+  /// エネルギー以外の価格要素だった。
+  #[error("{path} の {dimension} 料金には対応していません (ENERGY のみ)")]
+  UnsupportedComponent {
+    /// 価格要素の位置。
+    path:      String,
+    /// 価格要素の種類。
+    dimension: &'static str,
+  },
+  /// 料金計算で表現できない項目が指定されていた。
+  #[error("{path} には対応していません")]
+  UnsupportedField {
+    /// 項目の位置。
+    path: String,
+  },
+  /// 整数円・1 Wh 単位で表せない値だった。
+  #[error("{path} の値 {value} は表現できません (価格は 1 円以上の整数円、エネルギーは 1 Wh 単位)")]
+  UnrepresentableValue {
+    /// 値の位置。
+    path:  String,
+    /// 入力された値。
+    value: String,
+  },
+  /// 0 円の要素が無料ルールとして解釈できなかった。
+  #[error("{path} の 0 円の要素は先頭で max_duration または max_kwh の一方だけで区切る必要があります")]
+  UnsupportedFreeElement {
+    /// 要素の位置。
+    path: String,
+  },
+  /// 段の kWh 範囲が前の段から途切れずに続いていなかった。
+  #[error("{path} の kWh 範囲が前の段と連続していません")]
+  NonContiguousTiers {
+    /// 範囲の位置。
+    path: String,
+  },
+  /// 無料時間と段階料金が併用されていた。
+  #[error("{path} の段は無料時間と組み合わせて表現できません")]
+  TierAfterTimeGrace {
+    /// 要素の位置。
+    path: String,
+  },
+  /// エネルギー単価の要素がなかった。
+  #[error("エネルギー単価の要素がありません")]
+  MissingEnergyPrice,
+  /// ドメインルールに違反した（停止していないセッションなど）。
+  #[error(transparent)]
+  Domain(#[from] SessionValueError),
+}
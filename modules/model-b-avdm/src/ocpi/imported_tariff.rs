@@ -0,0 +1,281 @@
+use std::str::FromStr;
+
+use time::OffsetDateTime;
+
+use super::{
+  errors::OcpiError,
+  party::OcpiParty,
+  tariff::{CURRENCY, ENERGY_STEP_SIZE, OcpiTariff, Price, TariffDimensionType, TariffElement, TariffRestrictions},
+};
+use crate::session::{
+  ActiveSession, BillingPolicy, FreeEnergyAllowance, GracePeriod, GraceRule, KwhMilli, MoneyYen, RateYenPerKwh,
+  SessionId, Tariff, TariffTier,
+};
+
+/// 整数とみなす誤差の許容幅。`12.345` kWh のような 10 進表記を 2 進浮動小数で受け取るため。
+const WHOLE_NUMBER_TOLERANCE: f64 = 1e-6;
+
+/// 料金要素のうち、料金計算で表現できる部分。
+struct EnergyElement {
+  path:         String,
+  rate:         u32,
+  min_kwh:      Option<u64>,
+  max_kwh:      Option<u64>,
+  max_duration: Option<u64>,
+}
+
+/// OCPI の Tariff を取り込み、model-b の料金表と課金方針に変換したもの。
+///
+/// 料金計算で表現できるのは次の形だけで、それ以外の要素・制約は黙って無視せずエラーにする。
+///
+/// - 先頭の 0 円の要素は無料ルールとする。`max_duration` だけなら無料時間、`max_kwh`
+///   だけなら無料エネルギー。
+/// - 残りの要素はエネルギー単価の段とし、`min_kwh` / `max_kwh` の範囲が途切れずに続く必要がある。
+///   OCPI は総エネルギーで段を判定するため、
+///   無料エネルギー分を差し引いて課金対象エネルギーの段にする。
+/// - `min_price` / `max_price` は最低料金・上限料金とする。
+/// - 価格は税抜の整数円、エネルギーの刻みは 1 Wh に限る。
+/// - `tariff_alt_text` / `tariff_alt_url` / `energy_mix` は説明用の項目として読み飛ばす。
+///   種別（`type`）や有効期間（`start_date_time` / `end_date_time`）、税込価格は料金計算で
+///   表現できないためエラーにする。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedTariff {
+  party:  OcpiParty,
+  id:     String,
+  tariff: Tariff,
+  policy: BillingPolicy,
+}
+
+impl ImportedTariff {
+  /// Tariff を公開した事業者を返す。
+  #[must_use]
+  pub fn party(&self) -> &OcpiParty {
+    &self.party
+  }
+
+  /// Tariff のIDを返す。
+  #[must_use]
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  /// 変換した料金表を返す。
+  #[must_use]
+  pub fn tariff(&self) -> &Tariff {
+    &self.tariff
+  }
+
+  /// 変換した課金方針を返す。
+  #[must_use]
+  pub fn policy(&self) -> BillingPolicy {
+    self.policy
+  }
+
+  /// この料金表と課金方針でセッションを開始する。
+  #[must_use]
+  pub fn start(&self, id: SessionId, started_at: OffsetDateTime) -> ActiveSession {
+    ActiveSession::new(id, started_at, self.tariff.clone(), self.policy)
+  }
+
+  fn try_from_document(document: OcpiTariff) -> Result<Self, OcpiError> {
+    let party = OcpiParty::try_new(&document.country_code, &document.party_id)?;
+    if document.currency != CURRENCY {
+      return Err(OcpiError::UnsupportedCurrency { currency: document.currency });
+    }
+    let unsupported = [
+      ("type", document.tariff_type.is_some()),
+      ("start_date_time", document.start_date_time.is_some()),
+      ("end_date_time", document.end_date_time.is_some()),
+    ];
+    if let Some((field, _)) = unsupported.into_iter().find(|(_, present)| *present) {
+      return Err(OcpiError::UnsupportedField { path: field.to_owned() });
+    }
+    let elements = document
+      .elements
+      .iter()
+      .enumerate()
+      .map(|(index, element)| energy_element(index, element))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let mut grace_period = None;
+    let mut allowance = None;
+    let mut priced = elements.iter().peekable();
+    while let Some(element) = priced.next_if(|element| element.rate == 0) {
+      match (element.min_kwh, element.max_kwh, element.max_duration) {
+        | (None, None, Some(seconds)) if grace_period.is_none() => {
+          grace_period = Some(GracePeriod::from_millis(u128::from(seconds) * 1_000));
+        },
+        | (None, Some(energy), None) if allowance.is_none() => allowance = Some(energy),
+        | _ => return Err(OcpiError::UnsupportedFreeElement { path: element.path.clone() }),
+      }
+    }
+
+    let free_energy = allowance.unwrap_or(0);
+    let mut tiers = Vec::new();
+    let mut next_start = Some(free_energy);
+    let mut last_path = None;
+    for element in priced {
+      let path = &element.path;
+      if element.rate == 0 {
+        return Err(OcpiError::UnrepresentableValue {
+          path:  format!("{path}.price_components[0].price"),
+          value: "0".to_owned(),
+        });
+      }
+      if element.max_duration.is_some() {
+        return Err(OcpiError::UnsupportedField { path: format!("{path}.restrictions.max_duration") });
+      }
+      let Some(start) = next_start.filter(|start| element.min_kwh.unwrap_or(0) == *start) else {
+        return Err(OcpiError::NonContiguousTiers { path: format!("{path}.restrictions.min_kwh") });
+      };
+      if element.max_kwh.is_some_and(|end| end <= start) {
+        return Err(OcpiError::NonContiguousTiers { path: format!("{path}.restrictions.max_kwh") });
+      }
+      if grace_period.is_some() && !tiers.is_empty() {
+        return Err(OcpiError::TierAfterTimeGrace { path: path.clone() });
+      }
+      let Some(from) = start.checked_sub(free_energy) else {
+        return Err(OcpiError::NonContiguousTiers { path: format!("{path}.restrictions.min_kwh") });
+      };
+      tiers.push(TariffTier::new(KwhMilli::try_new(from)?, RateYenPerKwh::try_new(element.rate)?));
+      next_start = element.max_kwh;
+      last_path = Some(path);
+    }
+    let Some(last_path) = last_path else {
+      return Err(OcpiError::MissingEnergyPrice);
+    };
+    if next_start.is_some() {
+      return Err(OcpiError::NonContiguousTiers { path: format!("{last_path}.restrictions.max_kwh") });
+    }
+
+    let mut tariff = Tariff::tiered(tiers)?;
+    if let Some(price) = document.min_price {
+      tariff = tariff.with_minimum_fee(yen("min_price", price)?)?;
+    }
+    if let Some(price) = document.max_price {
+      tariff = tariff.with_price_cap(yen("max_price", price)?)?;
+    }
+    let allowance = allowance.map(|energy| KwhMilli::try_new(energy).map(FreeEnergyAllowance::new)).transpose()?;
+    let grace = match (grace_period, allowance) {
+      | (Some(period), Some(allowance)) => GraceRule::TimeAndEnergy(period, allowance),
+      | (Some(period), None) => GraceRule::Time(period),
+      | (None, Some(allowance)) => GraceRule::Energy(allowance),
+      | (None, None) => GraceRule::Time(GracePeriod::from_millis(0)),
+    };
+
+    Ok(Self { party, id: document.id, tariff, policy: BillingPolicy::with_grace(grace) })
+  }
+}
+
+impl FromStr for ImportedTariff {
+  type Err = OcpiError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let document: OcpiTariff =
+      serde_json::from_str(s).map_err(|error| OcpiError::MalformedTariff { reason: error.to_string() })?;
+    Self::try_from_document(document)
+  }
+}
+
+/// 料金要素を検証し、エネルギー単価と kWh・時間の範囲を取り出す。
+fn energy_element(index: usize, element: &TariffElement) -> Result<EnergyElement, OcpiError> {
+  let path = format!("elements[{index}]");
+  for (position, component) in element.price_components.iter().enumerate() {
+    if component.dimension != TariffDimensionType::Energy {
+      return Err(OcpiError::UnsupportedComponent {
+        path:      format!("{path}.price_components[{position}]"),
+        dimension: component.dimension.as_str(),
+      });
+    }
+  }
+  let [component] = element.price_components.as_slice() else {
+    return Err(OcpiError::UnsupportedField { path: format!("{path}.price_components") });
+  };
+  let component_path = format!("{path}.price_components[0]");
+  if component.vat.is_some() {
+    return Err(OcpiError::UnsupportedField { path: format!("{component_path}.vat") });
+  }
+  if component.step_size != ENERGY_STEP_SIZE {
+    return Err(OcpiError::UnsupportedField { path: format!("{component_path}.step_size") });
+  }
+  let rate = whole(component.price, 1.0).and_then(|price| u32::try_from(price).ok()).ok_or_else(|| {
+    OcpiError::UnrepresentableValue { path: format!("{component_path}.price"), value: component.price.to_string() }
+  })?;
+
+  let restrictions = element.restrictions.clone().unwrap_or_default();
+  reject_unsupported_restrictions(&path, &restrictions)?;
+  let energy = |field: &str, kwh: Option<f64>| {
+    kwh
+      .map(|kwh| {
+        whole(kwh, 1_000.0).ok_or_else(|| OcpiError::UnrepresentableValue {
+          path:  format!("{path}.restrictions.{field}"),
+          value: kwh.to_string(),
+        })
+      })
+      .transpose()
+  };
+
+  Ok(EnergyElement {
+    min_kwh: energy("min_kwh", restrictions.min_kwh)?,
+    max_kwh: energy("max_kwh", restrictions.max_kwh)?,
+    max_duration: restrictions.max_duration,
+    path,
+    rate,
+  })
+}
+
+/// 料金計算で表現できない制約（時間帯・曜日・電流など）を拒否する。
+fn reject_unsupported_restrictions(path: &str, restrictions: &TariffRestrictions) -> Result<(), OcpiError> {
+  let TariffRestrictions {
+    start_time,
+    end_time,
+    start_date,
+    end_date,
+    min_kwh: _,
+    max_kwh: _,
+    min_current,
+    max_current,
+    min_power,
+    max_power,
+    min_duration,
+    max_duration: _,
+    day_of_week,
+    reservation,
+  } = restrictions;
+  let present = [
+    ("start_time", start_time.is_some()),
+    ("end_time", end_time.is_some()),
+    ("start_date", start_date.is_some()),
+    ("end_date", end_date.is_some()),
+    ("min_current", min_current.is_some()),
+    ("max_current", max_current.is_some()),
+    ("min_power", min_power.is_some()),
+    ("max_power", max_power.is_some()),
+    ("min_duration", min_duration.is_some()),
+    ("day_of_week", day_of_week.is_some()),
+    ("reservation", reservation.is_some()),
+  ];
+  match present.into_iter().find(|(_, present)| *present) {
+    | Some((field, _)) => Err(OcpiError::UnsupportedField { path: format!("{path}.restrictions.{field}") }),
+    | None => Ok(()),
+  }
+}
+
+fn yen(field: &str, price: Price) -> Result<MoneyYen, OcpiError> {
+  if price.incl_vat.is_some() {
+    return Err(OcpiError::UnsupportedField { path: format!("{field}.incl_vat") });
+  }
+  let amount = whole(price.excl_vat, 1.0).ok_or_else(|| OcpiError::UnrepresentableValue {
+    path:  format!("{field}.excl_vat"),
+    value: price.excl_vat.to_string(),
+  })?;
+  Ok(MoneyYen::try_new(amount)?)
+}
+
+/// `value * scale` が 0 以上の整数なら返す。
+fn whole(value: f64, scale: f64) -> Option<u64> {
+  let scaled = value * scale;
+  let rounded = scaled.round();
+  ((scaled - rounded).abs() < WHOLE_NUMBER_TOLERANCE && (0.0..=u64::MAX as f64).contains(&rounded))
+    .then_some(rounded as u64)
+}
@@ -0,0 +1,37 @@
+use super::errors::OcpiError;
+
+/// OCPI の事業者（CPO・eMSP）を識別する国コードと事業者IDの組。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcpiParty {
+  country_code: String,
+  party_id:     String,
+}
+
+impl OcpiParty {
+  /// ISO 3166-1 alpha-2 の国コードと 3 文字の事業者IDから生成する。
+  ///
+  /// # Errors
+  /// 国コードが英大文字2文字、事業者IDが英大文字・数字3文字でない場合、
+  /// `OcpiError::InvalidParty` を返します。
+  pub fn try_new(country_code: &str, party_id: &str) -> Result<Self, OcpiError> {
+    let valid_country = country_code.len() == 2 && country_code.bytes().all(|byte| byte.is_ascii_uppercase());
+    let valid_party =
+      party_id.len() == 3 && party_id.bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit());
+    if !valid_country || !valid_party {
+      return Err(OcpiError::InvalidParty { country_code: country_code.to_owned(), party_id: party_id.to_owned() });
+    }
+    Ok(Self { country_code: country_code.to_owned(), party_id: party_id.to_owned() })
+  }
+
+  /// 国コードを返す。
+  #[must_use]
+  pub fn country_code(&self) -> &str {
+    &self.country_code
+  }
+
+  /// 事業者IDを返す。
+  #[must_use]
+  pub fn party_id(&self) -> &str {
+    &self.party_id
+  }
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::party::OcpiParty;
+use crate::session::{ClosedSession, KwhMilli, MoneyYen};
+
+/// 請求通貨。model-b の金額はすべて円で扱う。
+pub(crate) const CURRENCY: &str = "JPY";
+/// エネルギーの課金単位（Wh）。計器の分解能に合わせて 1 Wh 刻みとする。
+pub(crate) const ENERGY_STEP_SIZE: u32 = 1;
+
+/// OCPI の Tariff オブジェクト。
+///
+/// 仕様にない項目は解析エラーにする。`tariff_alt_text` / `tariff_alt_url` / `energy_mix`
+/// は説明用の項目として受け取るだけで、料金計算には使わない。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct OcpiTariff {
+  pub(crate) country_code:    String,
+  pub(crate) party_id:        String,
+  pub(crate) id:              String,
+  pub(crate) currency:        String,
+  #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+  pub(crate) tariff_type:     Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) tariff_alt_text: Option<Value>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) tariff_alt_url:  Option<String>,
+  pub(crate) elements:        Vec<TariffElement>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) min_price:       Option<Price>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) max_price:       Option<Price>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) energy_mix:      Option<Value>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) start_date_time: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) end_date_time:   Option<String>,
+  pub(crate) last_updated:    String,
+}
+
+/// 料金要素。`restrictions` を満たす間だけ `price_components` が適用される。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TariffElement {
+  pub(crate) price_components: Vec<PriceComponent>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) restrictions:     Option<TariffRestrictions>,
+}
+
+/// 料金要素の価格。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PriceComponent {
+  #[serde(rename = "type")]
+  pub(crate) dimension: TariffDimensionType,
+  pub(crate) price:     f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) vat:       Option<f64>,
+  pub(crate) step_size: u32,
+}
+
+/// 価格の課金対象。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum TariffDimensionType {
+  /// エネルギー（kWh あたり）。
+  Energy,
+  /// セッションごとの固定料金。
+  Flat,
+  /// 充電していない駐車時間（時間あたり）。
+  ParkingTime,
+  /// 充電時間（時間あたり）。
+  Time,
+}
+
+impl TariffDimensionType {
+  pub(crate) fn as_str(self) -> &'static str {
+    match self {
+      | Self::Energy => "ENERGY",
+      | Self::Flat => "FLAT",
+      | Self::ParkingTime => "PARKING_TIME",
+      | Self::Time => "TIME",
+    }
+  }
+}
+
+/// 料金要素の適用条件。kWh・時間はいずれもセッション開始からの累計で判定する。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TariffRestrictions {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) start_time:   Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) end_time:     Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) start_date:   Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) end_date:     Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) min_kwh:      Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) max_kwh:      Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) min_current:  Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) max_current:  Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) min_power:    Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) max_power:    Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) min_duration: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) max_duration: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) day_of_week:  Option<Vec<String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) reservation:  Option<String>,
+}
+
+/// 価格。model-b は税抜の金額だけを扱う。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Price {
+  pub(crate) excl_vat: f64,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub(crate) incl_vat: Option<f64>,
+}
+
+impl Price {
+  pub(crate) fn yen(amount: MoneyYen) -> Self {
+    Self { excl_vat: u64::from(amount) as f64, incl_vat: None }
+  }
+}
+
+impl TariffElement {
+  fn energy(rate: u32, restrictions: TariffRestrictions) -> Self {
+    let restrictions = (restrictions != TariffRestrictions::default()).then_some(restrictions);
+    Self {
+      price_components: vec![PriceComponent {
+        dimension: TariffDimensionType::Energy,
+        price:     f64::from(rate),
+        vat:       None,
+        step_size: ENERGY_STEP_SIZE,
+      }],
+      restrictions,
+    }
+  }
+}
+
+/// ミリkWh を OCPI の kWh（小数）に変換する。
+pub(crate) fn kwh(milli: u64) -> f64 {
+  milli as f64 / 1_000.0
+}
+
+/// 停止済みセッションに適用した料金表を OCPI の Tariff として表す。
+///
+/// 無料ルールは先頭の 0 円の要素として表し、時間は `max_duration`、無料エネルギーは `max_kwh`
+/// で区切る。model-b の段階料金は課金対象エネルギーで段を判定するが、OCPI は総エネルギーで
+/// 判定するため、段の境界には無料エネルギー量を足して表す。
+///
+/// 無料時間内のエネルギー量はセッションごとに異なるため、無料時間と段階料金を併用する場合は
+/// このセッションで無料になったエネルギー量で境界をずらし、ID にセッションIDを付けて
+/// セッション専用の Tariff として区別する。それ以外は `tariff_id` のまま、どのセッションにも
+/// 共通の Tariff になる。
+pub(crate) fn session_tariff(
+  party: &OcpiParty,
+  tariff_id: &str,
+  closed: &ClosedSession,
+  free_energy: KwhMilli,
+  last_updated: String,
+) -> OcpiTariff {
+  let grace = closed.policy().grace();
+  let mut elements = Vec::new();
+  if !grace.grace_period().is_zero() {
+    let max_duration = u64::try_from(grace.grace_period().millis() / 1_000).unwrap_or(u64::MAX);
+    elements
+      .push(TariffElement::energy(0, TariffRestrictions { max_duration: Some(max_duration), ..Default::default() }));
+  }
+  if grace.free_energy() > KwhMilli::zero() {
+    let max_kwh = kwh(u64::from(grace.free_energy()));
+    elements.push(TariffElement::energy(0, TariffRestrictions { max_kwh: Some(max_kwh), ..Default::default() }));
+  }
+
+  let tiers = closed.tariff().tiers();
+  let per_session = !grace.grace_period().is_zero() && tiers.len() > 1;
+  let (id, free_energy) = if per_session {
+    (format!("{tariff_id}-{}", closed.identity()), free_energy)
+  } else {
+    (tariff_id.to_owned(), grace.free_energy())
+  };
+  let shifted = |energy: KwhMilli| kwh(u64::from(energy).saturating_add(u64::from(free_energy)));
+  for (index, tier) in tiers.iter().enumerate() {
+    let starts_at = shifted(tier.starts_at());
+    let restrictions = TariffRestrictions {
+      min_kwh: (starts_at > 0.0).then_some(starts_at),
+      max_kwh: tiers.get(index + 1).map(|next| shifted(next.starts_at())),
+      ..Default::default()
+    };
+    elements.push(TariffElement::energy(u32::from(tier.rate()), restrictions));
+  }
+
+  OcpiTariff {
+    country_code: party.country_code().to_owned(),
+    party_id: party.party_id().to_owned(),
+    id,
+    currency: CURRENCY.to_owned(),
+    tariff_type: None,
+    tariff_alt_text: None,
+    tariff_alt_url: None,
+    elements,
+    min_price: closed.tariff().minimum_fee().map(Price::yen),
+    max_price: closed.tariff().price_cap().map(Price::yen),
+    energy_mix: None,
+    start_date_time: None,
+    end_date_time: None,
+    last_updated,
+  }
+}
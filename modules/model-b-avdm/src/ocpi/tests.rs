@@ -0,0 +1,419 @@
+use serde_json::{Value, json};
+use time::{OffsetDateTime, macros::datetime};
+
+use super::{
+  AuthMethod, CdrExporter, CdrLocation, CdrToken, GeoLocation, ImportedTariff, OcpiError, OcpiParty, TokenType,
+};
+use crate::session::{
+  BillingPolicy, FreeEnergyAllowance, GracePeriod, GraceRule, IdGenerator, KwhMilli, MoneyYen, RateYenPerKwh,
+  SequentialIdGenerator, Session, SessionValueError, Tariff, TariffTier, test_support::stop_session,
+};
+
+const STARTED_AT: OffsetDateTime = datetime!(2025-04-01 10:00 UTC);
+
+fn exporter() -> CdrExporter {
+  let location = CdrLocation {
+    id:                   "LOC-001".to_owned(),
+    name:                 Some("駅前充電ステーション".to_owned()),
+    address:              "丸の内1-9-1".to_owned(),
+    city:                 "千代田区".to_owned(),
+    postal_code:          Some("100-0005".to_owned()),
+    country:              "JPN".to_owned(),
+    coordinates:          GeoLocation { latitude: "35.681236".to_owned(), longitude: "139.767125".to_owned() },
+    evse_uid:             "EVSE-001".to_owned(),
+    evse_id:              "JP*ABC*E001".to_owned(),
+    connector_id:         "1".to_owned(),
+    connector_standard:   "CHADEMO".to_owned(),
+    connector_format:     "CABLE".to_owned(),
+    connector_power_type: "DC".to_owned(),
+  };
+  CdrExporter::new(OcpiParty::try_new("JP", "ABC").unwrap(), "TARIFF-STD", location)
+}
+
+fn token() -> CdrToken {
+  CdrToken {
+    issuer:      OcpiParty::try_new("JP", "XYZ").unwrap(),
+    uid:         "04A1B2C3".to_owned(),
+    token_type:  TokenType::Rfid,
+    contract_id: "JP-XYZ-C12345678-9".to_owned(),
+    auth_method: AuthMethod::Whitelist,
+  }
+}
+
+fn closed_session(tariff: Tariff, policy: BillingPolicy, minutes: i64, energy: u64) -> Session {
+  stop_session(1, STARTED_AT, tariff, policy, minutes, energy)
+}
+
+fn dimensions(period: &Value) -> (f64, f64) {
+  let volume = |kind: &str| {
+    period["dimensions"].as_array().unwrap().iter().find(|dimension| dimension["type"] == kind).unwrap()["volume"]
+      .as_f64()
+      .unwrap()
+  };
+  (volume("ENERGY"), volume("TIME"))
+}
+
+// ========================================
+// CDR の出力
+// ========================================
+
+#[test]
+fn closed_session_is_exported_as_cdr() {
+  let session = closed_session(Tariff::flat(RateYenPerKwh::try_new(50).unwrap()), BillingPolicy::default(), 30, 12_000);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+
+  assert_eq!(cdr["country_code"], "JP");
+  assert_eq!(cdr["party_id"], "ABC");
+  assert_eq!(cdr["id"], session.identity().to_string());
+  assert_eq!(cdr["session_id"], session.identity().to_string());
+  assert_eq!(cdr["start_date_time"], "2025-04-01T10:00:00Z");
+  assert_eq!(cdr["end_date_time"], "2025-04-01T10:30:00Z");
+  assert_eq!(cdr["last_updated"], "2025-04-01T10:30:00Z");
+  assert_eq!(
+    cdr["cdr_token"],
+    json!({
+      "country_code": "JP",
+      "party_id": "XYZ",
+      "uid": "04A1B2C3",
+      "type": "RFID",
+      "contract_id": "JP-XYZ-C12345678-9",
+    })
+  );
+  assert_eq!(cdr["auth_method"], "WHITELIST");
+  assert_eq!(cdr["cdr_location"]["evse_id"], "JP*ABC*E001");
+  assert_eq!(cdr["cdr_location"]["coordinates"]["latitude"], "35.681236");
+  assert_eq!(cdr["currency"], "JPY");
+  assert_eq!(cdr["total_cost"], json!({ "excl_vat": 500.0 }));
+  assert_eq!(cdr["total_energy"], 12.0);
+  assert_eq!(cdr["total_time"], 0.5);
+}
+
+#[test]
+fn grace_period_is_a_zero_priced_charging_period() {
+  let session = closed_session(Tariff::flat(RateYenPerKwh::try_new(50).unwrap()), BillingPolicy::default(), 30, 12_000);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+
+  let periods = cdr["charging_periods"].as_array().unwrap();
+  assert_eq!(periods.len(), 2);
+  assert_eq!(periods[0]["start_date_time"], "2025-04-01T10:00:00Z");
+  assert_eq!(dimensions(&periods[0]), (2.0, 5.0 / 60.0));
+  assert_eq!(periods[1]["start_date_time"], "2025-04-01T10:05:00Z");
+  assert_eq!(dimensions(&periods[1]), (10.0, 25.0 / 60.0));
+  assert!(periods.iter().all(|period| period["tariff_id"] == "TARIFF-STD"));
+
+  let tariff = &cdr["tariffs"][0];
+  assert_eq!(tariff["id"], "TARIFF-STD");
+  assert_eq!(tariff["currency"], "JPY");
+  assert_eq!(
+    tariff["elements"],
+    json!([
+      {
+        "price_components": [{ "type": "ENERGY", "price": 0.0, "step_size": 1 }],
+        "restrictions": { "max_duration": 300 },
+      },
+      { "price_components": [{ "type": "ENERGY", "price": 50.0, "step_size": 1 }] },
+    ])
+  );
+}
+
+#[test]
+fn session_dependent_tiers_get_a_session_specific_tariff_id() {
+  let tariff = Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), RateYenPerKwh::try_new(40).unwrap()),
+    TariffTier::new(KwhMilli::try_new(10_000).unwrap(), RateYenPerKwh::try_new(30).unwrap()),
+  ])
+  .unwrap();
+  let session = closed_session(tariff, BillingPolicy::default(), 30, 12_000);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+
+  // 無料時間内の 2 kWh はこのセッション固有なので、境界をずらした Tariff を別IDで出力する
+  let tariff_id = format!("TARIFF-STD-{}", session.identity());
+  assert_eq!(cdr["tariffs"][0]["id"], tariff_id.as_str());
+  assert_eq!(cdr["tariffs"][0]["elements"][2]["restrictions"], json!({ "min_kwh": 12.0 }));
+  let periods = cdr["charging_periods"].as_array().unwrap();
+  assert!(periods.iter().all(|period| period["tariff_id"] == tariff_id.as_str()));
+}
+
+#[test]
+fn free_energy_and_tiers_are_reflected_in_the_tariff() {
+  let tariff = Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), RateYenPerKwh::try_new(40).unwrap()),
+    TariffTier::new(KwhMilli::try_new(10_000).unwrap(), RateYenPerKwh::try_new(30).unwrap()),
+  ])
+  .unwrap()
+  .with_minimum_fee(MoneyYen::try_new(100).unwrap())
+  .unwrap();
+  let policy =
+    BillingPolicy::with_grace(GraceRule::Energy(FreeEnergyAllowance::new(KwhMilli::try_new(1_000).unwrap())));
+  let session = closed_session(tariff, policy, 60, 12_000);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+
+  assert_eq!(cdr["total_cost"], json!({ "excl_vat": 430.0 }));
+  let periods = cdr["charging_periods"].as_array().unwrap();
+  assert_eq!(dimensions(&periods[0]), (1.0, 5.0 / 60.0));
+  assert_eq!(periods[1]["start_date_time"], "2025-04-01T10:05:00Z");
+  assert_eq!(dimensions(&periods[1]), (11.0, 55.0 / 60.0));
+  let tariff = &cdr["tariffs"][0];
+  let restrictions: Vec<_> =
+    tariff["elements"].as_array().unwrap().iter().map(|element| element["restrictions"].clone()).collect();
+  assert_eq!(restrictions, vec![
+    json!({ "max_kwh": 1.0 }),
+    json!({ "min_kwh": 1.0, "max_kwh": 11.0 }),
+    json!({ "min_kwh": 11.0 }),
+  ]);
+  assert_eq!(tariff["min_price"], json!({ "excl_vat": 100.0 }));
+  assert!(tariff.get("max_price").is_none());
+}
+
+#[test]
+fn session_within_grace_period_has_only_the_free_period() {
+  let session = closed_session(Tariff::flat(RateYenPerKwh::try_new(50).unwrap()), BillingPolicy::default(), 3, 800);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+
+  let periods = cdr["charging_periods"].as_array().unwrap();
+  assert_eq!(periods.len(), 1);
+  assert_eq!(dimensions(&periods[0]), (0.8, 3.0 / 60.0));
+  assert_eq!(cdr["total_cost"], json!({ "excl_vat": 0.0 }));
+}
+
+#[test]
+fn active_sessions_and_invalid_parties_are_rejected() {
+  let session_id = SequentialIdGenerator::new().next_id();
+  let active = Session::new_active(session_id, STARTED_AT, RateYenPerKwh::try_new(50).unwrap());
+
+  assert_eq!(exporter().export(&active, &token()), Err(OcpiError::Domain(SessionValueError::NotClosed { session_id })));
+  assert!(matches!(OcpiParty::try_new("JPN", "ABC"), Err(OcpiError::InvalidParty { .. })));
+  assert!(matches!(OcpiParty::try_new("JP", "ab1"), Err(OcpiError::InvalidParty { .. })));
+}
+
+// ========================================
+// Tariff の取り込み
+// ========================================
+
+fn tariff_json(elements: Value) -> Value {
+  json!({
+    "country_code": "JP",
+    "party_id": "XYZ",
+    "id": "PARTNER-01",
+    "currency": "JPY",
+    "elements": elements,
+    "last_updated": "2025-04-01T00:00:00Z",
+  })
+}
+
+fn energy_element(price: f64, restrictions: Value) -> Value {
+  json!({
+    "price_components": [{ "type": "ENERGY", "price": price, "step_size": 1 }],
+    "restrictions": restrictions,
+  })
+}
+
+fn import(document: &Value) -> Result<ImportedTariff, OcpiError> {
+  document.to_string().parse()
+}
+
+#[test]
+fn energy_tiers_and_free_energy_are_imported() {
+  let mut document = tariff_json(json!([
+    energy_element(0.0, json!({ "max_kwh": 1.0 })),
+    energy_element(40.0, json!({ "min_kwh": 1.0, "max_kwh": 11.0 })),
+    energy_element(30.0, json!({ "min_kwh": 11.0 })),
+  ]));
+  document["min_price"] = json!({ "excl_vat": 100 });
+  document["tariff_alt_text"] = json!([{ "language": "ja", "text": "40 円/kWh（10 kWh 超は 30 円/kWh）" }]);
+  document["tariff_alt_url"] = json!("https://example.com/tariffs/partner-01");
+  document["energy_mix"] = json!({ "is_green_energy": true });
+
+  let imported = import(&document).unwrap();
+
+  assert_eq!(imported.id(), "PARTNER-01");
+  assert_eq!(imported.party(), &OcpiParty::try_new("JP", "XYZ").unwrap());
+  assert_eq!(
+    imported.tariff(),
+    &Tariff::tiered(vec![
+      TariffTier::new(KwhMilli::zero(), RateYenPerKwh::try_new(40).unwrap()),
+      TariffTier::new(KwhMilli::try_new(10_000).unwrap(), RateYenPerKwh::try_new(30).unwrap()),
+    ])
+    .unwrap()
+    .with_minimum_fee(MoneyYen::try_new(100).unwrap())
+    .unwrap()
+  );
+  assert_eq!(
+    imported.policy(),
+    BillingPolicy::with_grace(GraceRule::Energy(FreeEnergyAllowance::new(KwhMilli::try_new(1_000).unwrap())))
+  );
+
+  let closed = imported
+    .start(SequentialIdGenerator::new().next_id(), STARTED_AT)
+    .stop(datetime!(2025-04-01 11:00 UTC), KwhMilli::try_new(12_000).unwrap())
+    .unwrap();
+  assert_eq!(closed.bill().amount_due(), MoneyYen::try_new(430).unwrap());
+}
+
+#[test]
+fn grace_period_and_price_cap_are_imported() {
+  let mut document = tariff_json(json!([
+    energy_element(0.0, json!({ "max_duration": 600 })),
+    { "price_components": [{ "type": "ENERGY", "price": 55, "step_size": 1 }] },
+  ]));
+  document["max_price"] = json!({ "excl_vat": 3000.0 });
+
+  let imported = import(&document).unwrap();
+
+  assert_eq!(
+    imported.tariff(),
+    &Tariff::flat(RateYenPerKwh::try_new(55).unwrap()).with_price_cap(MoneyYen::try_new(3_000).unwrap()).unwrap()
+  );
+  assert_eq!(imported.policy(), BillingPolicy::with_grace(GraceRule::Time(GracePeriod::from_minutes(10))));
+}
+
+#[test]
+fn exported_tariff_can_be_imported_back() {
+  let tariff = Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), RateYenPerKwh::try_new(40).unwrap()),
+    TariffTier::new(KwhMilli::try_new(10_000).unwrap(), RateYenPerKwh::try_new(30).unwrap()),
+  ])
+  .unwrap();
+  let policy =
+    BillingPolicy::with_grace(GraceRule::Energy(FreeEnergyAllowance::new(KwhMilli::try_new(1_500).unwrap())));
+  let session = closed_session(tariff.clone(), policy, 60, 12_345);
+
+  let cdr = exporter().export(&session, &token()).unwrap();
+  let imported = import(&cdr["tariffs"][0]).unwrap();
+
+  assert_eq!(imported.id(), "TARIFF-STD");
+  assert_eq!(imported.tariff(), &tariff);
+  assert_eq!(imported.policy(), policy);
+}
+
+#[test]
+fn components_the_engine_cannot_represent_are_rejected() {
+  let unsupported = |dimension: &str| {
+    tariff_json(json!([{
+      "price_components": [
+        { "type": "ENERGY", "price": 50, "step_size": 1 },
+        { "type": dimension, "price": 100, "step_size": 60 },
+      ],
+    }]))
+  };
+  for dimension in ["TIME", "FLAT", "PARKING_TIME"] {
+    assert_eq!(
+      import(&unsupported(dimension)),
+      Err(OcpiError::UnsupportedComponent { path: "elements[0].price_components[1]".to_owned(), dimension })
+    );
+  }
+}
+
+#[test]
+fn restrictions_the_engine_cannot_represent_are_rejected() {
+  let cases = [
+    (json!({ "start_time": "22:00", "end_time": "06:00" }), "elements[0].restrictions.start_time"),
+    (json!({ "min_duration": 1800 }), "elements[0].restrictions.min_duration"),
+    (json!({ "day_of_week": ["SATURDAY", "SUNDAY"] }), "elements[0].restrictions.day_of_week"),
+    (json!({ "max_power": 50.0 }), "elements[0].restrictions.max_power"),
+    (json!({ "max_duration": 600 }), "elements[0].restrictions.max_duration"),
+  ];
+  for (restrictions, path) in cases {
+    let document = tariff_json(json!([energy_element(50.0, restrictions)]));
+    assert_eq!(import(&document), Err(OcpiError::UnsupportedField { path: path.to_owned() }));
+  }
+
+  let mut with_vat = tariff_json(json!([energy_element(50.0, json!({}))]));
+  with_vat["elements"][0]["price_components"][0]["vat"] = json!(10.0);
+  assert_eq!(
+    import(&with_vat),
+    Err(OcpiError::UnsupportedField { path: "elements[0].price_components[0].vat".to_owned() })
+  );
+}
+
+#[test]
+fn tariff_fields_the_engine_cannot_represent_are_rejected() {
+  let cases = [
+    ("type", json!("REGULAR"), "type"),
+    ("start_date_time", json!("2025-04-01T00:00:00Z"), "start_date_time"),
+    ("end_date_time", json!("2025-10-01T00:00:00Z"), "end_date_time"),
+    ("min_price", json!({ "excl_vat": 100, "incl_vat": 110 }), "min_price.incl_vat"),
+    ("max_price", json!({ "excl_vat": 3000, "incl_vat": 3300 }), "max_price.incl_vat"),
+  ];
+  for (field, value, path) in cases {
+    let mut document = tariff_json(json!([energy_element(50.0, json!({}))]));
+    document[field] = value;
+    assert_eq!(import(&document), Err(OcpiError::UnsupportedField { path: path.to_owned() }));
+  }
+
+  // 仕様にない項目は黙って無視しない
+  let mut unknown_field = tariff_json(json!([energy_element(50.0, json!({}))]));
+  unknown_field["discount"] = json!(10);
+  assert!(matches!(import(&unknown_field), Err(OcpiError::MalformedTariff { .. })));
+  let mut unknown_component_field = tariff_json(json!([energy_element(50.0, json!({}))]));
+  unknown_component_field["elements"][0]["price_components"][0]["currency"] = json!("EUR");
+  assert!(matches!(import(&unknown_component_field), Err(OcpiError::MalformedTariff { .. })));
+}
+
+#[test]
+fn prices_and_tiers_must_be_representable() {
+  let fractional = tariff_json(json!([energy_element(45.5, json!({}))]));
+  assert_eq!(
+    import(&fractional),
+    Err(OcpiError::UnrepresentableValue {
+      path:  "elements[0].price_components[0].price".to_owned(),
+      value: "45.5".to_owned(),
+    })
+  );
+
+  let gap = tariff_json(json!([
+    energy_element(40.0, json!({ "max_kwh": 10.0 })),
+    energy_element(30.0, json!({ "min_kwh": 12.0 })),
+  ]));
+  assert_eq!(import(&gap), Err(OcpiError::NonContiguousTiers { path: "elements[1].restrictions.min_kwh".to_owned() }));
+
+  let bounded = tariff_json(json!([energy_element(40.0, json!({ "max_kwh": 10.0 }))]));
+  assert_eq!(
+    import(&bounded),
+    Err(OcpiError::NonContiguousTiers { path: "elements[0].restrictions.max_kwh".to_owned() })
+  );
+
+  // 上限が下限以下の段は後続の段と連続していても受け付けない
+  let shrinking = tariff_json(json!([
+    energy_element(0.0, json!({ "max_kwh": 10.0 })),
+    energy_element(40.0, json!({ "min_kwh": 10.0, "max_kwh": 5.0 })),
+    energy_element(30.0, json!({ "min_kwh": 5.0 })),
+  ]));
+  assert_eq!(
+    import(&shrinking),
+    Err(OcpiError::NonContiguousTiers { path: "elements[1].restrictions.max_kwh".to_owned() })
+  );
+
+  let tiers_after_time_grace = tariff_json(json!([
+    energy_element(0.0, json!({ "max_duration": 300 })),
+    energy_element(40.0, json!({ "max_kwh": 10.0 })),
+    energy_element(30.0, json!({ "min_kwh": 10.0 })),
+  ]));
+  assert_eq!(import(&tiers_after_time_grace), Err(OcpiError::TierAfterTimeGrace { path: "elements[2]".to_owned() }));
+
+  let free_only = tariff_json(json!([energy_element(0.0, json!({ "max_duration": 300 }))]));
+  assert_eq!(import(&free_only), Err(OcpiError::MissingEnergyPrice));
+
+  let late_free = tariff_json(json!([energy_element(0.0, json!({ "min_kwh": 5.0 })), energy_element(40.0, json!({}))]));
+  assert_eq!(import(&late_free), Err(OcpiError::UnsupportedFreeElement { path: "elements[0]".to_owned() }));
+}
+
+#[test]
+fn invalid_tariff_documents_are_rejected() {
+  let mut euro = tariff_json(json!([energy_element(50.0, json!({}))]));
+  euro["currency"] = json!("EUR");
+  assert_eq!(import(&euro), Err(OcpiError::UnsupportedCurrency { currency: "EUR".to_owned() }));
+
+  let mut invalid_party = tariff_json(json!([energy_element(50.0, json!({}))]));
+  invalid_party["party_id"] = json!("xy");
+  assert!(matches!(import(&invalid_party), Err(OcpiError::InvalidParty { .. })));
+
+  assert!(matches!("{\"id\": 1}".parse::<ImportedTariff>(), Err(OcpiError::MalformedTariff { .. })));
+  let unknown = tariff_json(json!([{ "price_components": [{ "type": "SPEED", "price": 1, "step_size": 1 }] }]));
+  assert!(matches!(import(&unknown), Err(OcpiError::MalformedTariff { .. })));
+}
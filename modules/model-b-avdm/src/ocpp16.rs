@@ -0,0 +1,12 @@
+mod central_system;
+mod errors;
+mod frame;
+mod server;
+
+pub use central_system::{CentralSystem, UnbilledTransaction};
+pub use errors::{CallError, CallErrorCode};
+pub use frame::Frame;
+pub use server::{CentralSystemServer, SUBPROTOCOL};
+
+#[cfg(test)]
+mod tests;
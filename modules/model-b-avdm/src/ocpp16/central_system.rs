@@ -0,0 +1,348 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use super::errors::{CallError, CallErrorCode};
+use crate::session::{
+  ActiveSession, BillingPolicy, ClosedSession, ConnectorId, IdGenerator, MeterReading, MeterRegister, MeteredSession,
+  OcppTransactionId, SessionBill, SessionValueError, Tariff, TransactionIdMap,
+};
+
+/// BootNotification の応答で指示するハートビート間隔（秒）。
+const HEARTBEAT_INTERVAL_SECONDS: u32 = 300;
+/// 既定の計測項目。`measurand` 省略時はこの値とみなす。
+const ENERGY_REGISTER: &str = "Energy.Active.Import.Register";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BootNotificationRequest {
+  charge_point_vendor: String,
+  charge_point_model:  String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorizeRequest {
+  id_tag: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartTransactionRequest {
+  connector_id: u32,
+  id_tag:       String,
+  meter_start:  u64,
+  timestamp:    String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MeterValuesRequest {
+  transaction_id: Option<i32>,
+  meter_value:    Vec<MeterValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MeterValue {
+  timestamp:     String,
+  sampled_value: Vec<SampledValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SampledValue {
+  value:     String,
+  measurand: Option<String>,
+  phase:     Option<String>,
+  unit:      Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StopTransactionRequest {
+  transaction_id: i32,
+  meter_stop:     u64,
+  timestamp:      String,
+}
+
+/// 進行中の取引。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OpenTransaction {
+  charge_point: String,
+  connector:    ConnectorId,
+  session:      MeteredSession,
+}
+
+/// 停止値で請求を確定できずに終了した取引。
+///
+/// 充電器は StopTransaction の再送を繰り返すため、取引は閉じたうえで原因とともに保持し、
+/// 請求の扱いは運用側で判断する。
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnbilledTransaction {
+  session:    MeteredSession,
+  ended_at:   OffsetDateTime,
+  meter_stop: MeterReading,
+  error:      SessionValueError,
+}
+
+impl UnbilledTransaction {
+  /// 停止できなかった計量中のセッションを返す。
+  #[must_use]
+  pub fn session(&self) -> &MeteredSession {
+    &self.session
+  }
+
+  /// 充電器が報告した終了時刻を返す。
+  #[must_use]
+  pub fn ended_at(&self) -> OffsetDateTime {
+    self.ended_at
+  }
+
+  /// 充電器が報告した計器の停止値を返す。
+  #[must_use]
+  pub fn meter_stop(&self) -> MeterReading {
+    self.meter_stop
+  }
+
+  /// 請求を確定できなかった原因を返す。
+  #[must_use]
+  pub fn error(&self) -> &SessionValueError {
+    &self.error
+  }
+}
+
+/// OCPP 1.6J の中央システム。充電器からの要求を model-b のセッション操作に対応付ける。
+///
+/// - StartTransaction: `ActiveSession::new` でセッションを開始し、取引IDを払い出す。
+/// - MeterValues: 計器の指示値で `bill_snapshot` を取り、見込み請求を更新する。
+/// - StopTransaction: 計器の停止値で `stop` し、請求を確定する。確定できなくても取引は閉じ、
+///   `UnbilledTransaction` として記録する。
+///
+/// 通信には依存しないため、WebSocket 経由では `CentralSystemServer` から呼び出す。
+#[derive(Debug)]
+pub struct CentralSystem<G: IdGenerator> {
+  tariff:        Tariff,
+  policy:        BillingPolicy,
+  register:      MeterRegister,
+  ids:           G,
+  accepted_tags: HashSet<String>,
+  booted:        HashSet<String>,
+  transactions:  TransactionIdMap,
+  open:          HashMap<OcppTransactionId, OpenTransaction>,
+  snapshots:     HashMap<OcppTransactionId, SessionBill>,
+  closed:        HashMap<OcppTransactionId, ClosedSession>,
+  unbilled:      HashMap<OcppTransactionId, UnbilledTransaction>,
+}
+
+impl<G: IdGenerator> CentralSystem<G> {
+  /// 料金表・課金方針とセッションIDの払い出し方を指定して生成する。
+  #[must_use]
+  pub fn new(tariff: Tariff, policy: BillingPolicy, ids: G) -> Self {
+    Self {
+      tariff,
+      policy,
+      register: MeterRegister::monotonic(),
+      ids,
+      accepted_tags: HashSet::new(),
+      booted: HashSet::new(),
+      transactions: TransactionIdMap::new(),
+      open: HashMap::new(),
+      snapshots: HashMap::new(),
+      closed: HashMap::new(),
+      unbilled: HashMap::new(),
+    }
+  }
+
+  /// 充電器の計器の桁あふれの扱いを設定する。
+  #[must_use]
+  pub fn with_register(mut self, register: MeterRegister) -> Self {
+    self.register = register;
+    self
+  }
+
+  /// 充電を許可する ID タグを追加する。
+  #[must_use]
+  pub fn with_id_tag(mut self, id_tag: impl Into<String>) -> Self {
+    self.accepted_tags.insert(id_tag.into());
+    self
+  }
+
+  /// 充電器からの要求を処理し、CALLRESULT の本体を返す。
+  ///
+  /// # Errors
+  /// 未対応のアクション、不正な要求、ドメインルール違反の場合、CALLERROR として返す
+  /// `CallError` を返します。
+  pub fn handle(&mut self, charge_point: &str, action: &str, payload: Value) -> Result<Value, CallError> {
+    match action {
+      | "BootNotification" => {
+        let request: BootNotificationRequest = parse_payload(payload)?;
+        check_length("chargePointVendor", &request.charge_point_vendor, 20)?;
+        check_length("chargePointModel", &request.charge_point_model, 20)?;
+        self.booted.insert(charge_point.to_owned());
+        Ok(json!({ "status": "Accepted", "currentTime": now(), "interval": HEARTBEAT_INTERVAL_SECONDS }))
+      },
+      | "Heartbeat" => Ok(json!({ "currentTime": now() })),
+      | "Authorize" => {
+        self.require_boot(charge_point)?;
+        let request: AuthorizeRequest = parse_payload(payload)?;
+        Ok(json!({ "idTagInfo": self.id_tag_info(&request.id_tag) }))
+      },
+      | "StartTransaction" => self.start_transaction(charge_point, parse_payload(payload)?),
+      | "MeterValues" => self.meter_values(charge_point, parse_payload(payload)?),
+      | "StopTransaction" => self.stop_transaction(charge_point, parse_payload(payload)?),
+      | _ => Err(CallError::new(CallErrorCode::NotImplemented, format!("action {action} is not implemented"))),
+    }
+  }
+
+  /// 取引IDとセッションIDの対応表を返す。
+  #[must_use]
+  pub fn transactions(&self) -> &TransactionIdMap {
+    &self.transactions
+  }
+
+  /// 進行中の取引の最新の見込み請求を返す。
+  #[must_use]
+  pub fn snapshot(&self, transaction_id: OcppTransactionId) -> Option<&SessionBill> {
+    self.snapshots.get(&transaction_id)
+  }
+
+  /// 終了した取引の停止済みセッションを返す。
+  #[must_use]
+  pub fn closed_session(&self, transaction_id: OcppTransactionId) -> Option<&ClosedSession> {
+    self.closed.get(&transaction_id)
+  }
+
+  /// 請求を確定できずに終了した取引を返す。
+  #[must_use]
+  pub fn unbilled_transaction(&self, transaction_id: OcppTransactionId) -> Option<&UnbilledTransaction> {
+    self.unbilled.get(&transaction_id)
+  }
+
+  /// 進行中の取引の件数を返す。
+  #[must_use]
+  pub fn open_transactions(&self) -> usize {
+    self.open.len()
+  }
+
+  fn start_transaction(&mut self, charge_point: &str, request: StartTransactionRequest) -> Result<Value, CallError> {
+    self.require_boot(charge_point)?;
+    let id_tag_info = self.id_tag_info(&request.id_tag);
+    if !self.accepted_tags.contains(&request.id_tag) {
+      // transactionId は必須項目のため、取引を開始しない場合は 0 を返す
+      return Ok(json!({ "transactionId": 0, "idTagInfo": id_tag_info }));
+    }
+    let connector = ConnectorId::new(request.connector_id);
+    if self.open.values().any(|open| open.charge_point == charge_point && open.connector == connector) {
+      return Err(CallError::new(
+        CallErrorCode::GenericError,
+        format!("connector {} already has an open transaction", request.connector_id),
+      ));
+    }
+
+    let started_at = parse_timestamp(&request.timestamp)?;
+    let active = ActiveSession::new(self.ids.next_id(), started_at, self.tariff.clone(), self.policy);
+    let session = MeteredSession::start(active, self.register, MeterReading::new(request.meter_start))?;
+    let transaction_id = self.transactions.assign(session.identity())?;
+    self.open.insert(transaction_id, OpenTransaction { charge_point: charge_point.to_owned(), connector, session });
+    Ok(json!({ "transactionId": i32::from(transaction_id), "idTagInfo": id_tag_info }))
+  }
+
+  fn meter_values(&mut self, charge_point: &str, request: MeterValuesRequest) -> Result<Value, CallError> {
+    self.require_boot(charge_point)?;
+    // 取引に紐付かない計測値（待機中のコネクタなど）は課金に使わない
+    let Some(transaction_id) = request.transaction_id.map(OcppTransactionId::new) else { return Ok(json!({})) };
+    let open = self.open_transaction(charge_point, transaction_id)?;
+
+    let mut latest = None;
+    for meter_value in &request.meter_value {
+      let at = parse_timestamp(&meter_value.timestamp)?;
+      // 相ごとの値は一部の相しか含まないため、相の指定がない合計値だけを使う
+      for sampled in &meter_value.sampled_value {
+        if sampled.measurand.as_deref().unwrap_or(ENERGY_REGISTER) == ENERGY_REGISTER && sampled.phase.is_none() {
+          let reading = MeterReading::parse_in(&sampled.value, sampled.unit.as_deref().unwrap_or("Wh"))?;
+          latest = Some((at, reading));
+        }
+      }
+    }
+    if let Some((at, reading)) = latest {
+      let bill = open.session.bill_snapshot(at, reading)?;
+      self.snapshots.insert(transaction_id, bill);
+    }
+    Ok(json!({}))
+  }
+
+  fn stop_transaction(&mut self, charge_point: &str, request: StopTransactionRequest) -> Result<Value, CallError> {
+    self.require_boot(charge_point)?;
+    let transaction_id = OcppTransactionId::new(request.transaction_id);
+    let ended_at = parse_timestamp(&request.timestamp)?;
+    // 応答を受け取れなかった充電器の再送には、閉じた取引をそのまま受け付け済みとして返す
+    if self.closed.contains_key(&transaction_id) || self.unbilled.contains_key(&transaction_id) {
+      return Ok(json!({ "idTagInfo": { "status": "Accepted" } }));
+    }
+    let session = self.open_transaction(charge_point, transaction_id)?.session.clone();
+    self.open.remove(&transaction_id);
+    self.snapshots.remove(&transaction_id);
+
+    // 拒否すると充電器が再送を続けるため、停止に失敗しても取引は閉じて受け付ける
+    let meter_stop = MeterReading::new(request.meter_stop);
+    match session.clone().stop(ended_at, meter_stop) {
+      | Ok(closed) => {
+        self.closed.insert(transaction_id, closed);
+      },
+      | Err(error) => {
+        self.unbilled.insert(transaction_id, UnbilledTransaction { session, ended_at, meter_stop, error });
+      },
+    }
+    Ok(json!({ "idTagInfo": { "status": "Accepted" } }))
+  }
+
+  fn open_transaction(
+    &self,
+    charge_point: &str,
+    transaction_id: OcppTransactionId,
+  ) -> Result<&OpenTransaction, CallError> {
+    self.open.get(&transaction_id).filter(|open| open.charge_point == charge_point).ok_or_else(|| {
+      CallError::new(
+        CallErrorCode::PropertyConstraintViolation,
+        format!("transaction {} is not open on this charge point", i32::from(transaction_id)),
+      )
+    })
+  }
+
+  fn require_boot(&self, charge_point: &str) -> Result<(), CallError> {
+    if self.booted.contains(charge_point) {
+      return Ok(());
+    }
+    Err(CallError::new(CallErrorCode::SecurityError, "BootNotification has not been accepted"))
+  }
+
+  fn id_tag_info(&self, id_tag: &str) -> Value {
+    let status = if self.accepted_tags.contains(id_tag) { "Accepted" } else { "Invalid" };
+    json!({ "status": status })
+  }
+}
+
+fn parse_payload<T: DeserializeOwned>(payload: Value) -> Result<T, CallError> {
+  serde_json::from_value(payload).map_err(|error| CallError::new(CallErrorCode::FormationViolation, error.to_string()))
+}
+
+/// OCPP の CiString の長さ制約を検証する。
+fn check_length(field: &str, value: &str, max: usize) -> Result<(), CallError> {
+  if value.chars().count() <= max {
+    return Ok(());
+  }
+  Err(CallError::new(CallErrorCode::PropertyConstraintViolation, format!("{field} exceeds {max} characters")))
+}
+
+fn parse_timestamp(value: &str) -> Result<OffsetDateTime, CallError> {
+  OffsetDateTime::parse(value, &Rfc3339).map_err(|_| {
+    CallError::new(CallErrorCode::PropertyConstraintViolation, format!("timestamp {value:?} is not RFC 3339"))
+  })
+}
+
+fn now() -> String {
+  OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default()
+}
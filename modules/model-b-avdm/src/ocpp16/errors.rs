@@ -0,0 +1,91 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::session::{Locale, SessionValueError};
+
+/// OCPP-J の CALLERROR で返すエラーコード（OCPP 1.6 で定義されたもの）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallErrorCode {
+  /// 要求されたアクションを実装していない。
+  NotImplemented,
+  /// 要求されたアクションは認識したが対応していない。
+  NotSupported,
+  /// 処理中に内部エラーが発生した。
+  InternalError,
+  /// 状態の不整合などで処理できなかった。
+  GenericError,
+  /// セキュリティ上の理由で処理できなかった。
+  SecurityError,
+  /// メッセージの構造が不正だった。
+  FormationViolation,
+  /// 項目の値が制約に違反していた。
+  PropertyConstraintViolation,
+  /// 項目の型が不正だった。
+  TypeConstraintViolation,
+}
+
+impl CallErrorCode {
+  /// OCPP-J の文字列表現を返す。
+  #[must_use]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      | Self::NotImplemented => "NotImplemented",
+      | Self::NotSupported => "NotSupported",
+      | Self::InternalError => "InternalError",
+      | Self::GenericError => "GenericError",
+      | Self::SecurityError => "SecurityError",
+      | Self::FormationViolation => "FormationViolation",
+      | Self::PropertyConstraintViolation => "PropertyConstraintViolation",
+      | Self::TypeConstraintViolation => "TypeConstraintViolation",
+    }
+  }
+
+  /// OCPP-J の文字列表現から解釈する。
+  #[must_use]
+  pub fn parse(code: &str) -> Option<Self> {
+    [
+      Self::NotImplemented,
+      Self::NotSupported,
+      Self::InternalError,
+      Self::GenericError,
+      Self::SecurityError,
+      Self::FormationViolation,
+      Self::PropertyConstraintViolation,
+      Self::TypeConstraintViolation,
+    ]
+    .into_iter()
+    .find(|candidate| candidate.as_str() == code)
+  }
+}
+
+impl fmt::Display for CallErrorCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// 充電器からの要求を処理できなかったことを表すエラー。CALLERROR として返す。
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{code}: {description}")]
+pub struct CallError {
+  /// エラーコード。
+  pub code:        CallErrorCode,
+  /// 充電器向けの説明（英語）。
+  pub description: String,
+}
+
+impl CallError {
+  /// エラーコードと説明から生成する。
+  #[must_use]
+  pub fn new(code: CallErrorCode, description: impl Into<String>) -> Self {
+    Self { code, description: description.into() }
+  }
+}
+
+/// ドメインエラーは値の制約違反として返す。説明は充電器のログ向けに英語にする。
+impl From<SessionValueError> for CallError {
+  fn from(error: SessionValueError) -> Self {
+    Self::new(CallErrorCode::PropertyConstraintViolation, format!("{}: {}", error.code(), error.message(Locale::EnUs)))
+  }
+}
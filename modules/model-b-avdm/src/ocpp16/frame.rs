@@ -0,0 +1,73 @@
+use serde_json::{Value, json};
+
+use super::errors::{CallError, CallErrorCode};
+
+const CALL: u64 = 2;
+const CALL_RESULT: u64 = 3;
+const CALL_ERROR: u64 = 4;
+
+/// OCPP-J の 1 メッセージ（JSON 配列）。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+  /// 要求 `[2, id, action, payload]`。
+  Call {
+    /// メッセージID。
+    id:      String,
+    /// アクション名。
+    action:  String,
+    /// 要求本体。
+    payload: Value,
+  },
+  /// 成功応答 `[3, id, payload]`。
+  CallResult {
+    /// 対応する要求のメッセージID。
+    id:      String,
+    /// 応答本体。
+    payload: Value,
+  },
+  /// エラー応答 `[4, id, code, description, details]`。
+  CallError {
+    /// 対応する要求のメッセージID。
+    id:    String,
+    /// エラー内容。
+    error: CallError,
+  },
+}
+
+impl Frame {
+  /// テキストメッセージを解析する。
+  ///
+  /// # Errors
+  /// OCPP-J の配列形式でない場合、`CallErrorCode::FormationViolation` の `CallError` を返します。
+  pub fn parse(text: &str) -> Result<Self, CallError> {
+    let violation = |reason: &str| CallError::new(CallErrorCode::FormationViolation, reason);
+    let value: Value = serde_json::from_str(text).map_err(|_| violation("message is not valid JSON"))?;
+    let items = value.as_array().ok_or_else(|| violation("message must be a JSON array"))?;
+    let id = || items.get(1).and_then(Value::as_str).map(str::to_owned).ok_or_else(|| violation("missing message id"));
+    match (items.first().and_then(Value::as_u64), items.len()) {
+      | (Some(CALL), 4) => Ok(Self::Call {
+        id:      id()?,
+        action:  items[2].as_str().ok_or_else(|| violation("action must be a string"))?.to_owned(),
+        payload: items[3].clone(),
+      }),
+      | (Some(CALL_RESULT), 3) => Ok(Self::CallResult { id: id()?, payload: items[2].clone() }),
+      | (Some(CALL_ERROR), 5) => {
+        let code = items[2].as_str().and_then(CallErrorCode::parse).unwrap_or(CallErrorCode::GenericError);
+        let description = items[3].as_str().unwrap_or_default();
+        Ok(Self::CallError { id: id()?, error: CallError::new(code, description) })
+      },
+      | _ => Err(violation("unknown message type or length")),
+    }
+  }
+
+  /// テキストメッセージに変換する。
+  #[must_use]
+  pub fn to_text(&self) -> String {
+    match self {
+      | Self::Call { id, action, payload } => json!([CALL, id, action, payload]),
+      | Self::CallResult { id, payload } => json!([CALL_RESULT, id, payload]),
+      | Self::CallError { id, error } => json!([CALL_ERROR, id, error.code.as_str(), error.description, {}]),
+    }
+    .to_string()
+  }
+}
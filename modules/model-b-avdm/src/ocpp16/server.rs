@@ -0,0 +1,121 @@
+use std::{
+  io,
+  net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+  sync::{Arc, Mutex, PoisonError},
+  thread,
+};
+
+use tungstenite::{
+  Message,
+  handshake::server::{ErrorResponse, Request, Response},
+  http::{HeaderValue, StatusCode},
+};
+
+use super::{central_system::CentralSystem, frame::Frame};
+use crate::session::IdGenerator;
+
+/// OCPP 1.6J の WebSocket サブプロトコル名。
+pub const SUBPROTOCOL: &str = "ocpp1.6";
+
+/// `CentralSystem` を WebSocket（`ws://host:port/<charge point id>`）で公開するサーバ。
+///
+/// 充電器ごとに 1 スレッドで接続を処理し、要求は共有の `CentralSystem` で直列に処理する。
+#[derive(Debug)]
+pub struct CentralSystemServer<G: IdGenerator> {
+  listener: TcpListener,
+  system:   Arc<Mutex<CentralSystem<G>>>,
+}
+
+impl<G: IdGenerator + Send + 'static> CentralSystemServer<G> {
+  /// 指定アドレスで待ち受けを開始する。
+  ///
+  /// # Errors
+  /// 待ち受けに失敗した場合、その `io::Error` を返します。
+  pub fn bind(address: impl ToSocketAddrs, system: CentralSystem<G>) -> io::Result<Self> {
+    Ok(Self { listener: TcpListener::bind(address)?, system: Arc::new(Mutex::new(system)) })
+  }
+
+  /// 待ち受けているアドレスを返す。
+  ///
+  /// # Errors
+  /// アドレスを取得できない場合、その `io::Error` を返します。
+  pub fn local_addr(&self) -> io::Result<SocketAddr> {
+    self.listener.local_addr()
+  }
+
+  /// 共有している中央システムを返す。
+  #[must_use]
+  pub fn system(&self) -> Arc<Mutex<CentralSystem<G>>> {
+    Arc::clone(&self.system)
+  }
+
+  /// 接続を受け付け続け、充電器ごとにスレッドを起動する。
+  ///
+  /// # Errors
+  /// 接続の受け付けに失敗した場合、その `io::Error` を返します。
+  pub fn run(&self) -> io::Result<()> {
+    loop {
+      let (stream, _) = self.listener.accept()?;
+      let system = self.system();
+      thread::spawn(move || serve(stream, &system));
+    }
+  }
+
+  /// 接続を 1 件だけ受け付け、切断されるまで処理する。
+  ///
+  /// # Errors
+  /// 接続の受け付けに失敗した場合、その `io::Error` を返します。
+  pub fn serve_one(&self) -> io::Result<()> {
+    let (stream, _) = self.listener.accept()?;
+    serve(stream, &self.system);
+    Ok(())
+  }
+}
+
+/// 1 台の充電器との接続を処理する。通信エラーや切断で終了する。
+// ハンドシェイクのコールバックの戻り値型は tungstenite が定めている
+#[allow(clippy::result_large_err)]
+fn serve<G: IdGenerator>(stream: TcpStream, system: &Mutex<CentralSystem<G>>) {
+  let mut charge_point = String::new();
+  let handshake = tungstenite::accept_hdr(stream, |request: &Request, mut response: Response| {
+    charge_point = request.uri().path().trim_matches('/').rsplit('/').next().unwrap_or_default().to_owned();
+    let offers_ocpp16 = request
+      .headers()
+      .get_all("Sec-WebSocket-Protocol")
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .flat_map(|value| value.split(','))
+      .any(|protocol| protocol.trim() == SUBPROTOCOL);
+    if charge_point.is_empty() || !offers_ocpp16 {
+      let mut rejection = ErrorResponse::new(Some("charge point id and ocpp1.6 subprotocol are required".to_owned()));
+      *rejection.status_mut() = StatusCode::BAD_REQUEST;
+      return Err(rejection);
+    }
+    response.headers_mut().insert("Sec-WebSocket-Protocol", HeaderValue::from_static(SUBPROTOCOL));
+    Ok(response)
+  });
+  let Ok(mut socket) = handshake else { return };
+
+  while let Ok(message) = socket.read() {
+    let text = match message {
+      | Message::Text(text) => text,
+      | Message::Close(_) => break,
+      | _ => continue,
+    };
+    let reply = match Frame::parse(&text) {
+      | Ok(Frame::Call { id, action, payload }) => {
+        let result = system.lock().unwrap_or_else(PoisonError::into_inner).handle(&charge_point, &action, payload);
+        match result {
+          | Ok(payload) => Frame::CallResult { id, payload },
+          | Err(error) => Frame::CallError { id, error },
+        }
+      },
+      // 中央システムからは要求を送らないため、応答は読み捨てる
+      | Ok(Frame::CallResult { .. } | Frame::CallError { .. }) => continue,
+      | Err(error) => Frame::CallError { id: String::new(), error },
+    };
+    if socket.send(Message::text(reply.to_text())).is_err() {
+      break;
+    }
+  }
+}
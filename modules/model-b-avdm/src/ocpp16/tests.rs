@@ -0,0 +1,252 @@
+use std::{
+  net::TcpStream,
+  sync::{Arc, Mutex},
+  thread::{self, JoinHandle},
+};
+
+use serde_json::{Value, json};
+use tungstenite::{Message, WebSocket, client::IntoClientRequest, stream::MaybeTlsStream};
+
+use super::{CallError, CallErrorCode, CentralSystem, CentralSystemServer, Frame, SUBPROTOCOL};
+use crate::session::{
+  BillingPolicy, KwhMilli, MeterReading, OcppTransactionId, RateYenPerKwh, SequentialIdGenerator, SessionValueError,
+  Tariff,
+};
+
+/// スクリプトどおりに要求を送る模擬充電器。
+struct ChargePoint {
+  socket:  WebSocket<MaybeTlsStream<TcpStream>>,
+  next_id: u32,
+}
+
+impl ChargePoint {
+  fn connect(url: &str) -> Self {
+    let mut request = url.into_client_request().unwrap();
+    request.headers_mut().insert("Sec-WebSocket-Protocol", SUBPROTOCOL.parse().unwrap());
+    let (socket, response) = tungstenite::connect(request).unwrap();
+    assert_eq!(response.headers()["Sec-WebSocket-Protocol"], SUBPROTOCOL);
+    Self { socket, next_id: 0 }
+  }
+
+  fn call(&mut self, action: &str, payload: Value) -> Result<Value, CallError> {
+    self.next_id += 1;
+    let id = self.next_id.to_string();
+    let frame = Frame::Call { id: id.clone(), action: action.to_owned(), payload };
+    self.socket.send(Message::text(frame.to_text())).unwrap();
+    loop {
+      if let Message::Text(text) = self.socket.read().unwrap() {
+        return match Frame::parse(&text).unwrap() {
+          | Frame::CallResult { id: reply, payload } if reply == id => Ok(payload),
+          | Frame::CallError { id: reply, error } if reply == id => Err(error),
+          | other => panic!("unexpected frame: {other:?}"),
+        };
+      }
+    }
+  }
+
+  fn boot(&mut self) {
+    let boot = self.call("BootNotification", json!({ "chargePointVendor": "ACME", "chargePointModel": "DC50" }));
+    assert_eq!(boot.unwrap()["status"], "Accepted");
+  }
+}
+
+fn central_system() -> CentralSystem<SequentialIdGenerator> {
+  CentralSystem::new(
+    Tariff::flat(RateYenPerKwh::try_new(50).unwrap()),
+    BillingPolicy::default(),
+    SequentialIdGenerator::new(),
+  )
+  .with_id_tag("TAG-001")
+}
+
+/// ローカルでサーバを起動し、1 台分の接続を処理するスレッドを返す。
+fn start_server() -> (String, Arc<Mutex<CentralSystem<SequentialIdGenerator>>>, JoinHandle<()>) {
+  let server = CentralSystemServer::bind("127.0.0.1:0", central_system()).unwrap();
+  let url = format!("ws://{}/ocpp/CP-001", server.local_addr().unwrap());
+  let system = server.system();
+  let handle = thread::spawn(move || server.serve_one().unwrap());
+  (url, system, handle)
+}
+
+// ========================================
+// エンドツーエンド
+// ========================================
+
+#[test]
+fn scripted_charge_point_completes_a_billed_transaction() {
+  let (url, system, server) = start_server();
+  let mut charge_point = ChargePoint::connect(&url);
+
+  charge_point.boot();
+  let authorize = charge_point.call("Authorize", json!({ "idTag": "TAG-001" })).unwrap();
+  assert_eq!(authorize["idTagInfo"]["status"], "Accepted");
+
+  let started = charge_point
+    .call(
+      "StartTransaction",
+      json!({ "connectorId": 1, "idTag": "TAG-001", "meterStart": 120_000, "timestamp": "2025-04-01T10:00:00Z" }),
+    )
+    .unwrap();
+  assert_eq!(started["transactionId"], 1);
+  let transaction_id = OcppTransactionId::new(1);
+
+  let meter_values = json!({
+    "connectorId": 1,
+    "transactionId": 1,
+    "meterValue": [{
+      "timestamp": "2025-04-01T10:20:00Z",
+      "sampledValue": [
+        { "value": "125.5", "measurand": "Energy.Active.Import.Register", "unit": "kWh" },
+        { "value": "48000", "measurand": "Power.Active.Import", "unit": "W" }
+      ]
+    }]
+  });
+  assert_eq!(charge_point.call("MeterValues", meter_values).unwrap(), json!({}));
+  {
+    let system = system.lock().unwrap();
+    let snapshot = system.snapshot(transaction_id).unwrap();
+    assert_eq!(snapshot.total_energy(), KwhMilli::try_new(5_500).unwrap());
+    assert_eq!(system.open_transactions(), 1);
+  }
+
+  let stop = json!({ "transactionId": 1, "meterStop": 130_000, "timestamp": "2025-04-01T10:40:00Z" });
+  let stopped = charge_point.call("StopTransaction", stop.clone()).unwrap();
+  assert_eq!(stopped["idTagInfo"]["status"], "Accepted");
+  // 応答を受け取れなかった充電器の再送も受け付け、請求は重複させない
+  let retransmitted = charge_point.call("StopTransaction", stop).unwrap();
+  assert_eq!(retransmitted["idTagInfo"]["status"], "Accepted");
+
+  charge_point.socket.close(None).unwrap();
+  server.join().unwrap();
+  let system = system.lock().unwrap();
+  let closed = system.closed_session(transaction_id).unwrap();
+  assert_eq!(closed.bill().total_energy(), KwhMilli::try_new(10_000).unwrap());
+  assert_eq!(system.transactions().session_for(transaction_id), Some(closed.identity()));
+  assert_eq!(system.open_transactions(), 0);
+}
+
+#[test]
+fn per_phase_register_values_are_not_billed() {
+  let (url, system, server) = start_server();
+  let mut charge_point = ChargePoint::connect(&url);
+
+  charge_point.boot();
+  charge_point
+    .call(
+      "StartTransaction",
+      json!({ "connectorId": 1, "idTag": "TAG-001", "meterStart": 120_000, "timestamp": "2025-04-01T10:00:00Z" }),
+    )
+    .unwrap();
+  let meter_values = json!({
+    "connectorId": 1,
+    "transactionId": 1,
+    "meterValue": [{
+      "timestamp": "2025-04-01T10:20:00Z",
+      "sampledValue": [
+        { "value": "125.5", "measurand": "Energy.Active.Import.Register", "unit": "kWh" },
+        { "value": "41800", "measurand": "Energy.Active.Import.Register", "phase": "L1", "unit": "Wh" }
+      ]
+    }]
+  });
+  assert_eq!(charge_point.call("MeterValues", meter_values).unwrap(), json!({}));
+
+  charge_point.socket.close(None).unwrap();
+  server.join().unwrap();
+  let system = system.lock().unwrap();
+  let snapshot = system.snapshot(OcppTransactionId::new(1)).unwrap();
+  assert_eq!(snapshot.total_energy(), KwhMilli::try_new(5_500).unwrap());
+}
+
+#[test]
+fn invalid_requests_are_answered_with_call_errors() {
+  let (url, _system, server) = start_server();
+  let mut charge_point = ChargePoint::connect(&url);
+
+  let before_boot = charge_point.call("Authorize", json!({ "idTag": "TAG-001" })).unwrap_err();
+  assert_eq!(before_boot.code, CallErrorCode::SecurityError);
+
+  charge_point.boot();
+  assert_eq!(charge_point.call("DataTransfer", json!({})).unwrap_err().code, CallErrorCode::NotImplemented);
+  assert_eq!(
+    charge_point.call("StartTransaction", json!({ "idTag": 1 })).unwrap_err().code,
+    CallErrorCode::FormationViolation
+  );
+
+  let unknown_tag = charge_point
+    .call(
+      "StartTransaction",
+      json!({ "connectorId": 1, "idTag": "UNKNOWN", "meterStart": 0, "timestamp": "2025-04-01T10:00:00Z" }),
+    )
+    .unwrap();
+  assert_eq!(unknown_tag, json!({ "transactionId": 0, "idTagInfo": { "status": "Invalid" } }));
+
+  charge_point
+    .call(
+      "StartTransaction",
+      json!({ "connectorId": 1, "idTag": "TAG-001", "meterStart": 5_000, "timestamp": "2025-04-01T10:00:00Z" }),
+    )
+    .unwrap();
+  let unknown_transaction = charge_point
+    .call("StopTransaction", json!({ "transactionId": 9, "meterStop": 6_000, "timestamp": "2025-04-01T10:30:00Z" }))
+    .unwrap_err();
+  assert_eq!(unknown_transaction.code, CallErrorCode::PropertyConstraintViolation);
+
+  charge_point.socket.close(None).unwrap();
+  server.join().unwrap();
+}
+
+#[test]
+fn failed_stops_close_the_transaction_as_unbilled() {
+  let (url, system, server) = start_server();
+  let mut charge_point = ChargePoint::connect(&url);
+
+  charge_point.boot();
+  charge_point
+    .call(
+      "StartTransaction",
+      json!({ "connectorId": 1, "idTag": "TAG-001", "meterStart": 5_000, "timestamp": "2025-04-01T10:00:00Z" }),
+    )
+    .unwrap();
+  let stop = json!({ "transactionId": 1, "meterStop": 4_000, "timestamp": "2025-04-01T10:30:00Z" });
+  let decreased = charge_point.call("StopTransaction", stop.clone()).unwrap();
+  assert_eq!(decreased["idTagInfo"]["status"], "Accepted");
+  // 請求できなかった取引への再送も受け付け済みとして返す
+  let retransmitted = charge_point.call("StopTransaction", stop).unwrap();
+  assert_eq!(retransmitted["idTagInfo"]["status"], "Accepted");
+
+  charge_point.socket.close(None).unwrap();
+  server.join().unwrap();
+  let system = system.lock().unwrap();
+  let transaction_id = OcppTransactionId::new(1);
+  let unbilled = system.unbilled_transaction(transaction_id).unwrap();
+  assert_eq!(unbilled.meter_stop(), MeterReading::new(4_000));
+  assert_eq!(unbilled.error(), &SessionValueError::MeterRegisterDecreased { start: 5_000, stop: 4_000 });
+  assert_eq!(system.closed_session(transaction_id), None);
+  assert_eq!(system.open_transactions(), 0);
+}
+
+#[test]
+fn handshake_requires_the_ocpp16_subprotocol() {
+  let server = CentralSystemServer::bind("127.0.0.1:0", central_system()).unwrap();
+  let url = format!("ws://{}/ocpp/CP-001", server.local_addr().unwrap());
+  let handle = thread::spawn(move || server.serve_one().unwrap());
+
+  assert!(tungstenite::connect(url).is_err());
+  handle.join().unwrap();
+}
+
+// ========================================
+// メッセージ形式
+// ========================================
+
+#[test]
+fn frames_round_trip_through_ocpp_j_arrays() {
+  let call = Frame::parse(r#"[2,"19223201","Heartbeat",{}]"#).unwrap();
+  assert_eq!(call, Frame::Call { id: "19223201".to_owned(), action: "Heartbeat".to_owned(), payload: json!({}) });
+  assert_eq!(Frame::parse(&call.to_text()).unwrap(), call);
+
+  let error = Frame::CallError { id: "1".to_owned(), error: CallError::new(CallErrorCode::NotImplemented, "nope") };
+  assert_eq!(error.to_text(), r#"[4,"1","NotImplemented","nope",{}]"#);
+  assert_eq!(Frame::parse(r#"{"not":"array"}"#).unwrap_err().code, CallErrorCode::FormationViolation);
+  assert_eq!(Frame::parse(r#"[9,"1"]"#).unwrap_err().code, CallErrorCode::FormationViolation);
+}
@@ -0,0 +1,11 @@
+mod errors;
+mod event;
+mod importer;
+mod report;
+
+pub use errors::ImportError;
+pub use importer::TransactionEventImporter;
+pub use report::{BilledTransaction, ImportReport, UnbilledReason, UnbilledTransaction, UnparseableEntry};
+
+#[cfg(test)]
+mod tests;
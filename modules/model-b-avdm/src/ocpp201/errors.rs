@@ -0,0 +1,13 @@
+use std::io;
+
+use thiserror::Error;
+
+/// ログ全体を読み込めなかったことを表すエラー。
+///
+/// 解釈できない行や個々の取引を課金できなかった理由はエラーにせず、`ImportReport` に記録する。
+#[derive(Debug, Error)]
+pub enum ImportError {
+  /// ファイルを読み込めなかった。
+  #[error("ログを読み込めません: {0}")]
+  Io(#[from] io::Error),
+}
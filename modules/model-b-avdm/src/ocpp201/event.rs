@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use serde_json::{Number, Value};
+
+use super::report::UnparseableEntry;
+
+/// OCPP 2.0.1 TransactionEventRequest のうち、課金に使う項目。
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionEvent {
+  pub(crate) event_type:       EventType,
+  pub(crate) timestamp:        String,
+  #[serde(default)]
+  pub(crate) seq_no:           Option<u64>,
+  pub(crate) transaction_info: TransactionInfo,
+  #[serde(default)]
+  pub(crate) meter_value:      Vec<MeterValue>,
+}
+
+/// 取引イベントの種別。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub(crate) enum EventType {
+  Started,
+  Updated,
+  Ended,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionInfo {
+  pub(crate) transaction_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MeterValue {
+  pub(crate) sampled_value: Vec<SampledValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SampledValue {
+  pub(crate) value:           Number,
+  #[serde(default)]
+  pub(crate) measurand:       Option<String>,
+  #[serde(default)]
+  pub(crate) phase:           Option<String>,
+  #[serde(default)]
+  pub(crate) context:         Option<String>,
+  #[serde(default)]
+  pub(crate) unit_of_measure: Option<UnitOfMeasure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct UnitOfMeasure {
+  #[serde(default)]
+  pub(crate) unit:       Option<String>,
+  #[serde(default)]
+  pub(crate) multiplier: Option<i32>,
+}
+
+/// ログを TransactionEvent の並びとして解釈する。
+///
+/// JSON 配列・1 行 1 イベントの JSON Lines のどちらも受け付け、各要素は
+/// リクエスト本体か OCPP-J の CALL（`[2, id, "TransactionEvent", {...}]`）のいずれでもよい。
+/// 解釈できない行は読み飛ばし、行番号（JSON 配列の場合は要素番号）とともに返す。
+pub(crate) fn parse_log(log: &str) -> (Vec<TransactionEvent>, Vec<UnparseableEntry>) {
+  let mut unparseable = Vec::new();
+  let values: Vec<(usize, Value)> = match serde_json::from_str::<Value>(log.trim()) {
+    | Ok(Value::Array(items)) if items.iter().all(|item| !item.is_number()) => {
+      items.into_iter().enumerate().map(|(index, item)| (index + 1, item)).collect()
+    },
+    | Ok(single @ Value::Object(_)) => vec![(1, single)],
+    | _ => log
+      .lines()
+      .enumerate()
+      .filter(|(_, line)| !line.trim().is_empty())
+      .filter_map(|(index, line)| match serde_json::from_str(line) {
+        | Ok(value) => Some((index + 1, value)),
+        | Err(error) => {
+          unparseable.push(UnparseableEntry { line: index + 1, reason: error.to_string() });
+          None
+        },
+      })
+      .collect(),
+  };
+
+  let mut events = Vec::new();
+  for (line, value) in values {
+    let request = match value {
+      | Value::Array(mut frame) if frame.len() == 4 && frame[2] == "TransactionEvent" => frame.swap_remove(3),
+      | other => other,
+    };
+    match serde_json::from_value(request) {
+      | Ok(event) => events.push(event),
+      | Err(error) => unparseable.push(UnparseableEntry { line, reason: error.to_string() }),
+    }
+  }
+  unparseable.sort_by_key(|entry| entry.line);
+  (events, unparseable)
+}
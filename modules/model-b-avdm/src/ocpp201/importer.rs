@@ -0,0 +1,141 @@
+use std::{fs, path::Path};
+
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use super::{
+  errors::ImportError,
+  event::{EventType, SampledValue, TransactionEvent, parse_log},
+  report::{BilledTransaction, ImportReport, UnbilledReason, UnbilledTransaction},
+};
+use crate::session::{
+  ActiveSession, BillingPolicy, ClosedSession, IdGenerator, MeterReading, MeterRegister, SessionValueError, Tariff,
+};
+
+/// 既定の計測項目。`measurand` 省略時はこの値とみなす。
+const ENERGY_REGISTER: &str = "Energy.Active.Import.Register";
+/// 取引開始時の計測値を表す `context`。
+const TRANSACTION_BEGIN: &str = "Transaction.Begin";
+/// 取引終了時の計測値を表す `context`。
+const TRANSACTION_END: &str = "Transaction.End";
+
+/// OCPP 2.0.1 の TransactionEvent ログから取引を再構成し、請求を確定させる。
+#[derive(Debug, Clone)]
+pub struct TransactionEventImporter<G: IdGenerator> {
+  tariff: Tariff,
+  policy: BillingPolicy,
+  ids:    G,
+}
+
+impl<G: IdGenerator> TransactionEventImporter<G> {
+  /// 料金表・課金方針とセッションIDの払い出し方を指定して生成する。
+  #[must_use]
+  pub fn new(tariff: Tariff, policy: BillingPolicy, ids: G) -> Self {
+    Self { tariff, policy, ids }
+  }
+
+  /// ログファイルを読み込んで取り込む。
+  ///
+  /// # Errors
+  /// ファイルを読み込めない場合、`ImportError::Io` を返します。
+  pub fn import_file(&mut self, path: impl AsRef<Path>) -> Result<ImportReport, ImportError> {
+    Ok(self.import_str(&fs::read_to_string(path)?))
+  }
+
+  /// ログ文字列を取り込み、取引ごとの請求と課金できなかった取引の一覧を返す。
+  ///
+  /// TransactionEvent として解釈できない行は読み飛ばし、`ImportReport::unparseable` に記録する。
+  pub fn import_str(&mut self, log: &str) -> ImportReport {
+    let (events, unparseable) = parse_log(log);
+    let mut transactions: Vec<(String, Vec<TransactionEvent>)> = Vec::new();
+    for event in events {
+      let transaction_id = &event.transaction_info.transaction_id;
+      match transactions.iter_mut().find(|(id, _)| id == transaction_id) {
+        | Some((_, events)) => events.push(event),
+        | None => transactions.push((transaction_id.clone(), vec![event])),
+      }
+    }
+
+    let mut report = ImportReport { unparseable, ..ImportReport::default() };
+    for (transaction_id, mut events) in transactions {
+      events.sort_by_key(|event| event.seq_no);
+      match self.bill_transaction(&events) {
+        | Ok(session) => report.billed.push(BilledTransaction { transaction_id, session }),
+        | Err(reason) => report.unbilled.push(UnbilledTransaction { transaction_id, reason }),
+      }
+    }
+    report
+  }
+
+  fn bill_transaction(&mut self, events: &[TransactionEvent]) -> Result<ClosedSession, UnbilledReason> {
+    let started = single_event(events, EventType::Started, "Started")?.ok_or(UnbilledReason::MissingStarted)?;
+    let ended = single_event(events, EventType::Ended, "Ended")?.ok_or(UnbilledReason::MissingEnded)?;
+    let started_at = parse_timestamp(&started.timestamp)?;
+    let ended_at = parse_timestamp(&ended.timestamp)?;
+    if ended_at <= started_at {
+      return Err(UnbilledReason::ReversedTimestamps { started_at, ended_at });
+    }
+
+    // 相別の値は合計の指示値ではないため使わない
+    let samples: Vec<&SampledValue> = events
+      .iter()
+      .flat_map(|event| &event.meter_value)
+      .flat_map(|meter| &meter.sampled_value)
+      .filter(|sampled| {
+        sampled.measurand.as_deref().unwrap_or(ENERGY_REGISTER) == ENERGY_REGISTER && sampled.phase.is_none()
+      })
+      .collect();
+    let [first, .., last] = samples[..] else { return Err(UnbilledReason::MissingMeterValues) };
+    // 取引の開始・終了時に計測した値があればそれを優先する
+    let first = samples.iter().find(|sampled| in_context(sampled, TRANSACTION_BEGIN)).map_or(first, |sampled| sampled);
+    let last = samples.iter().rfind(|sampled| in_context(sampled, TRANSACTION_END)).map_or(last, |sampled| sampled);
+    let energy =
+      MeterRegister::monotonic().energy_between(energy_reading(first)?, energy_reading(last)?).map_err(|error| {
+        match error {
+          | SessionValueError::MeterRegisterDecreased { start, stop } => {
+            UnbilledReason::NegativeEnergy { start_wh: start, stop_wh: stop }
+          },
+          | other => UnbilledReason::Rejected(other),
+        }
+      })?;
+
+    ActiveSession::new(self.ids.next_id(), started_at, self.tariff.clone(), self.policy)
+      .stop(ended_at, energy)
+      .map_err(UnbilledReason::Rejected)
+  }
+}
+
+fn single_event<'a>(
+  events: &'a [TransactionEvent],
+  event_type: EventType,
+  name: &'static str,
+) -> Result<Option<&'a TransactionEvent>, UnbilledReason> {
+  let mut matching = events.iter().filter(|event| event.event_type == event_type);
+  let first = matching.next();
+  if matching.next().is_some() {
+    return Err(UnbilledReason::DuplicateEvent { event_type: name });
+  }
+  Ok(first)
+}
+
+fn in_context(sampled: &SampledValue, context: &str) -> bool {
+  sampled.context.as_deref() == Some(context)
+}
+
+fn parse_timestamp(value: &str) -> Result<OffsetDateTime, UnbilledReason> {
+  OffsetDateTime::parse(value, &Rfc3339).map_err(|_| UnbilledReason::InvalidValue { value: value.to_owned() })
+}
+
+/// `unitOfMeasure`（省略時は Wh、倍率 10^0）に従って指示値を解釈する。
+fn energy_reading(sampled: &SampledValue) -> Result<MeterReading, UnbilledReason> {
+  let value = sampled.value.to_string();
+  let unit = sampled.unit_of_measure.as_ref();
+  let unit_name = unit.and_then(|unit| unit.unit.as_deref()).unwrap_or("Wh");
+  let multiplier = unit.and_then(|unit| unit.multiplier).unwrap_or(0);
+  let invalid = || UnbilledReason::InvalidValue { value: format!("{value} {unit_name} (10^{multiplier})") };
+  let unit = match (unit_name, multiplier) {
+    | ("Wh", 0) => "Wh",
+    | ("Wh", 3) | ("kWh", 0) => "kWh",
+    | _ => return Err(invalid()),
+  };
+  MeterReading::parse_in(&value, unit).map_err(|_| invalid())
+}
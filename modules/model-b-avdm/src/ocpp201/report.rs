@@ -0,0 +1,134 @@
+use std::fmt;
+
+use time::OffsetDateTime;
+
+use crate::session::{ClosedSession, SessionBill, SessionValueError};
+
+/// 課金できた取引。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BilledTransaction {
+  pub(crate) transaction_id: String,
+  pub(crate) session:        ClosedSession,
+}
+
+impl BilledTransaction {
+  /// OCPP の取引IDを返す。
+  #[must_use]
+  pub fn transaction_id(&self) -> &str {
+    &self.transaction_id
+  }
+
+  /// 再構成した停止済みセッションを返す。
+  #[must_use]
+  pub fn session(&self) -> &ClosedSession {
+    &self.session
+  }
+
+  /// 確定した請求を返す。
+  #[must_use]
+  pub fn bill(&self) -> &SessionBill {
+    self.session.bill()
+  }
+}
+
+/// 取引を課金できなかった理由。
+#[derive(Debug, PartialEq, Eq)]
+pub enum UnbilledReason {
+  /// `Started` イベントがなかった。
+  MissingStarted,
+  /// `Ended` イベントがなかった（取引が終わっていない、またはログが欠けている）。
+  MissingEnded,
+  /// 終了時刻が開始時刻以前だった。
+  ReversedTimestamps {
+    /// 開始時刻。
+    started_at: OffsetDateTime,
+    /// 終了時刻。
+    ended_at:   OffsetDateTime,
+  },
+  /// 終了時の計器の指示値が開始時を下回り、エネルギー量が負になった。
+  NegativeEnergy {
+    /// 開始時の指示値（Wh）。
+    start_wh: u64,
+    /// 終了時の指示値（Wh）。
+    stop_wh:  u64,
+  },
+  /// 開始・終了の両方の指示値（相別でない `Energy.Active.Import.Register`）がそろわなかった。
+  MissingMeterValues,
+  /// 時刻や計測値を解釈できなかった。
+  InvalidValue {
+    /// 解釈できなかった値。
+    value: String,
+  },
+  /// 重複した `Started` / `Ended` イベントがあった。
+  DuplicateEvent {
+    /// 重複したイベント種別。
+    event_type: &'static str,
+  },
+  /// その他のドメインルールに違反した。
+  Rejected(SessionValueError),
+}
+
+impl fmt::Display for UnbilledReason {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      | Self::MissingStarted => f.write_str("Started イベントがありません"),
+      | Self::MissingEnded => f.write_str("Ended イベントがありません"),
+      | Self::ReversedTimestamps { started_at, ended_at } => {
+        write!(f, "終了時刻 {ended_at} が開始時刻 {started_at} 以前です")
+      },
+      | Self::NegativeEnergy { start_wh, stop_wh } => {
+        write!(f, "終了時の指示値 {stop_wh} Wh が開始時の {start_wh} Wh を下回っています")
+      },
+      | Self::MissingMeterValues => f.write_str("開始・終了の指示値がそろっていません"),
+      | Self::InvalidValue { value } => write!(f, "値 {value:?} を解釈できません"),
+      | Self::DuplicateEvent { event_type } => write!(f, "{event_type} イベントが重複しています"),
+      | Self::Rejected(error) => error.fmt(f),
+    }
+  }
+}
+
+/// 課金できなかった取引。
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnbilledTransaction {
+  /// OCPP の取引ID。
+  pub transaction_id: String,
+  /// 課金できなかった理由。
+  pub reason:         UnbilledReason,
+}
+
+/// TransactionEvent として解釈できず、読み飛ばした行。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnparseableEntry {
+  /// 1 始まりの行番号（JSON 配列の場合は要素番号）。
+  pub line:   usize,
+  /// 解析エラーの内容。
+  pub reason: String,
+}
+
+/// ログの取り込み結果。取引はログに最初に現れた順、読み飛ばした行は行番号順に並ぶ。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+  pub(crate) billed:      Vec<BilledTransaction>,
+  pub(crate) unbilled:    Vec<UnbilledTransaction>,
+  pub(crate) unparseable: Vec<UnparseableEntry>,
+}
+
+impl ImportReport {
+  /// 課金できた取引を返す。
+  #[must_use]
+  pub fn billed(&self) -> &[BilledTransaction] {
+    &self.billed
+  }
+
+  /// 課金できなかった取引と理由を返す。
+  #[must_use]
+  pub fn unbilled(&self) -> &[UnbilledTransaction] {
+    &self.unbilled
+  }
+
+  /// 解釈できずに読み飛ばした行を返す。
+  #[must_use]
+  pub fn unparseable(&self) -> &[UnparseableEntry] {
+    &self.unparseable
+  }
+}
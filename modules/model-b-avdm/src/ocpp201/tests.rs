@@ -0,0 +1,171 @@
+use serde_json::{Value, json};
+use time::macros::datetime;
+
+use super::{TransactionEventImporter, UnbilledReason, UnparseableEntry};
+use crate::session::{BillingPolicy, KwhMilli, MoneyYen, RateYenPerKwh, SequentialIdGenerator, Tariff};
+
+fn importer() -> TransactionEventImporter<SequentialIdGenerator> {
+  TransactionEventImporter::new(
+    Tariff::flat(RateYenPerKwh::try_new(50).unwrap()),
+    BillingPolicy::default(),
+    SequentialIdGenerator::new(),
+  )
+}
+
+fn event(event_type: &str, transaction_id: &str, seq_no: u64, timestamp: &str, register_wh: Option<f64>) -> Value {
+  let meter_value = register_wh.map_or(json!([]), |value| {
+    json!([{
+      "timestamp": timestamp,
+      "sampledValue": [
+        { "value": value, "measurand": "Energy.Active.Import.Register", "unitOfMeasure": { "unit": "Wh" } },
+        { "value": 22000.0, "measurand": "Power.Active.Import", "unitOfMeasure": { "unit": "W" } }
+      ]
+    }])
+  });
+  json!({
+    "eventType": event_type,
+    "timestamp": timestamp,
+    "triggerReason": "MeterValuePeriodic",
+    "seqNo": seq_no,
+    "transactionInfo": { "transactionId": transaction_id },
+    "meterValue": meter_value,
+  })
+}
+
+fn json_lines(events: &[Value]) -> String {
+  events.iter().map(Value::to_string).collect::<Vec<_>>().join("\n")
+}
+
+// ========================================
+// TransactionEvent ログの取り込み
+// ========================================
+
+#[test]
+fn complete_transactions_are_billed() {
+  let log = json_lines(&[
+    event("Started", "TX-1", 0, "2025-04-01T10:00:00Z", Some(10_000.0)),
+    event("Updated", "TX-1", 1, "2025-04-01T10:15:00Z", Some(14_000.0)),
+    event("Ended", "TX-1", 2, "2025-04-01T10:30:00Z", Some(20_000.0)),
+  ]);
+
+  let report = importer().import_str(&log);
+
+  assert!(report.unbilled().is_empty());
+  let billed = &report.billed()[0];
+  assert_eq!(billed.transaction_id(), "TX-1");
+  assert_eq!(billed.session().started_at(), datetime!(2025-04-01 10:00 UTC));
+  assert_eq!(billed.session().ended_at(), datetime!(2025-04-01 10:30 UTC));
+  assert_eq!(billed.bill().total_energy(), KwhMilli::try_new(10_000).unwrap());
+  assert!(billed.bill().amount_due() > MoneyYen::zero());
+}
+
+#[test]
+fn unbillable_transactions_are_reported_with_reasons() {
+  let log = json_lines(&[
+    event("Started", "OK", 0, "2025-04-01T10:00:00Z", Some(0.0)),
+    event("Started", "OPEN", 0, "2025-04-01T10:00:00Z", Some(0.0)),
+    event("Updated", "OPEN", 1, "2025-04-01T10:10:00Z", Some(500.0)),
+    event("Started", "REVERSED", 0, "2025-04-01T11:00:00Z", Some(0.0)),
+    event("Ended", "REVERSED", 1, "2025-04-01T10:00:00Z", Some(1_000.0)),
+    event("Started", "NEGATIVE", 0, "2025-04-01T10:00:00Z", Some(5_000.0)),
+    event("Ended", "NEGATIVE", 1, "2025-04-01T10:30:00Z", Some(4_000.0)),
+    event("Ended", "ORPHAN", 3, "2025-04-01T10:30:00Z", Some(4_000.0)),
+    event("Started", "NO-METER", 0, "2025-04-01T10:00:00Z", None),
+    event("Ended", "NO-METER", 1, "2025-04-01T10:30:00Z", Some(4_000.0)),
+    event("Ended", "OK", 1, "2025-04-01T10:30:00Z", Some(1_000.0)),
+  ]);
+
+  let report = importer().import_str(&log);
+
+  assert_eq!(report.billed().len(), 1);
+  assert_eq!(report.billed()[0].transaction_id(), "OK");
+  let reasons: Vec<_> =
+    report.unbilled().iter().map(|unbilled| (unbilled.transaction_id.as_str(), &unbilled.reason)).collect();
+  assert_eq!(reasons, vec![
+    ("OPEN", &UnbilledReason::MissingEnded),
+    ("REVERSED", &UnbilledReason::ReversedTimestamps {
+      started_at: datetime!(2025-04-01 11:00 UTC),
+      ended_at:   datetime!(2025-04-01 10:00 UTC),
+    }),
+    ("NEGATIVE", &UnbilledReason::NegativeEnergy { start_wh: 5_000, stop_wh: 4_000 }),
+    ("ORPHAN", &UnbilledReason::MissingStarted),
+    ("NO-METER", &UnbilledReason::MissingMeterValues),
+  ]);
+  assert_eq!(report.unbilled()[0].reason.to_string(), "Ended イベントがありません");
+}
+
+#[test]
+fn events_are_ordered_by_sequence_number() {
+  let log = json_lines(&[
+    event("Ended", "TX-1", 2, "2025-04-01T10:30:00Z", Some(3_000.0)),
+    event("Updated", "TX-1", 1, "2025-04-01T10:15:00Z", Some(2_000.0)),
+    event("Started", "TX-1", 0, "2025-04-01T10:00:00Z", Some(1_000.0)),
+  ]);
+
+  let report = importer().import_str(&log);
+
+  assert_eq!(report.billed()[0].bill().total_energy(), KwhMilli::try_new(2_000).unwrap());
+}
+
+#[test]
+fn json_arrays_call_frames_and_kwh_units_are_accepted() {
+  let mut ended = event("Ended", "TX-1", 1, "2025-04-01T10:30:00Z", None);
+  ended["meterValue"] = json!([{
+    "timestamp": "2025-04-01T10:30:00Z",
+    "sampledValue": [{ "value": 12.5, "unitOfMeasure": { "unit": "Wh", "multiplier": 3 } }]
+  }]);
+  let log = json!([
+    [2, "msg-1", "TransactionEvent", event("Started", "TX-1", 0, "2025-04-01T10:00:00Z", Some(10_000.0))],
+    ended,
+  ])
+  .to_string();
+
+  let report = importer().import_str(&log);
+
+  assert_eq!(report.billed()[0].bill().total_energy(), KwhMilli::try_new(2_500).unwrap());
+}
+
+#[test]
+fn phase_values_are_ignored_and_transaction_contexts_are_preferred() {
+  let mut started = event("Started", "TX-1", 0, "2025-04-01T10:00:00Z", None);
+  started["meterValue"] = json!([{
+    "timestamp": "2025-04-01T10:00:00Z",
+    "sampledValue": [
+      { "value": 900.0, "context": "Sample.Periodic" },
+      { "value": 1_000.0, "context": "Transaction.Begin" },
+      { "value": 300.0, "phase": "L1", "context": "Transaction.Begin" }
+    ]
+  }]);
+  let mut ended = event("Ended", "TX-1", 1, "2025-04-01T10:30:00Z", None);
+  ended["meterValue"] = json!([{
+    "timestamp": "2025-04-01T10:30:00Z",
+    "sampledValue": [
+      { "value": 4_000.0, "context": "Transaction.End" },
+      { "value": 1_100.0, "phase": "L1", "context": "Transaction.End" },
+      { "value": 4_200.0, "context": "Sample.Periodic" }
+    ]
+  }]);
+
+  let report = importer().import_str(&json_lines(&[started, ended]));
+
+  assert_eq!(report.billed()[0].bill().total_energy(), KwhMilli::try_new(3_000).unwrap());
+}
+
+#[test]
+fn malformed_lines_are_reported_and_skipped() {
+  let log = json_lines(&[
+    event("Started", "TX-1", 0, "2025-04-01T10:00:00Z", Some(0.0)),
+    json!("not an event"),
+    event("Ended", "TX-1", 1, "2025-04-01T10:30:00Z", Some(1_000.0)),
+  ]) + "\nnot json";
+
+  let report = importer().import_str(&log);
+
+  assert_eq!(report.billed()[0].bill().total_energy(), KwhMilli::try_new(1_000).unwrap());
+  let lines: Vec<usize> = report.unparseable().iter().map(|entry| entry.line).collect();
+  assert_eq!(lines, vec![2, 4]);
+  assert!(matches!(importer().import_str(r#"{"eventType":"Started"}"#).unparseable(), [UnparseableEntry {
+    line: 1,
+    ..
+  }]));
+}
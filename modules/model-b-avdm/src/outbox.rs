@@ -0,0 +1,14 @@
+mod dispatcher;
+mod errors;
+mod event;
+mod store;
+mod webhook;
+
+pub use dispatcher::{DispatchReport, RetryPolicy, WebhookDispatcher};
+pub use errors::OutboxError;
+pub use event::SessionEvent;
+pub use store::{DeliveryStatus, OutboxEntry, SessionStore};
+pub use webhook::{SIGNATURE_HEADER, WebhookEndpoint, WebhookSecret, sign_payload, verify_signature};
+
+#[cfg(test)]
+mod tests;
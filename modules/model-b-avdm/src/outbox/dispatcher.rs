@@ -0,0 +1,167 @@
+use std::{
+  num::NonZeroU32,
+  thread,
+  time::{Duration, Instant},
+};
+
+use super::{
+  errors::OutboxError,
+  store::{DeliveryStatus, SessionStore},
+  webhook::WebhookEndpoint,
+};
+
+/// 配送失敗時の再試行方針（指数バックオフ）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+  max_attempts:    NonZeroU32,
+  initial_backoff: Duration,
+  max_backoff:     Duration,
+}
+
+impl RetryPolicy {
+  /// 最大試行回数と待機時間の初期値・上限から再試行方針を生成する。
+  ///
+  /// # Errors
+  /// 初期値が上限を超える場合、`OutboxError::InvalidRetryPolicy` を返します。
+  pub fn new(max_attempts: NonZeroU32, initial_backoff: Duration, max_backoff: Duration) -> Result<Self, OutboxError> {
+    if initial_backoff > max_backoff {
+      return Err(OutboxError::InvalidRetryPolicy);
+    }
+    Ok(Self { max_attempts, initial_backoff, max_backoff })
+  }
+
+  /// 最大試行回数を返す。
+  #[must_use]
+  pub fn max_attempts(&self) -> NonZeroU32 {
+    self.max_attempts
+  }
+
+  /// `attempts` 回失敗した後、次の試行までに待つ時間を返す（失敗ごとに倍、上限あり）。
+  #[must_use]
+  pub fn backoff_after(&self, attempts: u32) -> Duration {
+    let factor = 2_u32.saturating_pow(attempts.saturating_sub(1));
+    self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+  }
+}
+
+/// 5 回まで、0.5 秒から倍々で最大 30 秒待って再試行する。
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts:    NonZeroU32::MIN.saturating_add(4),
+      initial_backoff: Duration::from_millis(500),
+      max_backoff:     Duration::from_secs(30),
+    }
+  }
+}
+
+/// 1 回の配送処理の結果。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispatchReport {
+  /// 配送に成功した件数。
+  pub delivered: usize,
+  /// 失敗し、再試行を予定した件数。
+  pub retrying:  usize,
+  /// 試行回数の上限に達し、配送を断念した件数。
+  pub abandoned: usize,
+}
+
+impl DispatchReport {
+  fn absorb(&mut self, other: Self) {
+    self.delivered += other.delivered;
+    self.retrying += other.retrying;
+    self.abandoned += other.abandoned;
+  }
+}
+
+/// アウトボックスのイベントを Webhook に配送するディスパッチャ。
+///
+/// 配送済みの記録は 2xx 応答を受け取った後にのみ行うため、受信側から見ると
+/// 同じイベントが複数回届くことがある（at-least-once）。受信側は `X-Webhook-Id`（イベントごとの
+/// UUIDv7）で重複を除く。
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+  endpoints: Vec<WebhookEndpoint>,
+  policy:    RetryPolicy,
+  timeout:   Duration,
+}
+
+impl WebhookDispatcher {
+  /// 再試行方針を指定してディスパッチャを生成する（タイムアウトは 5 秒）。
+  #[must_use]
+  pub fn new(policy: RetryPolicy) -> Self {
+    Self { endpoints: Vec::new(), policy, timeout: Duration::from_secs(5) }
+  }
+
+  /// 送信先を追加する。
+  #[must_use]
+  pub fn with_endpoint(mut self, endpoint: WebhookEndpoint) -> Self {
+    self.endpoints.push(endpoint);
+    self
+  }
+
+  /// 1 回の HTTP 通信のタイムアウトを設定する。
+  #[must_use]
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// `now` の時点で配送期限を迎えたイベントを、記録順にすべての送信先へ配送する。
+  pub fn dispatch_due(&self, store: &mut SessionStore, now: Instant) -> DispatchReport {
+    let mut report = DispatchReport::default();
+    for entry in store.outbox_mut() {
+      for endpoint in &self.endpoints {
+        if !entry.is_due_for(endpoint.name(), now) {
+          continue;
+        }
+        let attempts = entry.status_for(endpoint.name()).attempts() + 1;
+        let payload = entry.event().to_payload(entry.id());
+        match endpoint.post(entry.id(), entry.event().kind(), &payload, self.timeout) {
+          | Ok(()) => {
+            entry.record(endpoint.name(), DeliveryStatus::Delivered { attempts }, None);
+            report.delivered += 1;
+          },
+          | Err(last_error) if attempts >= self.policy.max_attempts.get() => {
+            entry.record(endpoint.name(), DeliveryStatus::Abandoned { attempts, last_error }, None);
+            report.abandoned += 1;
+          },
+          | Err(last_error) => {
+            let not_before = now + self.policy.backoff_after(attempts);
+            entry.record(
+              endpoint.name(),
+              DeliveryStatus::Pending { attempts, last_error: Some(last_error) },
+              Some(not_before),
+            );
+            report.retrying += 1;
+          },
+        }
+      }
+    }
+    report
+  }
+
+  /// 未配送のイベントがなくなるか `deadline` を過ぎるまで、バックオフを待ちながら配送を繰り返す。
+  pub fn run_until_idle(&self, store: &mut SessionStore, deadline: Duration) -> DispatchReport {
+    let started = Instant::now();
+    let mut report = DispatchReport::default();
+    loop {
+      report.absorb(self.dispatch_due(store, Instant::now()));
+      let Some(next) = self.next_attempt(store) else { return report };
+      let now = Instant::now();
+      if next.saturating_duration_since(started) > deadline {
+        return report;
+      }
+      thread::sleep(next.saturating_duration_since(now));
+    }
+  }
+
+  fn next_attempt(&self, store: &SessionStore) -> Option<Instant> {
+    store
+      .outbox()
+      .iter()
+      .flat_map(|entry| self.endpoints.iter().filter_map(|endpoint| entry.next_attempt_for(endpoint.name())))
+      .map(|not_before| not_before.unwrap_or_else(Instant::now))
+      .min()
+  }
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+use crate::session::SessionId;
+
+/// アウトボックスの保存と Webhook 設定で発生し得るエラー。
+///
+/// 配送の失敗はエラーとして返さず、アウトボックスの配送状態として記録する。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OutboxError {
+  /// 保存済みの状態より前の状態で上書きしようとした。
+  #[error("セッション {session_id} を保存済みの状態より前の状態で上書きできません")]
+  StateRegression {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// Webhook の URL が解釈できなかった。
+  #[error("Webhook の URL {url:?} を解釈できません (http://host:port/path の形式のみ対応)")]
+  InvalidEndpoint {
+    /// 入力された URL。
+    url: String,
+  },
+  /// Webhook の共有シークレットが空だった。
+  #[error("Webhook の共有シークレットは空にできません")]
+  EmptySecret,
+  /// 再試行方針の初回待機時間が上限を超えていた。
+  #[error("再試行の初回待機時間は上限以下にしてください")]
+  InvalidRetryPolicy,
+}
@@ -0,0 +1,90 @@
+use serde_json::{Value, json};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+use crate::session::{KwhMilli, MoneyYen, SessionId};
+
+/// 下流システムに通知するセッションのライフサイクルイベント。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+  /// セッションが開始された。
+  Started {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// 開始時刻。
+    started_at: OffsetDateTime,
+  },
+  /// セッションが停止し、請求が確定した。
+  Closed {
+    /// 対象セッションID。
+    session_id:   SessionId,
+    /// 終了時刻。
+    ended_at:     OffsetDateTime,
+    /// 総エネルギー量。
+    total_energy: KwhMilli,
+    /// 請求額。
+    amount_due:   MoneyYen,
+  },
+  /// 確定済みの請求が訂正された。
+  Amended {
+    /// 対象セッションID。
+    session_id:   SessionId,
+    /// 訂正後の総エネルギー量。
+    total_energy: KwhMilli,
+    /// 訂正後の請求額。
+    amount_due:   MoneyYen,
+    /// 訂正理由。
+    reason:       String,
+  },
+}
+
+impl SessionEvent {
+  /// Webhook の `type` として送るイベント名を返す。
+  #[must_use]
+  pub fn kind(&self) -> &'static str {
+    match self {
+      | Self::Started { .. } => "session.started",
+      | Self::Closed { .. } => "session.closed",
+      | Self::Amended { .. } => "session.amended",
+    }
+  }
+
+  /// 対象セッションIDを返す。
+  #[must_use]
+  pub fn session_id(&self) -> SessionId {
+    match self {
+      | Self::Started { session_id, .. } | Self::Closed { session_id, .. } | Self::Amended { session_id, .. } => {
+        *session_id
+      },
+    }
+  }
+
+  /// アウトボックスのイベントIDを付けた JSON ペイロードを生成する。
+  pub(crate) fn to_payload(&self, id: Uuid) -> String {
+    let data = match self {
+      | Self::Started { started_at, .. } => json!({ "started_at": rfc3339(*started_at) }),
+      | Self::Closed { ended_at, total_energy, amount_due, .. } => json!({
+        "ended_at": rfc3339(*ended_at),
+        "total_energy_wh": u64::from(*total_energy),
+        "amount_due_yen": u64::from(*amount_due),
+      }),
+      | Self::Amended { total_energy, amount_due, reason, .. } => json!({
+        "total_energy_wh": u64::from(*total_energy),
+        "amount_due_yen": u64::from(*amount_due),
+        "reason": reason,
+      }),
+    };
+    json!({
+      "id": id.hyphenated().to_string(),
+      "type": self.kind(),
+      "session_id": self.session_id().to_string(),
+      "data": data,
+    })
+    .to_string()
+  }
+}
+
+fn rfc3339(at: OffsetDateTime) -> Value {
+  // OffsetDateTime は常に RFC 3339 で表現できる範囲に収まる年を持つ
+  at.format(&Rfc3339).map_or(Value::Null, Value::String)
+}
@@ -0,0 +1,220 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  time::Instant,
+};
+
+use uuid::Uuid;
+
+use super::{errors::OutboxError, event::SessionEvent};
+use crate::session::{IdGenerator, Session, SessionId, UuidV7Generator};
+
+/// Webhook 送信先ごとの配送状態。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryStatus {
+  /// 未配送（再試行待ちを含む）。
+  Pending {
+    /// これまでの試行回数。
+    attempts:   u32,
+    /// 直近の失敗理由。
+    last_error: Option<String>,
+  },
+  /// 2xx 応答を受け取り配送済み。
+  Delivered {
+    /// 配送までの試行回数。
+    attempts: u32,
+  },
+  /// 試行回数の上限に達し、配送を断念した。
+  Abandoned {
+    /// 試行回数。
+    attempts:   u32,
+    /// 最後の失敗理由。
+    last_error: String,
+  },
+}
+
+impl DeliveryStatus {
+  /// これまでの試行回数を返す。
+  #[must_use]
+  pub fn attempts(&self) -> u32 {
+    match self {
+      | Self::Pending { attempts, .. } | Self::Delivered { attempts } | Self::Abandoned { attempts, .. } => *attempts,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EndpointDelivery {
+  status:     DeliveryStatus,
+  not_before: Option<Instant>,
+}
+
+/// セッションの保存と同時に記録されたイベント。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxEntry {
+  id:         Uuid,
+  sequence:   u64,
+  event:      SessionEvent,
+  deliveries: BTreeMap<String, EndpointDelivery>,
+}
+
+impl OutboxEntry {
+  /// イベントごとに一意な ID（UUIDv7、Webhook のメッセージID）を返す。
+  ///
+  /// ストアを作り直しても重複しないため、受信側はこの値で重複を除ける。
+  #[must_use]
+  pub fn id(&self) -> Uuid {
+    self.id
+  }
+
+  /// アウトボックス内の記録順の連番を返す（ストアごとに 1 から数える）。
+  #[must_use]
+  pub fn sequence(&self) -> u64 {
+    self.sequence
+  }
+
+  /// 記録されたイベントを返す。
+  #[must_use]
+  pub fn event(&self) -> &SessionEvent {
+    &self.event
+  }
+
+  /// 指定した送信先への配送状態を返す（未試行なら試行 0 回の `Pending`）。
+  #[must_use]
+  pub fn status_for(&self, endpoint: &str) -> DeliveryStatus {
+    self
+      .deliveries
+      .get(endpoint)
+      .map_or(DeliveryStatus::Pending { attempts: 0, last_error: None }, |delivery| delivery.status.clone())
+  }
+
+  /// 指定した送信先へ `now` の時点で配送を試みるべきかを判定する。
+  pub(crate) fn is_due_for(&self, endpoint: &str, now: Instant) -> bool {
+    match self.deliveries.get(endpoint) {
+      | None => true,
+      | Some(EndpointDelivery { status: DeliveryStatus::Pending { .. }, not_before }) => {
+        not_before.is_none_or(|not_before| not_before <= now)
+      },
+      | Some(_) => false,
+    }
+  }
+
+  /// 指定した送信先への次回試行時刻を返す（配送済み・断念済みなら `None`）。
+  pub(crate) fn next_attempt_for(&self, endpoint: &str) -> Option<Option<Instant>> {
+    match self.deliveries.get(endpoint) {
+      | None => Some(None),
+      | Some(EndpointDelivery { status: DeliveryStatus::Pending { .. }, not_before }) => Some(*not_before),
+      | Some(_) => None,
+    }
+  }
+
+  pub(crate) fn record(&mut self, endpoint: &str, status: DeliveryStatus, not_before: Option<Instant>) {
+    self.deliveries.insert(endpoint.to_owned(), EndpointDelivery { status, not_before });
+  }
+}
+
+/// セッションとアウトボックスを 1 つの単位で保存するインメモリのストア。
+///
+/// `save` は保存済みの状態との差分からイベントを導出し、セッションの更新と
+/// イベントの記録をまとめて行う。検証に失敗した場合はどちらも反映しない。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionStore {
+  sessions:      HashMap<SessionId, Session>,
+  outbox:        Vec<OutboxEntry>,
+  next_sequence: u64,
+}
+
+impl SessionStore {
+  /// 空のストアを生成する。
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// セッションを保存し、状態遷移に応じたイベントをアウトボックスに記録する。
+  ///
+  /// 記録したイベントの件数を返す。
+  ///
+  /// # Errors
+  /// 停止済みのセッションを進行中の状態で上書きする場合や、訂正の追記以外の方法で
+  /// 確定済みの請求を変える場合、`OutboxError::StateRegression` を返します。
+  pub fn save(&mut self, session: Session) -> Result<usize, OutboxError> {
+    let session_id = session.identity();
+    let events = derive_events(self.sessions.get(&session_id), &session)?;
+    let recorded = events.len();
+    for event in events {
+      self.next_sequence += 1;
+      self.outbox.push(OutboxEntry {
+        id: UuidV7Generator.next_id().into_uuid(),
+        sequence: self.next_sequence,
+        event,
+        deliveries: BTreeMap::new(),
+      });
+    }
+    self.sessions.insert(session_id, session);
+    Ok(recorded)
+  }
+
+  /// 保存済みのセッションを返す。
+  #[must_use]
+  pub fn get(&self, session_id: SessionId) -> Option<&Session> {
+    self.sessions.get(&session_id)
+  }
+
+  /// 記録順のアウトボックスを返す。
+  #[must_use]
+  pub fn outbox(&self) -> &[OutboxEntry] {
+    &self.outbox
+  }
+
+  pub(crate) fn outbox_mut(&mut self) -> &mut [OutboxEntry] {
+    &mut self.outbox
+  }
+}
+
+fn derive_events(previous: Option<&Session>, next: &Session) -> Result<Vec<SessionEvent>, OutboxError> {
+  let session_id = next.identity();
+  let mut events = Vec::new();
+  if previous.is_none() {
+    let started_at = match next {
+      | Session::Active(active) => active.started_at(),
+      | Session::Closed(closed) => closed.started_at(),
+    };
+    events.push(SessionEvent::Started { session_id, started_at });
+  }
+
+  let Session::Closed(closed) = next else {
+    if matches!(previous, Some(Session::Closed(_))) {
+      return Err(OutboxError::StateRegression { session_id });
+    }
+    return Ok(events);
+  };
+  let known = match previous {
+    | Some(Session::Closed(stored)) => {
+      // 確定済みの請求は訂正の追記によってのみ変わる
+      let known = stored.amendments().len();
+      let appended = closed.amendments().len() > known && closed.amendments()[..known] == *stored.amendments();
+      if previous != Some(next) && !appended {
+        return Err(OutboxError::StateRegression { session_id });
+      }
+      known
+    },
+    | _ => {
+      // 停止時点の請求は最初の訂正前の請求（訂正がなければ現在の請求）
+      let original = closed.amendments().first().map_or(closed.bill(), |amendment| amendment.original());
+      events.push(SessionEvent::Closed {
+        session_id,
+        ended_at: closed.ended_at(),
+        total_energy: original.total_energy(),
+        amount_due: original.amount_due(),
+      });
+      0
+    },
+  };
+  events.extend(closed.amendments()[known..].iter().map(|amendment| SessionEvent::Amended {
+    session_id,
+    total_energy: amendment.amended().total_energy(),
+    amount_due: amendment.amended().amount_due(),
+    reason: amendment.reason().as_str().to_owned(),
+  }));
+  Ok(events)
+}
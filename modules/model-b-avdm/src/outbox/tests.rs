@@ -0,0 +1,236 @@
+use std::{
+  collections::HashMap,
+  io::{BufRead, BufReader, Read, Write},
+  net::TcpListener,
+  num::NonZeroU32,
+  thread::{self, JoinHandle},
+  time::{Duration, Instant},
+};
+
+use time::OffsetDateTime;
+
+use super::{
+  DeliveryStatus, OutboxError, RetryPolicy, SIGNATURE_HEADER, SessionEvent, SessionStore, WebhookDispatcher,
+  WebhookEndpoint, WebhookSecret, sign_payload, verify_signature,
+};
+use crate::session::{
+  AmendmentReason, IdGenerator, KwhMilli, RateYenPerKwh, SequentialIdGenerator, Session, test_support,
+};
+
+/// 受信側が受け取った 1 件の HTTP リクエスト。
+#[derive(Debug)]
+struct ReceivedRequest {
+  headers: HashMap<String, String>,
+  body:    String,
+}
+
+/// 指定したステータスを順に返し、受け取ったリクエストを記録するローカル受信サーバ。
+fn spawn_receiver(statuses: Vec<u16>) -> (String, JoinHandle<Vec<ReceivedRequest>>) {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let url = format!("http://{}/hooks/session", listener.local_addr().unwrap());
+  let handle = thread::spawn(move || {
+    statuses
+      .into_iter()
+      .map(|status| {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = HashMap::new();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        loop {
+          line.clear();
+          reader.read_line(&mut line).unwrap();
+          let Some((name, value)) = line.trim_end().split_once(": ") else { break };
+          headers.insert(name.to_owned(), value.to_owned());
+        }
+        let mut body = vec![0; headers["Content-Length"].parse().unwrap()];
+        reader.read_exact(&mut body).unwrap();
+        write!(stream, "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+        ReceivedRequest { headers, body: String::from_utf8(body).unwrap() }
+      })
+      .collect()
+  });
+  (url, handle)
+}
+
+fn secret() -> WebhookSecret {
+  WebhookSecret::try_new("shared-secret").unwrap()
+}
+
+fn fast_retry(max_attempts: u32) -> RetryPolicy {
+  RetryPolicy::new(NonZeroU32::new(max_attempts).unwrap(), Duration::from_millis(10), Duration::from_millis(40))
+    .unwrap()
+}
+
+fn closed_session() -> Session {
+  test_support::closed_session(42, OffsetDateTime::now_utc(), 30, 10_000)
+}
+
+// ========================================
+// アウトボックスへの記録
+// ========================================
+
+#[test]
+fn saving_records_lifecycle_events_with_the_session() {
+  let mut store = SessionStore::new();
+  let closed = closed_session();
+  let active = Session::new_active(
+    closed.identity(),
+    closed.as_closed().unwrap().started_at(),
+    RateYenPerKwh::try_new(30).unwrap(),
+  );
+
+  assert_eq!(store.save(active), Ok(1));
+  assert_eq!(store.save(closed.clone()), Ok(1));
+  assert_eq!(store.save(closed.clone()), Ok(0));
+  let amended = closed.amend(KwhMilli::try_new(8_000).unwrap(), AmendmentReason::try_new("計量誤差").unwrap()).unwrap();
+  assert_eq!(store.save(amended.clone()), Ok(1));
+
+  let kinds: Vec<_> = store.outbox().iter().map(|entry| (entry.sequence(), entry.event().kind())).collect();
+  assert_eq!(kinds, vec![(1, "session.started"), (2, "session.closed"), (3, "session.amended")]);
+  assert_eq!(store.get(amended.identity()), Some(&amended));
+}
+
+#[test]
+fn event_ids_stay_unique_across_store_restarts() {
+  let mut before_restart = SessionStore::new();
+  let mut after_restart = SessionStore::new();
+  before_restart.save(closed_session()).unwrap();
+  after_restart.save(closed_session()).unwrap();
+
+  // 連番はストアごとに 1 から数え直すが、Webhook のメッセージIDは重複しない
+  let (old, new) = (&before_restart.outbox()[0], &after_restart.outbox()[0]);
+  assert_eq!((old.sequence(), new.sequence()), (1, 1));
+  assert_ne!(old.id(), new.id());
+  assert_eq!(new.id().get_version_num(), 7);
+}
+
+#[test]
+fn regressing_a_closed_session_records_nothing() {
+  let mut store = SessionStore::new();
+  let closed = closed_session();
+  store.save(closed.clone()).unwrap();
+  let before = store.clone();
+
+  let reopened = Session::new_active(
+    closed.identity(),
+    closed.as_closed().unwrap().started_at(),
+    RateYenPerKwh::try_new(30).unwrap(),
+  );
+
+  assert_eq!(store.save(reopened), Err(OutboxError::StateRegression { session_id: closed.identity() }));
+  assert_eq!(store, before);
+}
+
+// ========================================
+// Webhook 配送
+// ========================================
+
+#[test]
+fn payload_signature_round_trips() {
+  let signature = sign_payload(&secret(), b"{}");
+
+  assert!(signature.starts_with("sha256="));
+  assert!(verify_signature(&secret(), b"{}", &signature));
+  assert!(!verify_signature(&secret(), b"{ }", &signature));
+  assert!(!verify_signature(&WebhookSecret::try_new("other").unwrap(), b"{}", &signature));
+  assert_eq!(WebhookSecret::try_new(""), Err(OutboxError::EmptySecret));
+}
+
+#[test]
+fn dispatcher_delivers_signed_payloads_to_local_receiver() {
+  let (url, receiver) = spawn_receiver(vec![200, 200]);
+  let mut store = SessionStore::new();
+  store.save(closed_session()).unwrap();
+  let dispatcher =
+    WebhookDispatcher::new(fast_retry(3)).with_endpoint(WebhookEndpoint::try_new("crm", &url, secret()).unwrap());
+
+  let report = dispatcher.dispatch_due(&mut store, Instant::now());
+
+  assert_eq!(report.delivered, 2);
+  let received = receiver.join().unwrap();
+  assert_eq!(received[0].headers["X-Webhook-Event"], "session.started");
+  assert_eq!(received[1].headers["X-Webhook-Id"], store.outbox()[1].id().to_string());
+  assert!(verify_signature(&secret(), received[1].body.as_bytes(), &received[1].headers[SIGNATURE_HEADER]));
+  let payload: serde_json::Value = serde_json::from_str(&received[1].body).unwrap();
+  assert_eq!(payload["id"], received[1].headers["X-Webhook-Id"]);
+  assert_eq!(payload["type"], "session.closed");
+  assert_eq!(payload["data"]["total_energy_wh"], 10_000);
+  assert!(store.outbox().iter().all(|entry| entry.status_for("crm") == DeliveryStatus::Delivered { attempts: 1 }));
+}
+
+#[test]
+fn dispatcher_retries_with_backoff_until_acknowledged() {
+  let (url, receiver) = spawn_receiver(vec![503, 500, 200]);
+  let mut store = SessionStore::new();
+  store
+    .save(Session::new_active(
+      SequentialIdGenerator::new().next_id(),
+      OffsetDateTime::now_utc(),
+      RateYenPerKwh::try_new(30).unwrap(),
+    ))
+    .unwrap();
+  let dispatcher =
+    WebhookDispatcher::new(fast_retry(5)).with_endpoint(WebhookEndpoint::try_new("crm", &url, secret()).unwrap());
+
+  let report = dispatcher.run_until_idle(&mut store, Duration::from_secs(5));
+
+  assert_eq!((report.delivered, report.retrying), (1, 2));
+  assert_eq!(store.outbox()[0].status_for("crm"), DeliveryStatus::Delivered { attempts: 3 });
+  // at-least-once: 同じイベントが同じIDで再送される
+  let ids: Vec<_> =
+    receiver.join().unwrap().into_iter().map(|request| request.headers["X-Webhook-Id"].clone()).collect();
+  let id = store.outbox()[0].id().to_string();
+  assert_eq!(ids, vec![id.clone(), id.clone(), id]);
+}
+
+#[test]
+fn dispatcher_abandons_after_max_attempts_and_keeps_the_entry() {
+  let (url, receiver) = spawn_receiver(vec![500, 500]);
+  let mut store = SessionStore::new();
+  store
+    .save(Session::new_active(
+      SequentialIdGenerator::new().next_id(),
+      OffsetDateTime::now_utc(),
+      RateYenPerKwh::try_new(30).unwrap(),
+    ))
+    .unwrap();
+  let dispatcher =
+    WebhookDispatcher::new(fast_retry(2)).with_endpoint(WebhookEndpoint::try_new("crm", &url, secret()).unwrap());
+
+  let report = dispatcher.run_until_idle(&mut store, Duration::from_secs(5));
+
+  receiver.join().unwrap();
+  assert_eq!(report.abandoned, 1);
+  assert_eq!(store.outbox()[0].status_for("crm"), DeliveryStatus::Abandoned {
+    attempts:   2,
+    last_error: "HTTP 500".to_owned(),
+  });
+}
+
+#[test]
+fn retry_policy_backs_off_exponentially_up_to_the_cap() {
+  let policy = fast_retry(5);
+
+  assert_eq!(policy.backoff_after(1), Duration::from_millis(10));
+  assert_eq!(policy.backoff_after(2), Duration::from_millis(20));
+  assert_eq!(policy.backoff_after(4), Duration::from_millis(40));
+  assert_eq!(
+    RetryPolicy::new(NonZeroU32::MIN, Duration::from_secs(2), Duration::from_secs(1)),
+    Err(OutboxError::InvalidRetryPolicy)
+  );
+  assert!(matches!(
+    WebhookEndpoint::try_new("crm", "https://127.0.0.1:1/", secret()),
+    Err(OutboxError::InvalidEndpoint { .. })
+  ));
+}
+
+#[test]
+fn session_event_exposes_kind_and_session() {
+  let session = closed_session();
+  let event =
+    SessionEvent::Started { session_id: session.identity(), started_at: session.as_closed().unwrap().started_at() };
+
+  assert_eq!(event.kind(), "session.started");
+  assert_eq!(event.session_id(), session.identity());
+}
@@ -0,0 +1,127 @@
+use std::{
+  fmt,
+  io::{BufRead, BufReader, Write},
+  net::{SocketAddr, TcpStream, ToSocketAddrs},
+  time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use super::errors::OutboxError;
+
+/// ペイロードの署名を載せる HTTP ヘッダー名。値は `sha256=<16進 HMAC>`。
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 送信先と共有する署名用シークレット。
+#[derive(Clone, PartialEq, Eq)]
+pub struct WebhookSecret(Vec<u8>);
+
+impl WebhookSecret {
+  /// 空でないバイト列からシークレットを生成する。
+  ///
+  /// # Errors
+  /// 空の場合、`OutboxError::EmptySecret` を返します。
+  pub fn try_new(secret: impl Into<Vec<u8>>) -> Result<Self, OutboxError> {
+    let secret = secret.into();
+    if secret.is_empty() {
+      return Err(OutboxError::EmptySecret);
+    }
+    Ok(Self(secret))
+  }
+
+  fn mac(&self) -> HmacSha256 {
+    HmacSha256::new_from_slice(&self.0).expect("HMAC は任意長の鍵を受け付ける")
+  }
+}
+
+/// シークレットはログに出さない。
+impl fmt::Debug for WebhookSecret {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("WebhookSecret(..)")
+  }
+}
+
+/// ペイロードの HMAC-SHA256 署名を `sha256=<16進>` の形式で返す。
+#[must_use]
+pub fn sign_payload(secret: &WebhookSecret, payload: &[u8]) -> String {
+  let mut mac = secret.mac();
+  mac.update(payload);
+  format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// 受信側で署名ヘッダーを定数時間で検証する。
+#[must_use]
+pub fn verify_signature(secret: &WebhookSecret, payload: &[u8], header: &str) -> bool {
+  let Some(encoded) = header.trim().strip_prefix("sha256=") else { return false };
+  let Ok(expected) = hex::decode(encoded) else { return false };
+  let mut mac = secret.mac();
+  mac.update(payload);
+  mac.verify_slice(&expected).is_ok()
+}
+
+/// イベントを配送する HTTP Webhook の送信先。
+///
+/// 構内の受信システムを想定し、TLS を使わない `http://` のみに対応する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEndpoint {
+  name:    String,
+  host:    String,
+  address: SocketAddr,
+  path:    String,
+  secret:  WebhookSecret,
+}
+
+impl WebhookEndpoint {
+  /// 名前・URL・共有シークレットから送信先を生成する。
+  ///
+  /// 名前は配送状態を送信先ごとに記録するためのキーとして使う。
+  ///
+  /// # Errors
+  /// URL が `http://host:port/path` の形式でない、または名前解決できない場合、
+  /// `OutboxError::InvalidEndpoint` を返します。
+  pub fn try_new(name: impl Into<String>, url: &str, secret: WebhookSecret) -> Result<Self, OutboxError> {
+    let invalid = || OutboxError::InvalidEndpoint { url: url.to_owned() };
+    let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+    let (host, path) = rest.find('/').map_or((rest, "/"), |index| rest.split_at(index));
+    let address = host.to_socket_addrs().ok().and_then(|mut addresses| addresses.next()).ok_or_else(invalid)?;
+    Ok(Self { name: name.into(), host: host.to_owned(), address, path: path.to_owned(), secret })
+  }
+
+  /// 送信先の名前を返す。
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// 署名付きでペイロードを POST し、2xx 以外の応答や通信エラーを失敗理由として返す。
+  pub(crate) fn post(&self, id: Uuid, kind: &str, payload: &str, timeout: Duration) -> Result<(), String> {
+    let mut stream = TcpStream::connect_timeout(&self.address, timeout).map_err(|error| error.to_string())?;
+    stream.set_read_timeout(Some(timeout)).map_err(|error| error.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|error| error.to_string())?;
+
+    let request = format!(
+      "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {length}\r\n\
+       X-Webhook-Id: {id}\r\nX-Webhook-Event: {kind}\r\n{SIGNATURE_HEADER}: {signature}\r\n\
+       Connection: close\r\n\r\n{payload}",
+      id = id.hyphenated(),
+      path = self.path,
+      host = self.host,
+      length = payload.len(),
+      signature = sign_payload(&self.secret, payload.as_bytes()),
+    );
+    stream.write_all(request.as_bytes()).map_err(|error| error.to_string())?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).map_err(|error| error.to_string())?;
+    let status = status_line
+      .split_whitespace()
+      .nth(1)
+      .and_then(|code| code.parse::<u16>().ok())
+      .ok_or_else(|| format!("不正な応答: {:?}", status_line.trim_end()))?;
+    if (200..300).contains(&status) { Ok(()) } else { Err(format!("HTTP {status}")) }
+  }
+}
@@ -5,29 +5,77 @@ pub(crate) const MAX_KWH_MILLI: u64 = 1_000_000;
 /// 1 セッションあたりに許容する最大請求額（100万円）。
 pub(crate) const MAX_YEN: u64 = 1_000_000;
 
+mod account;
+mod active_session;
+mod amendment;
 mod base;
 mod bill;
+mod billing_policy;
 mod bounded;
 mod charge_ratio;
 mod chargeable_energy;
 mod chargeable_window;
+mod charging_curve;
+mod closed_session;
+mod connector_id;
+mod daily_split;
+mod driver_id;
 mod errors;
+mod estimator;
+mod free_energy_allowance;
 mod grace_period;
+mod grace_rule;
+mod id_generator;
 mod kwh_milli;
+mod locale;
+mod meter_register;
+mod metered_session;
 mod money_yen;
+mod power_watts;
 mod rate;
+mod reconnect;
 mod session_id;
+mod statement;
+mod tariff;
 mod timeline;
+mod transaction_id;
+mod units;
+mod wallet;
 
+pub use account::{Account, AccountId};
+pub use active_session::ActiveSession;
+pub use amendment::{AmendmentReason, BillAmendment};
 pub use base::Session;
-pub use bill::SessionBill;
-pub use bounded::BoundedU64;
+pub use bill::{BillLine, SessionBill};
+pub use billing_policy::BillingPolicy;
+pub use bounded::{Bounded, BoundedError, BoundedInt};
 pub use chargeable_energy::ChargeableEnergy;
+pub use charging_curve::{ChargingCurve, EnergyProfile};
+pub use closed_session::ClosedSession;
+pub use connector_id::ConnectorId;
+pub use daily_split::{DailyRevenue, DailyRevenueSplit};
+pub use driver_id::DriverId;
 pub use errors::SessionValueError;
-pub use kwh_milli::KwhMilli;
-pub use money_yen::MoneyYen;
+pub use estimator::CostEstimator;
+pub use free_energy_allowance::FreeEnergyAllowance;
+pub use grace_period::GracePeriod;
+pub use grace_rule::GraceRule;
+pub use id_generator::{IdGenerator, SequentialIdGenerator, UuidV7Generator};
+pub use kwh_milli::{KwhMilli, KwhMilliRange};
+pub use locale::Locale;
+pub use meter_register::{MeterReading, MeterRegister};
+pub use metered_session::MeteredSession;
+pub use money_yen::{MoneyYen, MoneyYenRange};
+pub use power_watts::PowerWatts;
 pub use rate::RateYenPerKwh;
+pub use reconnect::{LogicalSession, ReconnectPolicy};
 pub use session_id::SessionId;
+pub use statement::{AccountStatement, BillingPeriod, StatementBuilder, StatementLine, StatementTotal};
+pub use tariff::{Tariff, TariffTier};
+pub use transaction_id::{OcppTransactionId, TransactionIdMap};
+pub use wallet::{HeldSession, HeldSnapshot, PrepaidWallet, WalletCaptureError, WalletSettlement};
 
+#[cfg(test)]
+pub(crate) mod test_support;
 #[cfg(test)]
 mod tests;
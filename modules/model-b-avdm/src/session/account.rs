@@ -0,0 +1,81 @@
+use std::fmt;
+
+use super::session_id::SessionId;
+
+/// 請求先アカウントの識別子を UUID で表す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountId(pub(super) uuid::Uuid);
+
+impl AccountId {
+  /// UUID から新しい `AccountId` を生成する。
+  ///
+  /// # Returns
+  /// 生成された `AccountId`。
+  #[must_use]
+  pub fn new(id: uuid::Uuid) -> Self {
+    Self(id)
+  }
+
+  /// 内部の UUID を取り出す。
+  ///
+  /// # Returns
+  /// 保持している UUID。
+  #[must_use]
+  pub fn into_uuid(self) -> uuid::Uuid {
+    self.0
+  }
+}
+
+impl fmt::Display for AccountId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.hyphenated().fmt(f)
+  }
+}
+
+impl From<AccountId> for uuid::Uuid {
+  fn from(value: AccountId) -> Self {
+    value.0
+  }
+}
+
+/// 月次請求の単位となるアカウント。
+///
+/// アカウントに紐づけられたセッションだけが明細に計上される。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+  id:       AccountId,
+  sessions: Vec<SessionId>,
+}
+
+impl Account {
+  /// セッションが紐づいていないアカウントを生成する。
+  #[must_use]
+  pub fn new(id: AccountId) -> Self {
+    Self { id, sessions: Vec::new() }
+  }
+
+  /// セッションをアカウントに紐づける（既に紐づいていれば何もしない）。
+  pub fn link(&mut self, session_id: SessionId) {
+    if !self.owns(session_id) {
+      self.sessions.push(session_id);
+    }
+  }
+
+  /// セッションがこのアカウントに紐づいているかを判定する。
+  #[must_use]
+  pub fn owns(&self, session_id: SessionId) -> bool {
+    self.sessions.contains(&session_id)
+  }
+
+  /// アカウントを識別する。
+  #[must_use]
+  pub fn identity(&self) -> AccountId {
+    self.id
+  }
+
+  /// 紐づいているセッションID一覧を返す。
+  #[must_use]
+  pub fn sessions(&self) -> &[SessionId] {
+    &self.sessions
+  }
+}
@@ -0,0 +1,88 @@
+use time::OffsetDateTime;
+
+use super::{
+  bill::SessionBill, billing_policy::BillingPolicy, closed_session::ClosedSession, errors::SessionValueError,
+  kwh_milli::KwhMilli, session_id::SessionId, tariff::Tariff, timeline::SessionTimeline,
+};
+
+/// 課金進行中のセッション。
+///
+/// `stop` で自身を消費して `ClosedSession` に遷移するため、停止後に課金を続ける操作は
+/// コンパイル時に表現できない。
+///
+/// ```compile_fail
+/// use model_b_avdm::session::{ActiveSession, KwhMilli};
+/// use time::OffsetDateTime;
+///
+/// fn charge_after_stop(active: ActiveSession, ended_at: OffsetDateTime) {
+///   let _closed = active.stop(ended_at, KwhMilli::zero());
+///   // stop で消費された ActiveSession は使えない
+///   let _ = active.bill_snapshot(ended_at, KwhMilli::zero());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveSession {
+  id:         SessionId,
+  started_at: OffsetDateTime,
+  tariff:     Tariff,
+  policy:     BillingPolicy,
+}
+
+impl ActiveSession {
+  /// 料金表と課金方針を指定してセッションを開始する。
+  #[must_use]
+  pub fn new(id: SessionId, started_at: OffsetDateTime, tariff: Tariff, policy: BillingPolicy) -> Self {
+    Self { id, started_at, tariff, policy }
+  }
+
+  /// セッションを停止し、請求を確定させる。
+  ///
+  /// # Errors
+  /// タイムラインや金額が不正な場合、対応する `SessionValueError` を返します。
+  pub fn stop(self, ended_at: OffsetDateTime, total_energy: KwhMilli) -> Result<ClosedSession, SessionValueError> {
+    let bill = self.bill_snapshot(ended_at, total_energy)?;
+    let Self { id, started_at, tariff, policy } = self;
+    Ok(ClosedSession::new(id, started_at, ended_at, tariff, policy, bill))
+  }
+
+  /// 指定時点での課金スナップショットを取得する。
+  ///
+  /// # Errors
+  /// タイムラインや金額が不正な場合、対応する `SessionValueError` を返します。
+  pub fn bill_snapshot(
+    &self,
+    ended_at: OffsetDateTime,
+    total_energy: KwhMilli,
+  ) -> Result<SessionBill, SessionValueError> {
+    let timeline = SessionTimeline::between(self.started_at, ended_at)?;
+    let energy = self.policy.allocate(timeline, total_energy)?;
+    SessionBill::settle_with(energy, &self.tariff)
+  }
+
+  /// セッションを識別する。
+  #[must_use]
+  pub fn identity(&self) -> SessionId {
+    self.id
+  }
+
+  /// セッション開始時刻を返す。
+  #[must_use]
+  pub fn started_at(&self) -> OffsetDateTime {
+    self.started_at
+  }
+
+  /// 料金表を返す。
+  #[must_use]
+  pub fn tariff(&self) -> &Tariff {
+    &self.tariff
+  }
+
+  /// 課金方針を返す。
+  #[must_use]
+  pub fn policy(&self) -> BillingPolicy {
+    self.policy
+  }
+}
+
+#[cfg(test)]
+mod tests;
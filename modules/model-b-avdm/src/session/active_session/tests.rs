@@ -0,0 +1,39 @@
+use std::num::NonZeroU32;
+
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+
+use super::ActiveSession;
+use crate::session::{
+  BillingPolicy, KwhMilli, RateYenPerKwh, Session, SessionId, SessionValueError, Tariff,
+  test_support::create_test_session,
+};
+
+#[test]
+fn test_active_session_stop_returns_closed_session() {
+  let started_at = datetime!(2025-10-20 10:00 UTC);
+  let rate = RateYenPerKwh::new(NonZeroU32::new(30).unwrap());
+  let active =
+    ActiveSession::new(SessionId::new(Uuid::nil()), started_at, Tariff::flat(rate), BillingPolicy::default());
+
+  let snapshot = active.bill_snapshot(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  let closed = active.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  assert_eq!(closed.bill(), &snapshot);
+  assert_eq!(closed.ended_at(), started_at + Duration::minutes(10));
+
+  // 型消去ラッパーとの相互変換
+  let session = Session::from(closed.clone());
+  assert_eq!(session.statement(), Some(&snapshot));
+  assert_eq!(session.into_closed().unwrap(), closed);
+}
+
+#[test]
+fn test_session_wrapper_rejects_wrong_state_without_panicking() {
+  let (session, started_at) = create_test_session();
+  let result = session.bill_after_stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap());
+  assert!(matches!(result, Err(SessionValueError::NotClosed { .. })));
+  assert!(matches!(session.clone().into_closed(), Err(SessionValueError::NotClosed { .. })));
+
+  let closed = session.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  assert!(matches!(closed.into_active(), Err(SessionValueError::AlreadyClosed { .. })));
+}
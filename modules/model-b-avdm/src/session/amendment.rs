@@ -0,0 +1,83 @@
+use super::{bill::SessionBill, errors::SessionValueError};
+
+/// 請求訂正の理由を表す値オブジェクト（空文字は許容しない）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmendmentReason(String);
+
+impl AmendmentReason {
+  /// 訂正理由を生成する。
+  ///
+  /// # Errors
+  /// 空白のみの理由が渡された場合、`SessionValueError::EmptyAmendmentReason` を返します。
+  pub fn try_new(reason: impl Into<String>) -> Result<Self, SessionValueError> {
+    let reason = reason.into();
+    if reason.trim().is_empty() {
+      return Err(SessionValueError::EmptyAmendmentReason);
+    }
+    Ok(Self(reason))
+  }
+
+  /// 訂正理由を文字列として参照する。
+  #[must_use]
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+/// 停止後に確定値が届いたことによる請求訂正の記録。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BillAmendment {
+  original: SessionBill,
+  amended:  SessionBill,
+  reason:   AmendmentReason,
+}
+
+impl BillAmendment {
+  pub(crate) fn new(original: SessionBill, amended: SessionBill, reason: AmendmentReason) -> Self {
+    Self { original, amended, reason }
+  }
+
+  /// 訂正前の請求を返す。
+  #[must_use]
+  pub fn original(&self) -> &SessionBill {
+    &self.original
+  }
+
+  /// 訂正後の請求を返す。
+  #[must_use]
+  pub fn amended(&self) -> &SessionBill {
+    &self.amended
+  }
+
+  /// 訂正理由を返す。
+  #[must_use]
+  pub fn reason(&self) -> &AmendmentReason {
+    &self.reason
+  }
+
+  /// 総エネルギーの差分（ミリkWh、訂正後 - 訂正前）を返す。
+  #[must_use]
+  pub fn total_energy_delta_milli(&self) -> i64 {
+    delta(u64::from(self.original.total_energy()), u64::from(self.amended.total_energy()))
+  }
+
+  /// 課金対象エネルギーの差分（ミリkWh、訂正後 - 訂正前）を返す。
+  #[must_use]
+  pub fn billable_energy_delta_milli(&self) -> i64 {
+    delta(u64::from(self.original.billable_energy()), u64::from(self.amended.billable_energy()))
+  }
+
+  /// 請求額の差分（円、訂正後 - 訂正前）を返す。正なら追加請求、負なら返金となる。
+  #[must_use]
+  pub fn amount_delta_yen(&self) -> i64 {
+    delta(u64::from(self.original.amount_due()), u64::from(self.amended.amount_due()))
+  }
+}
+
+/// 上限が `MAX_KWH_MILLI` / `MAX_YEN` に収まる値同士の差なので `i64` で表現できる。
+fn delta(before: u64, after: u64) -> i64 {
+  after as i64 - before as i64
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,56 @@
+use time::Duration;
+
+use super::AmendmentReason;
+use crate::session::{KwhMilli, SessionValueError, test_support::create_test_session};
+
+#[test]
+fn test_amend_recalculates_bill_and_keeps_original() {
+  let (session, started_at) = create_test_session();
+  let closed = session.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+
+  // 停止後に届いた確定値は 12 kWh
+  let reason = AmendmentReason::try_new("late final meter value").unwrap();
+  let amended = closed.amend(KwhMilli::try_new(12_000).unwrap(), reason.clone()).unwrap();
+
+  let bill = amended.statement().unwrap();
+  assert_eq!(u64::from(bill.billable_energy()), 6_000);
+  assert_eq!(u64::from(bill.amount_due()), 180);
+
+  let amendment = &amended.amendments()[0];
+  assert_eq!(u64::from(amendment.original().amount_due()), 150);
+  assert_eq!(amendment.amended(), bill);
+  assert_eq!(amendment.reason(), &reason);
+  assert_eq!(amendment.total_energy_delta_milli(), 2_000);
+  assert_eq!(amendment.billable_energy_delta_milli(), 1_000);
+  assert_eq!(amendment.amount_delta_yen(), 30);
+
+  // 下方修正は負の差分（返金）になり、履歴は古い順に残る
+  let reason = AmendmentReason::try_new("meter correction").unwrap();
+  let amended = amended.amend(KwhMilli::try_new(8_000).unwrap(), reason).unwrap();
+  assert_eq!(amended.amendments().len(), 2);
+  assert_eq!(amended.amendments()[1].amount_delta_yen(), -60);
+}
+
+#[test]
+fn test_amend_does_not_reopen_post_stop_billing() {
+  let (session, started_at) = create_test_session();
+  let ended_at = started_at + Duration::minutes(10);
+  let closed = session.stop(ended_at, KwhMilli::try_new(10_000).unwrap()).unwrap();
+  let reason = AmendmentReason::try_new("late final meter value").unwrap();
+  let amended = closed.amend(KwhMilli::try_new(12_000).unwrap(), reason).unwrap();
+
+  let result = amended.bill_after_stop(ended_at + Duration::minutes(5), KwhMilli::try_new(15_000).unwrap());
+  assert!(matches!(result, Err(SessionValueError::AlreadyClosed { .. })));
+  let result = amended.stop(ended_at + Duration::minutes(5), KwhMilli::try_new(15_000).unwrap());
+  assert!(matches!(result, Err(SessionValueError::AlreadyClosed { .. })));
+}
+
+#[test]
+fn test_amend_requires_closed_session_and_reason() {
+  let (session, _) = create_test_session();
+  let reason = AmendmentReason::try_new("late final meter value").unwrap();
+  let result = session.amend(KwhMilli::try_new(12_000).unwrap(), reason);
+  assert!(matches!(result, Err(SessionValueError::NotClosed { .. })));
+
+  assert!(matches!(AmendmentReason::try_new("  "), Err(SessionValueError::EmptyAmendmentReason)));
+}
@@ -1,109 +1,182 @@
 use time::OffsetDateTime;
 
 use super::{
-  bill::SessionBill, chargeable_energy::ChargeableEnergy, errors::SessionValueError, grace_period::GracePeriod,
-  kwh_milli::KwhMilli, rate::RateYenPerKwh, session_id::SessionId, timeline::SessionTimeline,
+  active_session::ActiveSession,
+  amendment::{AmendmentReason, BillAmendment},
+  bill::SessionBill,
+  billing_policy::BillingPolicy,
+  closed_session::ClosedSession,
+  errors::SessionValueError,
+  grace_rule::GraceRule,
+  kwh_milli::KwhMilli,
+  rate::RateYenPerKwh,
+  session_id::SessionId,
+  tariff::Tariff,
 };
 
 /// 充電セッションのライフサイクルを表す列挙体。
+///
+/// 保存や一覧など状態を問わず扱う場面のための型消去ラッパー。状態ごとの操作は
+/// `ActiveSession` / `ClosedSession` を直接使うと、誤った状態での呼び出しがコンパイル時に弾かれる。
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Session {
   /// 課金進行中の状態。
-  Active {
-    /// セッションID。
-    id:         SessionId,
-    /// セッション開始時刻。
-    started_at: OffsetDateTime,
-    /// 単価（円/kWh）。
-    rate:       RateYenPerKwh,
-  },
+  Active(ActiveSession),
   /// 停止済みで請求が確定した状態。
-  Closed {
-    /// セッションID。
-    id:         SessionId,
-    /// セッション開始時刻。
-    started_at: OffsetDateTime,
-    /// 終了時刻。
-    ended_at:   OffsetDateTime,
-    /// 単価（円/kWh）。
-    rate:       RateYenPerKwh,
-    /// 確定した請求。
-    bill:       SessionBill,
-  },
+  Closed(ClosedSession),
 }
 
 impl Session {
-  /// アクティブ状態のセッションを生成する。
+  /// アクティブ状態のセッションを生成する（無料ルールは開始から5分）。
   pub fn new_active(id: SessionId, started_at: OffsetDateTime, rate: RateYenPerKwh) -> Self {
-    Self::Active { id, started_at, rate }
+    Self::new_active_with_policy(id, started_at, rate, BillingPolicy::default())
+  }
+
+  /// 無料ルールを指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_grace(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    rate: RateYenPerKwh,
+    grace: GraceRule,
+  ) -> Self {
+    Self::new_active_with_policy(id, started_at, rate, BillingPolicy::with_grace(grace))
+  }
+
+  /// 課金方針を指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_policy(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    rate: RateYenPerKwh,
+    policy: BillingPolicy,
+  ) -> Self {
+    Self::new_active_with_tariff(id, started_at, Tariff::flat(rate), policy)
+  }
+
+  /// 料金表と課金方針を指定してアクティブ状態のセッションを生成する。
+  pub fn new_active_with_tariff(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    tariff: Tariff,
+    policy: BillingPolicy,
+  ) -> Self {
+    Self::Active(ActiveSession::new(id, started_at, tariff, policy))
   }
 
   /// セッションを停止し、請求を確定させる。
+  ///
+  /// # Errors
+  /// 停止済みの場合は `SessionValueError::AlreadyClosed`、タイムラインや金額が不正な場合は対応する
+  /// `SessionValueError` を返します。
   pub fn stop(self, ended_at: OffsetDateTime, total_energy: KwhMilli) -> Result<Self, SessionValueError> {
-    match self {
-      Self::Active { id, started_at, rate } => {
-        let timeline = SessionTimeline::between(started_at, ended_at)?;
-        let window = timeline.consume_grace_period(Self::grace_period());
-        let energy = ChargeableEnergy::allocate(total_energy, window)?;
-        let bill = SessionBill::settle(energy, rate)?;
-
-        Ok(Self::Closed { id, started_at, ended_at, rate, bill })
-      }
-      Self::Closed { id, .. } => Err(SessionValueError::AlreadyClosed { session_id: id }),
-    }
+    Ok(self.into_active()?.stop(ended_at, total_energy)?.into())
   }
 
   /// 指定時点での課金スナップショットを取得する。
+  ///
+  /// # Errors
+  /// 停止済みの場合は `SessionValueError::AlreadyClosed`、タイムラインや金額が不正な場合は対応する
+  /// `SessionValueError` を返します。
   pub fn bill_snapshot(
     &self,
     ended_at: OffsetDateTime,
     total_energy: KwhMilli,
   ) -> Result<SessionBill, SessionValueError> {
     match self {
-      Self::Active { started_at, rate, .. } => {
-        let timeline = SessionTimeline::between(*started_at, ended_at)?;
-        let window = timeline.consume_grace_period(Self::grace_period());
-        let energy = ChargeableEnergy::allocate(total_energy, window)?;
-        SessionBill::settle(energy, *rate)
-      }
-      Self::Closed { id, .. } => Err(SessionValueError::AlreadyClosed { session_id: *id }),
+      | Self::Active(active) => active.bill_snapshot(ended_at, total_energy),
+      | Self::Closed(closed) => Err(SessionValueError::AlreadyClosed { session_id: closed.identity() }),
     }
   }
 
   /// 停止後の追加課金要求に応答する。
+  ///
+  /// # Errors
+  /// - 停止済みセッションでは常に `SessionValueError::AlreadyClosed` を返します。
+  /// - アクティブなセッションでは `SessionValueError::NotClosed` を返します（`bill_snapshot`
+  ///   を使うこと）。
   pub fn bill_after_stop(
     &self,
     _ended_at: OffsetDateTime,
     _total_energy: KwhMilli,
   ) -> Result<SessionBill, SessionValueError> {
     match self {
-      Self::Active { .. } => {
-        // Active状態のときは bill_snapshot を使うべき
-        // ここに到達することは想定外だが、エラーを返すのが安全
-        panic!("bill_after_stop should not be called on Active session. Use bill_snapshot instead.")
-      }
-      Self::Closed { id, .. } => Err(SessionValueError::AlreadyClosed { session_id: *id }),
+      | Self::Active(active) => Err(SessionValueError::NotClosed { session_id: active.identity() }),
+      | Self::Closed(closed) => Err(SessionValueError::AlreadyClosed { session_id: closed.identity() }),
+    }
+  }
+
+  /// 停止後に届いた確定エネルギー量で請求を訂正する。
+  ///
+  /// 通常の停止後課金（`bill_after_stop`）とは異なり、訂正前の請求と理由を履歴に残した上で
+  /// 請求を再計算する。
+  ///
+  /// # Errors
+  /// - 停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - 再計算に失敗した場合、対応する `SessionValueError` を返します。
+  pub fn amend(self, corrected_energy: KwhMilli, reason: AmendmentReason) -> Result<Self, SessionValueError> {
+    Ok(self.into_closed()?.amend(corrected_energy, reason)?.into())
+  }
+
+  /// アクティブなセッションを取り出す。
+  ///
+  /// # Errors
+  /// 停止済みの場合、`SessionValueError::AlreadyClosed` を返します。
+  pub fn into_active(self) -> Result<ActiveSession, SessionValueError> {
+    match self {
+      | Self::Active(active) => Ok(active),
+      | Self::Closed(closed) => Err(SessionValueError::AlreadyClosed { session_id: closed.identity() }),
     }
   }
 
+  /// 停止済みのセッションを取り出す。
+  ///
+  /// # Errors
+  /// 停止していない場合、`SessionValueError::NotClosed` を返します。
+  pub fn into_closed(self) -> Result<ClosedSession, SessionValueError> {
+    match self {
+      | Self::Active(active) => Err(SessionValueError::NotClosed { session_id: active.identity() }),
+      | Self::Closed(closed) => Ok(closed),
+    }
+  }
+
+  /// 停止済みであれば参照を返す。
+  #[must_use]
+  pub fn as_closed(&self) -> Option<&ClosedSession> {
+    match self {
+      | Self::Active(_) => None,
+      | Self::Closed(closed) => Some(closed),
+    }
+  }
+
+  /// 請求訂正の履歴を返す（アクティブ状態や未訂正の場合は空）。
+  #[must_use]
+  pub fn amendments(&self) -> &[BillAmendment] {
+    self.as_closed().map_or(&[], ClosedSession::amendments)
+  }
+
   /// セッションを識別する。
   #[must_use]
   pub fn identity(&self) -> SessionId {
     match self {
-      Self::Active { id, .. } | Self::Closed { id, .. } => *id,
+      | Self::Active(active) => active.identity(),
+      | Self::Closed(closed) => closed.identity(),
     }
   }
 
   /// 請求書を参照する（停止済みのみ）。
   #[must_use]
   pub fn statement(&self) -> Option<&SessionBill> {
-    match self {
-      Self::Active { .. } => None,
-      Self::Closed { bill, .. } => Some(bill),
-    }
+    self.as_closed().map(ClosedSession::bill)
   }
+}
+
+impl From<ActiveSession> for Session {
+  fn from(value: ActiveSession) -> Self {
+    Self::Active(value)
+  }
+}
 
-  fn grace_period() -> GracePeriod {
-    GracePeriod::from_minutes(super::FREE_MINUTES)
+impl From<ClosedSession> for Session {
+  fn from(value: ClosedSession) -> Self {
+    Self::Closed(value)
   }
 }
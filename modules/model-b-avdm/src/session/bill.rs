@@ -1,27 +1,115 @@
 use super::{
-  chargeable_energy::ChargeableEnergy, errors::SessionValueError, kwh_milli::KwhMilli, money_yen::MoneyYen,
+  chargeable_energy::ChargeableEnergy,
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  money_yen::MoneyYen,
   rate::RateYenPerKwh,
+  tariff::{PriceLimit, Tariff},
+  units::{MILLI_YEN, Money},
 };
 
-/// セッション請求を表す値オブジェクト。
+/// 請求明細の 1 行（料金表の 1 段に対応する）。
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BillLine {
+  energy: KwhMilli,
+  rate:   RateYenPerKwh,
+}
+
+impl BillLine {
+  pub(crate) fn new(energy: KwhMilli, rate: RateYenPerKwh) -> Self {
+    Self { energy, rate }
+  }
+
+  /// この行に計上された課金対象エネルギーを返す。
+  #[must_use]
+  pub fn energy(&self) -> KwhMilli {
+    self.energy
+  }
+
+  /// この行に適用された単価を返す。
+  #[must_use]
+  pub fn rate(&self) -> RateYenPerKwh {
+    self.rate
+  }
+
+  /// 丸め前の小計をミリ円単位で返す。
+  ///
+  /// 請求額は全行の小計を合算してから一度だけ床で丸めるため、行ごとには丸めない。
+  #[must_use]
+  pub fn amount_milli_yen(&self) -> u128 {
+    self.amount().value()
+  }
+
+  pub(crate) fn amount(&self) -> Money<MILLI_YEN> {
+    self.energy.energy() * self.rate.price()
+  }
+}
+
+/// セッション請求を表す値オブジェクト。
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SessionBill {
-  energy: ChargeableEnergy,
-  amount: MoneyYen,
+  energy:              ChargeableEnergy,
+  lines:               Vec<BillLine>,
+  amount:              MoneyYen,
+  minimum_fee_applied: bool,
+  price_cap_applied:   bool,
 }
 
 impl SessionBill {
   /// 課金対象エネルギーと単価から請求を確定する。
+  ///
+  /// # Errors
+  /// 算出金額が上限を超えた場合、`SessionValueError::AmountOutOfRange` を返します。
   pub fn settle(energy: ChargeableEnergy, rate: RateYenPerKwh) -> Result<Self, SessionValueError> {
-    let amount = rate.quote_for(energy.billable())?;
-    Ok(Self { energy, amount })
+    Self::settle_with(energy, &Tariff::flat(rate))
+  }
+
+  /// 課金対象エネルギーと料金表から請求を確定する。
+  ///
+  /// 料金表に最低料金・上限料金があれば、段ごとの算出後に適用する。
+  ///
+  /// # Errors
+  /// 上限料金を適用しない算出金額が上限を超えた場合、`SessionValueError::AmountOutOfRange`
+  /// を返します。
+  pub fn settle_with(energy: ChargeableEnergy, tariff: &Tariff) -> Result<Self, SessionValueError> {
+    let (lines, amount, limit) = tariff.price_for(energy.billable())?;
+    Ok(Self {
+      energy,
+      lines,
+      amount,
+      minimum_fee_applied: limit == Some(PriceLimit::MinimumFee),
+      price_cap_applied: limit == Some(PriceLimit::PriceCap),
+    })
   }
 
   /// 請求の合成を行う（同一セッション内の分割計算を想定）。
+  ///
+  /// 金額を単純に合算するため、段階料金・最低料金・上限料金は合算後のエネルギーに
+  /// 対して適用し直されない。明細行は単価が同じもの同士をまとめ、最低料金・上限料金の
+  /// 適用有無はいずれかの請求で適用されていれば適用済みとして残す。
+  ///
+  /// # Errors
+  /// 合算したエネルギー量または金額が上限を超えた場合、`SessionValueError` を返します。
+  #[deprecated(
+    note = "合算したエネルギーを `SessionBill::settle_with` で算出し直すか、`LogicalSession` を使ってください"
+  )]
   pub fn merge(self, other: Self) -> Result<Self, SessionValueError> {
     let energy = self.energy.combine(other.energy)?;
-    let amount = self.amount.saturating_add(other.amount)?;
-    Ok(Self { energy, amount })
+    let amount = self.amount.checked_add(other.amount)?;
+    let mut lines = self.lines;
+    for line in other.lines {
+      match lines.iter_mut().find(|existing| existing.rate == line.rate) {
+        | Some(existing) => existing.energy = existing.energy.checked_add(line.energy)?,
+        | None => lines.push(line),
+      }
+    }
+    Ok(Self {
+      energy,
+      lines,
+      amount,
+      minimum_fee_applied: self.minimum_fee_applied || other.minimum_fee_applied,
+      price_cap_applied: self.price_cap_applied || other.price_cap_applied,
+    })
   }
 
   /// 課金対象エネルギーを返す。
@@ -30,15 +118,37 @@ impl SessionBill {
     self.energy.billable()
   }
 
+  pub(crate) fn chargeable_energy(&self) -> ChargeableEnergy {
+    self.energy
+  }
+
   /// 総エネルギー消費量を返す。
   #[must_use]
   pub fn total_energy(&self) -> KwhMilli {
     self.energy.total_consumed()
   }
 
+  /// 料金表の段ごとの明細行を返す。
+  #[must_use]
+  pub fn lines(&self) -> &[BillLine] {
+    &self.lines
+  }
+
   /// 請求金額を返す。
   #[must_use]
   pub fn amount_due(&self) -> MoneyYen {
     self.amount
   }
+
+  /// 最低料金まで引き上げられたかを返す。
+  #[must_use]
+  pub fn minimum_fee_applied(&self) -> bool {
+    self.minimum_fee_applied
+  }
+
+  /// 上限料金まで引き下げられたかを返す。
+  #[must_use]
+  pub fn price_cap_applied(&self) -> bool {
+    self.price_cap_applied
+  }
 }
@@ -0,0 +1,55 @@
+use super::{
+  chargeable_energy::ChargeableEnergy, charging_curve::EnergyProfile, errors::SessionValueError, grace_rule::GraceRule,
+  kwh_milli::KwhMilli, timeline::SessionTimeline,
+};
+
+/// セッションの課金方針（無料ルールとエネルギー分布の仮定）を表す値オブジェクト。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BillingPolicy {
+  grace:   GraceRule,
+  profile: EnergyProfile,
+}
+
+impl BillingPolicy {
+  /// 無料ルールとエネルギー分布の仮定から課金方針を生成する。
+  #[must_use]
+  pub fn new(grace: GraceRule, profile: EnergyProfile) -> Self {
+    Self { grace, profile }
+  }
+
+  /// 一様分布を仮定し、無料ルールのみを指定して課金方針を生成する。
+  #[must_use]
+  pub fn with_grace(grace: GraceRule) -> Self {
+    Self::new(grace, EnergyProfile::Uniform)
+  }
+
+  /// 無料ルールを返す。
+  #[must_use]
+  pub fn grace(&self) -> GraceRule {
+    self.grace
+  }
+
+  /// エネルギー分布の仮定を返す。
+  #[must_use]
+  pub fn profile(&self) -> EnergyProfile {
+    self.profile
+  }
+
+  /// 既に消費した時間とエネルギーを差し引いた、残りの無料ルールを持つ課金方針を求める。
+  #[must_use]
+  pub fn remaining_after(self, elapsed_millis: u128, consumed_energy: KwhMilli) -> Self {
+    Self { grace: self.grace.remaining_after(elapsed_millis, consumed_energy), ..self }
+  }
+
+  /// 課金方針を適用し、課金対象エネルギーを割り当てる。
+  ///
+  /// # Errors
+  /// 課金比率が不正な場合、`SessionValueError::InvalidChargeRatio` を返します。
+  pub fn allocate(
+    self,
+    timeline: SessionTimeline,
+    total_energy: KwhMilli,
+  ) -> Result<ChargeableEnergy, SessionValueError> {
+    self.grace.allocate(timeline, total_energy, self.profile)
+  }
+}
@@ -1,28 +1,189 @@
-/// 固定上限付きの `u64` 値を表すユーティリティ型。
+use std::{fmt::Debug, iter::Sum};
+
+use thiserror::Error;
+
+mod sealed {
+  pub trait Sealed {}
+}
+
+/// `Bounded` の内部表現に使える整数型。
 ///
-/// コンストラクタで上限検証を行うことで、不正な範囲の値を静的に排除する。
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BoundedU64<const MAX: u64>(u64);
+/// 範囲検査と演算は `i128` 上で行うため、`i128` に損失なく変換できる型に限定している。
+pub trait BoundedInt: sealed::Sealed + Copy + Ord + Debug + Into<i128> + TryFrom<i128> {
+  /// 型が表せる最小値。
+  const MIN: i128;
+  /// 型が表せる最大値。
+  const MAX: i128;
+}
+
+macro_rules! bounded_int {
+  ($($int:ty),*) => {
+    $(
+      impl sealed::Sealed for $int {}
+      impl BoundedInt for $int {
+        const MIN: i128 = <$int>::MIN as i128;
+        const MAX: i128 = <$int>::MAX as i128;
+      }
+
+      impl<const MIN: i128, const MAX: i128> From<Bounded<$int, MIN, MAX>> for $int {
+        fn from(value: Bounded<$int, MIN, MAX>) -> Self {
+          value.get()
+        }
+      }
+    )*
+  };
+}
+
+bounded_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// 値が範囲外だったことを表すエラー。
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum BoundedError {
+  /// 下限を下回った。
+  #[error("値 {value} は下限 {min} を下回っています")]
+  BelowMinimum {
+    /// 入力または演算結果の値。
+    value: i128,
+    /// 許容下限。
+    min:   i128,
+  },
+  /// 上限を超えた。
+  #[error("値 {value} は上限 {max} を超えています")]
+  AboveMaximum {
+    /// 入力または演算結果の値。
+    value: i128,
+    /// 許容上限。
+    max:   i128,
+  },
+}
 
-impl<const MAX: u64> BoundedU64<MAX> {
-  /// 上限 `MAX` 以下の値を生成する。
+/// `MIN` 以上 `MAX` 以下の整数を表すユーティリティ型。
+///
+/// コンストラクタと各演算で範囲検証を行うことで、不正な範囲の値を静的に排除する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bounded<T: BoundedInt, const MIN: i128, const MAX: i128>(T);
+
+impl<T: BoundedInt, const MIN: i128, const MAX: i128> Bounded<T, MIN, MAX> {
+  /// 範囲内の値を生成する。
   ///
-  /// # Returns
-  /// `value <= MAX` のとき `Some`、それ以外は `None`。
-  #[must_use]
-  pub fn new(value: u64) -> Option<Self> {
-    if value <= MAX { Some(Self(value)) } else { None }
+  /// # Errors
+  /// 範囲外の値の場合、`BoundedError` を返します。
+  pub fn new(value: T) -> Result<Self, BoundedError> {
+    Self::from_i128(value.into())
   }
 
   /// 内部の数値を取得する。
   #[must_use]
-  pub fn get(self) -> u64 {
+  pub fn get(self) -> T {
     self.0
   }
+
+  /// 範囲検証付きの加算を行う。
+  ///
+  /// # Errors
+  /// 結果が範囲外の場合、`BoundedError` を返します。
+  pub fn checked_add(self, rhs: Self) -> Result<Self, BoundedError> {
+    Self::from_i128(self.into_i128().saturating_add(rhs.into_i128()))
+  }
+
+  /// 範囲検証付きの減算を行う。
+  ///
+  /// # Errors
+  /// 結果が範囲外の場合、`BoundedError` を返します。
+  pub fn checked_sub(self, rhs: Self) -> Result<Self, BoundedError> {
+    Self::from_i128(self.into_i128().saturating_sub(rhs.into_i128()))
+  }
+
+  /// 範囲検証付きの乗算を行う。
+  ///
+  /// # Errors
+  /// 結果が範囲外の場合、`BoundedError` を返します。
+  pub fn checked_mul(self, rhs: Self) -> Result<Self, BoundedError> {
+    Self::from_i128(self.into_i128().saturating_mul(rhs.into_i128()))
+  }
+
+  /// 別の範囲へ変換する。
+  ///
+  /// # Errors
+  /// 値が変換先の範囲外の場合、`BoundedError` を返します。
+  pub fn rebound<const TO_MIN: i128, const TO_MAX: i128>(self) -> Result<Bounded<T, TO_MIN, TO_MAX>, BoundedError> {
+    Bounded::from_i128(self.into_i128())
+  }
+
+  /// 自身の範囲を包含する広い範囲へ変換する。
+  ///
+  /// 範囲が包含されない組み合わせはコンパイル時に拒否される。
+  #[must_use]
+  pub fn widen<const TO_MIN: i128, const TO_MAX: i128>(self) -> Bounded<T, TO_MIN, TO_MAX> {
+    const {
+      assert!(TO_MIN <= MIN && MAX <= TO_MAX, "widen には現在の範囲を包含する範囲を指定してください")
+    };
+    Bounded(self.0)
+  }
+
+  fn into_i128(self) -> i128 {
+    self.0.into()
+  }
+
+  fn from_i128(value: i128) -> Result<Self, BoundedError> {
+    if value < MIN {
+      return Err(BoundedError::BelowMinimum { value, min: MIN });
+    }
+    if value > MAX {
+      return Err(BoundedError::AboveMaximum { value, max: MAX });
+    }
+    // 範囲内でも内部表現の型に収まらない値は、型の表せる端を限界として報告する
+    T::try_from(value).map(Self).map_err(|_| {
+      if value < T::MIN {
+        BoundedError::BelowMinimum { value, min: T::MIN }
+      } else {
+        BoundedError::AboveMaximum { value, max: T::MAX }
+      }
+    })
+  }
 }
 
-impl<const MAX: u64> From<BoundedU64<MAX>> for u64 {
-  fn from(value: BoundedU64<MAX>) -> Self {
-    value.get()
+impl<T: BoundedInt, const MIN: i128, const MAX: i128> Sum<Bounded<T, MIN, MAX>>
+  for Result<Bounded<T, MIN, MAX>, BoundedError>
+{
+  /// 0 から順に加算する。途中で範囲外になった時点、または 0 が範囲外の場合はエラーとなる。
+  fn sum<I: Iterator<Item = Bounded<T, MIN, MAX>>>(mut iter: I) -> Self {
+    iter.try_fold(Bounded::from_i128(0)?, Bounded::checked_add)
   }
 }
+
+/// `Bounded` を内部表現に持つ値オブジェクトへ、範囲検証付きの演算を実装する。
+///
+/// 範囲エラーは各型の `range_error` で `SessionValueError` に変換する。
+macro_rules! bounded_value {
+  ($name:ident) => {
+    impl $name {
+      /// 範囲検証付きの加算を行う。
+      ///
+      /// # Errors
+      /// 結果が範囲外の場合、対応する `SessionValueError` を返します。
+      pub fn checked_add(self, other: Self) -> Result<Self, SessionValueError> {
+        self.0.checked_add(other.0).map(Self).map_err(Self::range_error)
+      }
+
+      /// 範囲検証付きの減算を行う。
+      ///
+      /// # Errors
+      /// 結果が範囲外の場合、対応する `SessionValueError` を返します。
+      pub fn checked_sub(self, other: Self) -> Result<Self, SessionValueError> {
+        self.0.checked_sub(other.0).map(Self).map_err(Self::range_error)
+      }
+    }
+
+    impl std::iter::Sum<$name> for Result<$name, SessionValueError> {
+      fn sum<I: Iterator<Item = $name>>(iter: I) -> Self {
+        iter.map(|value| value.0).sum::<Result<_, _>>().map($name).map_err($name::range_error)
+      }
+    }
+  };
+}
+
+pub(crate) use bounded_value;
+
+#[cfg(test)]
+mod tests;
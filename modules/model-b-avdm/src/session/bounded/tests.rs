@@ -0,0 +1,52 @@
+use super::{Bounded, BoundedError};
+use crate::session::{KwhMilli, MoneyYen, SessionValueError};
+
+#[test]
+fn test_bounded_checked_arithmetic_reports_range_errors() {
+  type Percent = Bounded<i32, -100, 100>;
+  let sixty = Percent::new(60).unwrap();
+  let seventy = Percent::new(70).unwrap();
+
+  assert_eq!(sixty.checked_sub(seventy).unwrap().get(), -10);
+  assert_eq!(sixty.checked_add(seventy), Err(BoundedError::AboveMaximum { value: 130, max: 100 }));
+  assert_eq!(
+    Percent::new(-60).unwrap().checked_mul(Percent::new(2).unwrap()),
+    Err(BoundedError::BelowMinimum { value: -120, min: -100 })
+  );
+  assert_eq!(Percent::new(101), Err(BoundedError::AboveMaximum { value: 101, max: 100 }));
+
+  // 範囲が内部表現の型より広い場合は、型の表せる端で範囲外を報告する
+  type Offset = Bounded<u8, -1_000, 1_000>;
+  let (three, five) = (Offset::new(3).unwrap(), Offset::new(5).unwrap());
+  assert_eq!(three.checked_sub(five), Err(BoundedError::BelowMinimum { value: -2, min: 0 }));
+  assert_eq!(
+    Offset::new(200).unwrap().checked_add(Offset::new(100).unwrap()),
+    Err(BoundedError::AboveMaximum { value: 300, max: 255 })
+  );
+}
+
+#[test]
+fn test_bounded_sum_and_rebound() {
+  type Small = Bounded<u8, 0, 10>;
+  let values = [3, 4, 2].map(|value| Small::new(value).unwrap());
+  let sum: Result<Small, _> = values.into_iter().sum();
+  assert_eq!(sum.unwrap().get(), 9);
+  let overflow: Result<Small, _> = [Small::new(6).unwrap(), Small::new(5).unwrap()].into_iter().sum();
+  assert!(matches!(overflow, Err(BoundedError::AboveMaximum { value: 11, .. })));
+
+  let wide: Bounded<u8, 0, 200> = Small::new(9).unwrap().widen();
+  assert_eq!(wide.get(), 9);
+  assert!(Bounded::<u8, 0, 200>::new(150).unwrap().rebound::<0, 10>().is_err());
+}
+
+#[test]
+fn test_value_objects_share_bounded_arithmetic() {
+  let energies = [400_000, 500_000].map(|value| KwhMilli::try_new(value).unwrap());
+  let total: Result<KwhMilli, _> = energies.into_iter().sum();
+  assert_eq!(u64::from(total.unwrap()), 900_000);
+  let result = energies[1].checked_add(KwhMilli::try_new(600_000).unwrap());
+  assert!(matches!(result, Err(SessionValueError::EnergyOutOfRange { provided: 1_100_000, .. })));
+
+  let result = MoneyYen::try_new(100).unwrap().checked_sub(MoneyYen::try_new(150).unwrap());
+  assert_eq!(result, Err(SessionValueError::NegativeAmount { provided: -50 }));
+}
@@ -30,9 +30,7 @@ impl ChargeRatio {
     if self.is_zero() {
       return KwhMilli::zero();
     }
-    let energy_milli = energy.into_u128_milli();
-    let billed_milli = (energy_milli * self.numerator) / self.denominator;
-    KwhMilli::from_milli(billed_milli as u64)
+    KwhMilli::from_energy(energy.energy().scale(self.numerator, self.denominator))
   }
 
   /// 比率の分子を取得する。
@@ -30,14 +30,20 @@ impl ChargeableEnergy {
   }
 
   /// 課金窓に基づきエネルギーを割り当てる。
+  ///
+  /// # Errors
+  /// 課金比率が不正な場合、`SessionValueError::InvalidChargeRatio` を返します。
   pub fn allocate(total_energy: KwhMilli, window: ChargeableWindow) -> Result<Self, SessionValueError> {
     window.allocate_energy(total_energy)
   }
 
   /// 課金対象エネルギー同士を合成する。
+  ///
+  /// # Errors
+  /// 合算値が上限を超えた場合、`SessionValueError::EnergyOutOfRange` を返します。
   pub fn combine(self, other: Self) -> Result<Self, SessionValueError> {
-    let total = self.total.bounded_sum(other.total)?;
-    let billed = self.billed.bounded_sum(other.billed)?;
+    let total = self.total.checked_add(other.total)?;
+    let billed = self.billed.checked_add(other.billed)?;
     Self::new(total, billed)
   }
 
@@ -0,0 +1,122 @@
+use super::{
+  chargeable_energy::ChargeableEnergy,
+  chargeable_window::ChargeableWindow,
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  power_watts::PowerWatts,
+  units::{Energy, WATT_MILLISECOND},
+};
+
+/// 定電流（CC）から定電圧（CV）へ移行する充電カーブ。
+///
+/// 総エネルギーのうち `taper_percent` % を最大電力で供給し、残りは電力が 0 まで
+/// 直線的に減衰しながら供給されるとみなす。計算はすべて W・ms 単位の整数で行う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChargingCurve {
+  max_power:     PowerWatts,
+  taper_percent: u8,
+}
+
+impl ChargingCurve {
+  /// 最大電力と CV 移行点（総エネルギーに対する % ）から充電カーブを生成する。
+  ///
+  /// # Errors
+  /// 移行点が 1〜100 の範囲外の場合、`SessionValueError::InvalidTaperPoint` を返します。
+  pub fn new(max_power: PowerWatts, taper_percent: u8) -> Result<Self, SessionValueError> {
+    if !(1..=100).contains(&taper_percent) {
+      return Err(SessionValueError::InvalidTaperPoint { percent: taper_percent });
+    }
+    Ok(Self { max_power, taper_percent })
+  }
+
+  /// 最大電力を返す。
+  #[must_use]
+  pub fn max_power(&self) -> PowerWatts {
+    self.max_power
+  }
+
+  /// CV 移行点（総エネルギーに対する %）を返す。
+  #[must_use]
+  pub fn taper_percent(&self) -> u8 {
+    self.taper_percent
+  }
+
+  /// 充電カーブに沿って、無料時間外に供給されたエネルギーを課金対象として割り当てる。
+  ///
+  /// 最大電力ではセッション時間内に総エネルギーを供給しきれない場合、カーブが
+  /// 実測と矛盾するため一様分布による按分にフォールバックする。
+  ///
+  /// # Errors
+  /// 課金比率が不正な場合、`SessionValueError::InvalidChargeRatio` を返します。
+  pub fn allocate(
+    &self,
+    window: ChargeableWindow,
+    total_energy: KwhMilli,
+  ) -> Result<ChargeableEnergy, SessionValueError> {
+    if window.total_millis() == 0 || window.is_free() {
+      return Ok(ChargeableEnergy::free(total_energy));
+    }
+
+    let power = self.max_power.power();
+    let total = total_energy.energy().convert::<WATT_MILLISECOND>();
+    let constant = total.scale(u128::from(self.taper_percent), 100);
+    // 移行点は 100% 以下なので constant <= total
+    let taper = total.saturating_sub(constant);
+    // CC 区間と、平均が最大電力の半分となる CV 区間の所要時間がセッションに収まるか
+    if constant + taper + taper > power * window.total() {
+      return window.allocate_energy(total_energy);
+    }
+
+    let billed = Self::remaining_after(power * window.grace(), constant, taper);
+    ChargeableEnergy::new(total_energy, KwhMilli::from_energy(billed))
+  }
+
+  /// 最大電力で供給できたはずのエネルギー `at_max_power` の時点以降に供給されたエネルギーを求める。
+  fn remaining_after(
+    at_max_power: Energy<WATT_MILLISECOND>,
+    constant: Energy<WATT_MILLISECOND>,
+    taper: Energy<WATT_MILLISECOND>,
+  ) -> Energy<WATT_MILLISECOND> {
+    if let Some(constant_left) = constant.checked_sub(at_max_power) {
+      return constant_left + taper;
+    }
+    // CV 移行後の経過時間 × 最大電力。これが taper の 2 倍に達すると供給完了
+    let tapering = at_max_power.saturating_sub(constant);
+    match (taper + taper).checked_sub(tapering) {
+      // P(t) = P × (1 - x / D)、D = 2 × taper / P を x から D まで積分すると (2 × taper - P × x)² / (4 ×
+      // taper)
+      | Some(left) if left.value() > 0 => left.scale(left.value(), 4 * taper.value()),
+      | _ => Energy::new(0),
+    }
+  }
+}
+
+/// 計測サンプルがないときに、総エネルギーを時間軸へどう分布させるかの仮定。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnergyProfile {
+  /// セッション全体で一様に供給されたとみなす。
+  #[default]
+  Uniform,
+  /// 充電カーブに沿って供給されたとみなす。
+  Curve(ChargingCurve),
+}
+
+impl EnergyProfile {
+  /// 課金窓に基づきエネルギーを割り当てる。
+  ///
+  /// # Errors
+  /// 課金比率が不正な場合、`SessionValueError::InvalidChargeRatio` を返します。
+  pub fn allocate(
+    self,
+    window: ChargeableWindow,
+    total_energy: KwhMilli,
+  ) -> Result<ChargeableEnergy, SessionValueError> {
+    match self {
+      | Self::Uniform => window.allocate_energy(total_energy),
+      | Self::Curve(curve) => curve.allocate(window, total_energy),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests;
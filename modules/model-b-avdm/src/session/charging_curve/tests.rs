@@ -0,0 +1,48 @@
+use time::OffsetDateTime;
+
+use super::{ChargingCurve, EnergyProfile};
+use crate::session::{
+  BillingPolicy, GraceRule, PowerWatts, Session, SessionValueError, test_support::stop_with_policy,
+};
+
+fn stop_with_curve(max_power_watts: u32, taper_percent: u8, minutes: i64, energy_milli: u64) -> Session {
+  let curve = ChargingCurve::new(PowerWatts::try_new(max_power_watts).unwrap(), taper_percent).unwrap();
+  let policy = BillingPolicy::new(GraceRule::default(), EnergyProfile::Curve(curve));
+  stop_with_policy(policy, OffsetDateTime::now_utc(), minutes, energy_milli)
+}
+
+#[test]
+fn test_charging_curve_front_loads_energy_into_grace_period() {
+  // 50 kW で 80% まで CC 充電: 最初の5分で 50 kW * 5分 = 4.1666 kWh を供給済み
+  // 課金対象: 20 kWh - 4.1666 kWh = 15.8333 kWh -> 床で 15,833 milli-kWh（一様なら 16,666）
+  let closed = stop_with_curve(50_000, 80, 30, 20_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.billable_energy()), 15_833);
+  assert_eq!(u64::from(bill.amount_due()), 474);
+}
+
+#[test]
+fn test_charging_curve_grace_ends_during_taper() {
+  // 3 kWh のうち 1.5 kWh を CC（1.8分）、残りを CV で減衰しながら 3.6分かけて供給
+  // 無料5分の時点で残る減衰区間は0.4分で、その間の供給量 ≒ 18.5 milli-kWh
+  let closed = stop_with_curve(50_000, 50, 10, 3_000);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 18);
+
+  // 供給完了後に無料時間が終わる場合は課金対象なし
+  let closed = stop_with_curve(50_000, 50, 10, 2_000);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 0);
+}
+
+#[test]
+fn test_charging_curve_falls_back_to_uniform_when_inconsistent() {
+  // 7 kW では10分で 10 kWh を供給できないため、一様分布で按分する
+  let closed = stop_with_curve(7_000, 80, 10, 10_000);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 5_000);
+}
+
+#[test]
+fn test_charging_curve_rejects_invalid_taper_point() {
+  let power = PowerWatts::try_new(50_000).unwrap();
+  assert!(matches!(ChargingCurve::new(power, 0), Err(SessionValueError::InvalidTaperPoint { percent: 0 })));
+  assert!(matches!(ChargingCurve::new(power, 101), Err(SessionValueError::InvalidTaperPoint { percent: 101 })));
+}
@@ -0,0 +1,108 @@
+use time::OffsetDateTime;
+
+use super::{
+  amendment::{AmendmentReason, BillAmendment},
+  bill::SessionBill,
+  billing_policy::BillingPolicy,
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  session_id::SessionId,
+  tariff::Tariff,
+  timeline::SessionTimeline,
+};
+
+/// 停止済みで請求が確定したセッション。
+///
+/// 課金操作を持たないため、停止後の追加課金は型として存在しない。
+/// 確定値の到着による訂正のみ `amend` で履歴付きで行える。
+///
+/// ```compile_fail
+/// use model_b_avdm::session::{ClosedSession, KwhMilli};
+/// use time::OffsetDateTime;
+///
+/// fn charge_after_stop(closed: &ClosedSession, ended_at: OffsetDateTime) {
+///   // 停止済みセッションには課金操作がない
+///   let _ = closed.bill_snapshot(ended_at, KwhMilli::zero());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedSession {
+  id:         SessionId,
+  started_at: OffsetDateTime,
+  ended_at:   OffsetDateTime,
+  tariff:     Tariff,
+  policy:     BillingPolicy,
+  bill:       SessionBill,
+  amendments: Vec<BillAmendment>,
+}
+
+impl ClosedSession {
+  pub(crate) fn new(
+    id: SessionId,
+    started_at: OffsetDateTime,
+    ended_at: OffsetDateTime,
+    tariff: Tariff,
+    policy: BillingPolicy,
+    bill: SessionBill,
+  ) -> Self {
+    Self { id, started_at, ended_at, tariff, policy, bill, amendments: Vec::new() }
+  }
+
+  /// 停止後に届いた確定エネルギー量で請求を訂正する。
+  ///
+  /// 訂正前の請求と理由を履歴に残した上で請求を再計算する。
+  ///
+  /// # Errors
+  /// 再計算に失敗した場合、対応する `SessionValueError` を返します。
+  pub fn amend(self, corrected_energy: KwhMilli, reason: AmendmentReason) -> Result<Self, SessionValueError> {
+    let timeline = SessionTimeline::between(self.started_at, self.ended_at)?;
+    let energy = self.policy.allocate(timeline, corrected_energy)?;
+    let amended = SessionBill::settle_with(energy, &self.tariff)?;
+    let mut amendments = self.amendments;
+    amendments.push(BillAmendment::new(self.bill, amended.clone(), reason));
+
+    Ok(Self { bill: amended, amendments, ..self })
+  }
+
+  /// セッションを識別する。
+  #[must_use]
+  pub fn identity(&self) -> SessionId {
+    self.id
+  }
+
+  /// セッション開始時刻を返す。
+  #[must_use]
+  pub fn started_at(&self) -> OffsetDateTime {
+    self.started_at
+  }
+
+  /// 終了時刻を返す。
+  #[must_use]
+  pub fn ended_at(&self) -> OffsetDateTime {
+    self.ended_at
+  }
+
+  /// 料金表を返す。
+  #[must_use]
+  pub fn tariff(&self) -> &Tariff {
+    &self.tariff
+  }
+
+  /// 課金方針を返す。
+  #[must_use]
+  pub fn policy(&self) -> BillingPolicy {
+    self.policy
+  }
+
+  /// 確定した請求（訂正済みの場合は最新の訂正後の請求）を返す。
+  #[must_use]
+  pub fn bill(&self) -> &SessionBill {
+    &self.bill
+  }
+
+  /// 請求訂正の履歴を古い順に返す。
+  #[must_use]
+  pub fn amendments(&self) -> &[BillAmendment] {
+    &self.amendments
+  }
+}
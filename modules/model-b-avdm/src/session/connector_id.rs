@@ -0,0 +1,17 @@
+/// 充電器のコネクタを識別する番号。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ConnectorId(pub(super) u32);
+
+impl ConnectorId {
+  /// 番号から新しい `ConnectorId` を生成する。
+  #[must_use]
+  pub fn new(value: u32) -> Self {
+    Self(value)
+  }
+}
+
+impl From<ConnectorId> for u32 {
+  fn from(value: ConnectorId) -> Self {
+    value.0
+  }
+}
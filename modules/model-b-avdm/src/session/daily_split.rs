@@ -0,0 +1,130 @@
+use time::{Date, Duration, Time, UtcOffset};
+
+use super::{
+  base::Session, charging_curve::EnergyProfile, errors::SessionValueError, kwh_milli::KwhMilli, money_yen::MoneyYen,
+};
+
+/// 1 暦日分に按分された売上。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyRevenue {
+  date:            Date,
+  billable_energy: KwhMilli,
+  amount:          MoneyYen,
+}
+
+impl DailyRevenue {
+  /// 暦日を返す。
+  #[must_use]
+  pub fn date(&self) -> Date {
+    self.date
+  }
+
+  /// その日に按分された課金対象エネルギーを返す。
+  #[must_use]
+  pub fn billable_energy(&self) -> KwhMilli {
+    self.billable_energy
+  }
+
+  /// その日に按分された金額を返す。
+  #[must_use]
+  pub fn amount(&self) -> MoneyYen {
+    self.amount
+  }
+}
+
+/// 停止済みセッションの請求を暦日ごとに按分した結果。
+///
+/// 請求と同じくエネルギーが一様に供給されたとみなし、無料ルール（時間・エネルギーのうち
+/// より多くを無料にする側）が終わった時点以降の時間の長さに比例して配分する。端数は
+/// 最大剰余法で割り当てるため、各日の合計は元の請求と必ず一致する。
+/// 充電カーブを仮定する課金方針は時間に比例しないため按分しない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyRevenueSplit {
+  days: Vec<DailyRevenue>,
+}
+
+impl DailyRevenueSplit {
+  /// 指定オフセットの暦日境界で停止済みセッションの請求を按分する。
+  ///
+  /// # Errors
+  /// - セッションが停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - 開始・終了時刻が不正な場合、`SessionValueError::InvalidTimeline` を返します。
+  /// - 課金方針が充電カーブを仮定する場合、`SessionValueError::UnsupportedDailySplit` を返します。
+  pub fn of(session: &Session, offset: UtcOffset) -> Result<Self, SessionValueError> {
+    let Some(closed) = session.as_closed() else {
+      return Err(SessionValueError::NotClosed { session_id: session.identity() });
+    };
+    let (started_at, ended_at, bill) = (closed.started_at(), closed.ended_at(), closed.bill());
+    if ended_at <= started_at {
+      return Err(SessionValueError::InvalidTimeline { started_at, ended_at });
+    }
+
+    let policy = closed.policy();
+    if policy.profile() != EnergyProfile::Uniform {
+      return Err(SessionValueError::UnsupportedDailySplit { session_id: closed.identity() });
+    }
+
+    // 一様供給では無料エネルギーを使い切る時点は総エネルギーに対する割合で決まる
+    let elapsed = u128::try_from((ended_at - started_at).whole_milliseconds()).unwrap_or(0);
+    let total = u128::from(u64::from(bill.total_energy()));
+    let allowance = u128::from(u64::from(policy.grace().free_energy())).min(total);
+    let by_energy = (elapsed * allowance).checked_div(total).unwrap_or(0);
+    let grace = policy.grace().grace_period().millis().max(by_energy);
+    let grace = i64::try_from(grace).unwrap_or(i64::MAX);
+    let chargeable_from = started_at.checked_add(Duration::milliseconds(grace)).unwrap_or(ended_at);
+    let mut dates = Vec::new();
+    let mut weights = Vec::new();
+    let mut cursor = started_at;
+    while cursor < ended_at {
+      let date = cursor.to_offset(offset).date();
+      let next_midnight = date.next_day().map(|next| next.with_time(Time::MIDNIGHT).assume_offset(offset));
+      let day_end = next_midnight.map_or(ended_at, |next| next.min(ended_at));
+      let overlap = day_end - cursor.max(chargeable_from);
+      dates.push(date);
+      weights.push(u128::try_from(overlap.whole_milliseconds()).unwrap_or(0));
+      cursor = day_end;
+    }
+
+    let energies = largest_remainder(u64::from(bill.billable_energy()), &weights);
+    let amounts = largest_remainder(u64::from(bill.amount_due()), &weights);
+    let days = dates
+      .into_iter()
+      .zip(energies.into_iter().zip(amounts))
+      .map(|(date, (energy, amount))| DailyRevenue {
+        date,
+        billable_energy: KwhMilli::from_milli(energy),
+        amount: MoneyYen::from_yen(amount),
+      })
+      .collect();
+    Ok(Self { days })
+  }
+
+  /// 暦日順の按分結果を返す。
+  #[must_use]
+  pub fn days(&self) -> &[DailyRevenue] {
+    &self.days
+  }
+}
+
+/// `total` を重みに比例して整数で配分する。
+///
+/// 端数は剰余の大きい順（同値なら先頭優先）に 1 ずつ割り当てる。
+fn largest_remainder(total: u64, weights: &[u128]) -> Vec<u64> {
+  let weight_sum: u128 = weights.iter().sum();
+  if weight_sum == 0 {
+    return vec![0; weights.len()];
+  }
+
+  let total = u128::from(total);
+  let mut parts: Vec<u64> = weights.iter().map(|weight| (total * weight / weight_sum) as u64).collect();
+  let mut order: Vec<usize> = (0..weights.len()).collect();
+  order.sort_by_key(|&index| std::cmp::Reverse(total * weights[index] % weight_sum));
+  let distributed: u128 = parts.iter().map(|part| u128::from(*part)).sum();
+  for &index in order.iter().take((total - distributed) as usize) {
+    parts[index] += 1;
+  }
+  parts
+}
+
+#[cfg(test)]
+mod tests;
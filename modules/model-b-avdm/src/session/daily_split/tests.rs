@@ -0,0 +1,84 @@
+use time::{
+  UtcOffset,
+  macros::{date, datetime},
+};
+
+use super::DailyRevenueSplit;
+use crate::session::{
+  BillingPolicy, ChargingCurve, EnergyProfile, FreeEnergyAllowance, GraceRule, KwhMilli, PowerWatts, SessionValueError,
+  test_support::{closed_session, create_test_session, stop_with_policy},
+};
+
+#[test]
+fn test_daily_split_apportions_across_midnight_in_jst() {
+  let jst = UtcOffset::from_hms(9, 0, 0).unwrap();
+  // JST 23:50 開始・00:30 終了（UTC では同日内）、10 kWh
+  let closed = closed_session(1, datetime!(2025-10-20 14:50 UTC), 40, 10_000);
+  let bill = closed.statement().unwrap().clone();
+  // 課金対象は 35/40 -> 8,750 milli-kWh、262円
+  assert_eq!(u64::from(bill.amount_due()), 262);
+
+  let split = DailyRevenueSplit::of(&closed, jst).unwrap();
+  let days = split.days();
+  assert_eq!(days.len(), 2);
+  // 課金対象時間は 20日に5分、21日に30分
+  assert_eq!(days[0].date(), date!(2025 - 10 - 20));
+  assert_eq!(u64::from(days[0].billable_energy()), 1_250);
+  assert_eq!(u64::from(days[1].billable_energy()), 7_500);
+  // 262円 * 5/35 = 37.4、262円 * 30/35 = 224.6 -> 端数1円は剰余の大きい21日へ
+  assert_eq!(u64::from(days[0].amount()), 37);
+  assert_eq!(u64::from(days[1].amount()), 225);
+
+  // UTC で見れば1日に収まる
+  let split = DailyRevenueSplit::of(&closed, UtcOffset::UTC).unwrap();
+  assert_eq!(split.days().len(), 1);
+  assert_eq!(split.days()[0].amount(), bill.amount_due());
+}
+
+#[test]
+fn test_daily_split_assigns_nothing_to_grace_only_day() {
+  let jst = UtcOffset::from_hms(9, 0, 0).unwrap();
+  // JST 23:58 開始: 20日は無料時間のみ
+  let closed = closed_session(1, datetime!(2025-10-20 23:58 +09:00), 10, 10_000);
+
+  let split = DailyRevenueSplit::of(&closed, jst).unwrap();
+  let amounts: Vec<_> = split.days().iter().map(|day| u64::from(day.amount())).collect();
+  assert_eq!(amounts, vec![0, 150]);
+}
+
+#[test]
+fn test_daily_split_starts_after_free_energy_is_used_up() {
+  let jst = UtcOffset::from_hms(9, 0, 0).unwrap();
+  // JST 23:30 開始・60分・10 kWh のうち最初の 5 kWh が無料。一様供給なら 00:00 に使い切る
+  let allowance = FreeEnergyAllowance::new(KwhMilli::try_new(5_000).unwrap());
+  let closed = stop_with_policy(
+    BillingPolicy::with_grace(GraceRule::Energy(allowance)),
+    datetime!(2025-10-20 23:30 +09:00),
+    60,
+    10_000,
+  );
+  assert_eq!(u64::from(closed.statement().unwrap().amount_due()), 150);
+
+  let split = DailyRevenueSplit::of(&closed, jst).unwrap();
+  let amounts: Vec<_> = split.days().iter().map(|day| u64::from(day.amount())).collect();
+  let energies: Vec<_> = split.days().iter().map(|day| u64::from(day.billable_energy())).collect();
+  assert_eq!(amounts, vec![0, 150]);
+  assert_eq!(energies, vec![0, 5_000]);
+}
+
+#[test]
+fn test_daily_split_rejects_charging_curve_policy() {
+  let curve = ChargingCurve::new(PowerWatts::try_new(50_000).unwrap(), 80).unwrap();
+  let policy = BillingPolicy::new(GraceRule::default(), EnergyProfile::Curve(curve));
+  let closed = stop_with_policy(policy, datetime!(2025-10-20 23:30 +09:00), 60, 10_000);
+
+  let result = DailyRevenueSplit::of(&closed, UtcOffset::UTC);
+  assert_eq!(result, Err(SessionValueError::UnsupportedDailySplit { session_id: closed.identity() }));
+}
+
+#[test]
+fn test_daily_split_rejects_active_session() {
+  let (session, _) = create_test_session();
+  let result = DailyRevenueSplit::of(&session, UtcOffset::UTC);
+  assert!(matches!(result, Err(SessionValueError::NotClosed { .. })));
+}
@@ -0,0 +1,29 @@
+/// 充電するドライバー（利用者）の識別子を UUID で表す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriverId(pub(super) uuid::Uuid);
+
+impl DriverId {
+  /// UUID から新しい `DriverId` を生成する。
+  ///
+  /// # Returns
+  /// 生成された `DriverId`。
+  #[must_use]
+  pub fn new(id: uuid::Uuid) -> Self {
+    Self(id)
+  }
+
+  /// 内部の UUID を取り出す。
+  ///
+  /// # Returns
+  /// 保持している UUID。
+  #[must_use]
+  pub fn into_uuid(self) -> uuid::Uuid {
+    self.0
+  }
+}
+
+impl From<DriverId> for uuid::Uuid {
+  fn from(value: DriverId) -> Self {
+    value.0
+  }
+}
@@ -1,9 +1,13 @@
+use serde_json::{Value, json};
 use thiserror::Error;
-use time::OffsetDateTime;
-use crate::session::KwhMilli;
-use super::session_id::SessionId;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+use super::{account::AccountId, locale::Locale, session_id::SessionId};
 
 /// セッション操作中に発生し得るドメインエラー。
+///
+/// `Display` は日本語のメッセージを返す。API 応答などでは `code` と `details` を、
+/// 利用者向けの表示では `message` でロケールを選んで使う。
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum SessionValueError {
   /// 負のエネルギー値が入力された。
@@ -12,9 +16,83 @@ pub enum SessionValueError {
     /// 入力された値。
     provided: i64,
   },
+  /// 演算の結果、金額が負になった。
+  #[error("金額は負にできません (結果: {provided})")]
+  NegativeAmount {
+    /// 演算結果の値。
+    provided: i64,
+  },
+  /// 文字列の書式が解析できなかった。
+  #[error("{input:?} を解析できません (書式の例: {expected})")]
+  InvalidFormat {
+    /// 入力文字列。
+    input:    String,
+    /// 期待する書式の例。
+    expected: &'static str,
+  },
+  /// 実際のセッションに nil のセッションIDが指定された。
+  #[error("実際のセッションに nil のセッションIDは使えません")]
+  NilSessionId,
+  /// セッションまたは外部取引IDが既に別の相手と対応付けられていた。
+  #[error("セッション {session_id} と取引ID {transaction_id} は既に別の相手と対応付けられています")]
+  TransactionIdConflict {
+    /// 対象セッションID。
+    session_id:     SessionId,
+    /// 外部取引ID。
+    transaction_id: i32,
+  },
+  /// 払い出せる外部取引IDが尽きた。
+  #[error("払い出せる取引IDが残っていません")]
+  TransactionIdExhausted,
+  /// 計器の指示値が周期以上だった。
+  #[error("計器の指示値 {reading} Wh が周期 {modulus} Wh 以上です")]
+  MeterReadingOutOfRange {
+    /// 指示値（Wh）。
+    reading: u64,
+    /// 計器の周期（Wh）。
+    modulus: u64,
+  },
+  /// 一周しない計器で停止値が開始値を下回った。
+  #[error("計器の停止値 {stop} Wh が開始値 {start} Wh を下回っています")]
+  MeterRegisterDecreased {
+    /// 開始値（Wh）。
+    start: u64,
+    /// 停止値（Wh）。
+    stop:  u64,
+  },
   /// 単価が 0 以下だった。
   #[error("単価は1円/kWh以上である必要があります")]
   NonPositiveRate,
+  /// 電力が 0 以下だった。
+  #[error("電力は1W以上である必要があります")]
+  NonPositivePower,
+  /// 充電カーブの CV 移行点が範囲外だった。
+  #[error("CV 移行点は 1〜100% で指定する必要があります (入力: {percent})")]
+  InvalidTaperPoint {
+    /// 入力された移行点（%）。
+    percent: u8,
+  },
+  /// 料金表に段が 1 つもなかった。
+  #[error("料金表には少なくとも1つの段が必要です")]
+  EmptyTariff,
+  /// 料金表の段の適用開始量が不正だった。
+  #[error(
+    "料金表の {index} 段目の適用開始量 {threshold} が不正です（最初の段は0から、以降は昇順である必要があります）"
+  )]
+  InvalidTierThreshold {
+    /// 段の位置（0 始まり）。
+    index:     usize,
+    /// 適用開始量（ミリkWh）。
+    threshold: u64,
+  },
+  /// 最低料金が上限料金を超えていた。
+  #[error("最低料金 {minimum} 円が上限料金 {cap} 円を超えています")]
+  InvalidPriceLimits {
+    /// 最低料金（円）。
+    minimum: u64,
+    /// 上限料金（円）。
+    cap:     u64,
+  },
   /// 終了時刻が開始時刻以前だった。
   #[error("終了時刻 {ended_at} は開始時刻 {started_at} より後でなければなりません")]
   InvalidTimeline {
@@ -30,7 +108,7 @@ pub enum SessionValueError {
     provided: u128,
   },
   /// 既に停止済みセッションに操作しようとした。
-  #[error("セッション {session_id:?} は既に停止済みです")]
+  #[error("セッション {session_id} は既に停止済みです")]
   AlreadyClosed {
     /// 対象セッションID。
     session_id: SessionId,
@@ -59,4 +137,299 @@ pub enum SessionValueError {
     /// 比率の分母。
     denominator: u128,
   },
+  /// 停止していないセッションに停止済み前提の操作をしようとした。
+  #[error("セッション {session_id} はまだ停止していません")]
+  NotClosed {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 請求期間の終了時刻が開始時刻以前だった。
+  #[error("請求期間の終了 {ends_at} は開始 {starts_at} より後でなければなりません")]
+  InvalidBillingPeriod {
+    /// 期間開始時刻。
+    starts_at: OffsetDateTime,
+    /// 期間終了時刻。
+    ends_at:   OffsetDateTime,
+  },
+  /// 暦月を表現できなかった。
+  #[error("暦月 {year}年{month}月 は表現できません")]
+  InvalidCalendarMonth {
+    /// 年。
+    year:  i32,
+    /// 月（1〜12）。
+    month: u8,
+  },
+  /// 別アカウントのセッションを明細に含めようとした。
+  #[error("セッション {session_id} はアカウント {account_id} に紐づいていません")]
+  ForeignSession {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// 明細のアカウントID。
+    account_id: AccountId,
+  },
+  /// 請求期間外に終了したセッションを明細に含めようとした。
+  #[error("セッション {session_id} の終了時刻 {ended_at} は請求期間外です")]
+  OutsideBillingPeriod {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// セッション終了時刻。
+    ended_at:   OffsetDateTime,
+  },
+  /// 同一セッションを明細に重複して含めようとした。
+  #[error("セッション {session_id} は既に明細に計上されています")]
+  DuplicateStatementLine {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 明細の合計値が表現できる上限を超えた。
+  #[error("セッション {session_id} を加えると明細の合計値が上限を超過します")]
+  StatementTotalOverflow {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 暦日按分で表現できない課金方針（充電カーブ）のセッションを按分しようとした。
+  #[error("セッション {session_id} の課金方針は暦日ごとに按分できません (充電カーブには未対応)")]
+  UnsupportedDailySplit {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 再接続として扱えないセッションを論理セッションに含めようとした。
+  #[error("セッション {session_id} は再接続として扱えません")]
+  ReconnectNotEligible {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 請求訂正の理由が空だった。
+  #[error("請求訂正には理由が必要です")]
+  EmptyAmendmentReason,
+  /// 利用可能残高を超える与信枠を確保しようとした。
+  #[error("残高が不足しています (要求: {requested} / 利用可能: {available})")]
+  InsufficientBalance {
+    /// 要求した与信枠。
+    requested: u64,
+    /// 利用可能な残高。
+    available: u64,
+  },
+  /// 同一セッションに与信枠を重複して確保しようとした。
+  #[error("セッション {session_id} には既に与信枠が確保されています")]
+  DuplicateHold {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 与信枠が確保されていないセッションを精算しようとした。
+  #[error("セッション {session_id} の与信枠が見つかりません")]
+  HoldNotFound {
+    /// 対象セッションID。
+    session_id: SessionId,
+  },
+  /// 見込み金額が与信枠を超えた。
+  #[error("セッション {session_id} の見込み金額が与信枠を超過しました (見込み: {projected} / 与信枠: {hold})")]
+  HoldExceeded {
+    /// 対象セッションID。
+    session_id: SessionId,
+    /// 見込み金額。
+    projected:  u64,
+    /// 与信枠。
+    hold:       u64,
+  },
+}
+
+impl SessionValueError {
+  /// バリアントごとに固定の、機械可読なエラーコードを返す。
+  #[must_use]
+  pub fn code(&self) -> &'static str {
+    match self {
+      | Self::NegativeEnergy { .. } => "ENERGY_NEGATIVE",
+      | Self::NegativeAmount { .. } => "AMOUNT_NEGATIVE",
+      | Self::InvalidFormat { .. } => "VALUE_INVALID_FORMAT",
+      | Self::NilSessionId => "SESSION_ID_NIL",
+      | Self::TransactionIdConflict { .. } => "TRANSACTION_ID_CONFLICT",
+      | Self::TransactionIdExhausted => "TRANSACTION_ID_EXHAUSTED",
+      | Self::MeterReadingOutOfRange { .. } => "METER_READING_OUT_OF_RANGE",
+      | Self::MeterRegisterDecreased { .. } => "METER_REGISTER_DECREASED",
+      | Self::NonPositiveRate => "RATE_NOT_POSITIVE",
+      | Self::NonPositivePower => "POWER_NOT_POSITIVE",
+      | Self::InvalidTaperPoint { .. } => "CURVE_INVALID_TAPER_POINT",
+      | Self::EmptyTariff => "TARIFF_EMPTY",
+      | Self::InvalidTierThreshold { .. } => "TARIFF_INVALID_TIER_THRESHOLD",
+      | Self::InvalidPriceLimits { .. } => "TARIFF_INVALID_PRICE_LIMITS",
+      | Self::InvalidTimeline { .. } => "SESSION_INVALID_TIMELINE",
+      | Self::AmountOverflow { .. } => "AMOUNT_OVERFLOW",
+      | Self::AlreadyClosed { .. } => "SESSION_ALREADY_CLOSED",
+      | Self::EnergyOutOfRange { .. } => "ENERGY_OUT_OF_RANGE",
+      | Self::AmountOutOfRange { .. } => "AMOUNT_OUT_OF_RANGE",
+      | Self::InvalidChargeRatio { .. } => "CHARGE_RATIO_INVALID",
+      | Self::NotClosed { .. } => "SESSION_NOT_CLOSED",
+      | Self::InvalidBillingPeriod { .. } => "BILLING_PERIOD_INVALID",
+      | Self::InvalidCalendarMonth { .. } => "BILLING_PERIOD_INVALID_MONTH",
+      | Self::ForeignSession { .. } => "STATEMENT_FOREIGN_SESSION",
+      | Self::OutsideBillingPeriod { .. } => "STATEMENT_OUTSIDE_PERIOD",
+      | Self::DuplicateStatementLine { .. } => "STATEMENT_DUPLICATE_LINE",
+      | Self::StatementTotalOverflow { .. } => "STATEMENT_TOTAL_OVERFLOW",
+      | Self::UnsupportedDailySplit { .. } => "DAILY_SPLIT_UNSUPPORTED_POLICY",
+      | Self::ReconnectNotEligible { .. } => "RECONNECT_NOT_ELIGIBLE",
+      | Self::EmptyAmendmentReason => "AMENDMENT_REASON_EMPTY",
+      | Self::InsufficientBalance { .. } => "WALLET_INSUFFICIENT_BALANCE",
+      | Self::DuplicateHold { .. } => "WALLET_DUPLICATE_HOLD",
+      | Self::HoldNotFound { .. } => "WALLET_HOLD_NOT_FOUND",
+      | Self::HoldExceeded { .. } => "WALLET_HOLD_EXCEEDED",
+    }
+  }
+
+  /// エラーに付随する値を、フィールド名をキーとする JSON オブジェクトで返す。
+  ///
+  /// フィールド名はバリアントのフィールド名と一致し、メッセージの言語によらず一定である。
+  /// 数値は数値のまま、識別子はハイフン区切りの UUID、時刻は RFC 3339 の文字列で表す。
+  #[must_use]
+  pub fn details(&self) -> Value {
+    match self {
+      | Self::NegativeEnergy { provided } | Self::NegativeAmount { provided } => json!({ "provided": provided }),
+      | Self::InvalidFormat { input, expected } => json!({ "input": input, "expected": expected }),
+      | Self::NilSessionId
+      | Self::TransactionIdExhausted
+      | Self::NonPositiveRate
+      | Self::NonPositivePower
+      | Self::EmptyTariff
+      | Self::EmptyAmendmentReason => json!({}),
+      | Self::MeterReadingOutOfRange { reading, modulus } => json!({ "reading": reading, "modulus": modulus }),
+      | Self::MeterRegisterDecreased { start, stop } => json!({ "start": start, "stop": stop }),
+      | Self::TransactionIdConflict { session_id, transaction_id } => {
+        json!({ "session_id": session_id.to_string(), "transaction_id": transaction_id })
+      },
+      | Self::InvalidTaperPoint { percent } => json!({ "percent": percent }),
+      | Self::InvalidTierThreshold { index, threshold } => json!({ "index": index, "threshold": threshold }),
+      | Self::InvalidPriceLimits { minimum, cap } => json!({ "minimum": minimum, "cap": cap }),
+      | Self::InvalidTimeline { started_at, ended_at } => {
+        json!({ "started_at": timestamp(*started_at), "ended_at": timestamp(*ended_at) })
+      },
+      | Self::AmountOverflow { provided } => json!({ "provided": wide_number(*provided) }),
+      | Self::EnergyOutOfRange { provided, max } | Self::AmountOutOfRange { provided, max } => {
+        json!({ "provided": provided, "max": max })
+      },
+      | Self::InvalidChargeRatio { numerator, denominator } => {
+        json!({ "numerator": wide_number(*numerator), "denominator": wide_number(*denominator) })
+      },
+      | Self::AlreadyClosed { session_id }
+      | Self::NotClosed { session_id }
+      | Self::DuplicateStatementLine { session_id }
+      | Self::StatementTotalOverflow { session_id }
+      | Self::UnsupportedDailySplit { session_id }
+      | Self::ReconnectNotEligible { session_id }
+      | Self::DuplicateHold { session_id }
+      | Self::HoldNotFound { session_id } => json!({ "session_id": session_id.to_string() }),
+      | Self::InvalidBillingPeriod { starts_at, ends_at } => {
+        json!({ "starts_at": timestamp(*starts_at), "ends_at": timestamp(*ends_at) })
+      },
+      | Self::InvalidCalendarMonth { year, month } => json!({ "year": year, "month": month }),
+      | Self::ForeignSession { session_id, account_id } => {
+        json!({ "session_id": session_id.to_string(), "account_id": account_id.to_string() })
+      },
+      | Self::OutsideBillingPeriod { session_id, ended_at } => {
+        json!({ "session_id": session_id.to_string(), "ended_at": timestamp(*ended_at) })
+      },
+      | Self::InsufficientBalance { requested, available } => {
+        json!({ "requested": requested, "available": available })
+      },
+      | Self::HoldExceeded { session_id, projected, hold } => {
+        json!({ "session_id": session_id.to_string(), "projected": projected, "hold": hold })
+      },
+    }
+  }
+
+  /// 指定したロケールの言語でメッセージを返す（日本語は `Display` と同じ）。
+  #[must_use]
+  pub fn message(&self, locale: Locale) -> String {
+    match locale {
+      | Locale::JaJp => self.to_string(),
+      | Locale::EnUs => self.english_message(),
+    }
+  }
+
+  fn english_message(&self) -> String {
+    match self {
+      | Self::NegativeEnergy { provided } => format!("Energy cannot be negative (provided: {provided})"),
+      | Self::NegativeAmount { provided } => format!("Amount cannot be negative (result: {provided})"),
+      | Self::InvalidFormat { input, expected } => format!("Cannot parse {input:?} (expected format: {expected})"),
+      | Self::NilSessionId => "A nil session id cannot be used for a real session".to_owned(),
+      | Self::TransactionIdConflict { session_id, transaction_id } => {
+        format!("Session {session_id} or transaction id {transaction_id} is already linked to another counterpart")
+      },
+      | Self::TransactionIdExhausted => "No transaction ids are left to assign".to_owned(),
+      | Self::MeterReadingOutOfRange { reading, modulus } => {
+        format!("Meter reading of {reading} Wh is not below the register modulus of {modulus} Wh")
+      },
+      | Self::MeterRegisterDecreased { start, stop } => {
+        format!("Meter stop value of {stop} Wh is below the meter start value of {start} Wh")
+      },
+      | Self::NonPositiveRate => "Rate must be at least 1 yen/kWh".to_owned(),
+      | Self::NonPositivePower => "Power must be at least 1 W".to_owned(),
+      | Self::InvalidTaperPoint { percent } => {
+        format!("CV transition point must be between 1 and 100% (provided: {percent})")
+      },
+      | Self::EmptyTariff => "A tariff needs at least one tier".to_owned(),
+      | Self::InvalidTierThreshold { index, threshold } => format!(
+        "Tier {index} starts at invalid threshold {threshold} (the first tier must start at 0 and thresholds must \
+         increase)"
+      ),
+      | Self::InvalidPriceLimits { minimum, cap } => {
+        format!("Minimum fee of {minimum} yen exceeds the price cap of {cap} yen")
+      },
+      | Self::InvalidTimeline { started_at, ended_at } => {
+        format!("End time {ended_at} must be after start time {started_at}")
+      },
+      | Self::AmountOverflow { provided } => format!("Amount exceeds the representable limit (provided: {provided})"),
+      | Self::AlreadyClosed { session_id } => format!("Session {session_id} is already closed"),
+      | Self::EnergyOutOfRange { provided, max } => {
+        format!("Energy exceeds the upper limit (provided: {provided} / max: {max})")
+      },
+      | Self::AmountOutOfRange { provided, max } => {
+        format!("Amount exceeds the upper limit (provided: {provided} / max: {max})")
+      },
+      | Self::InvalidChargeRatio { numerator, denominator } => {
+        format!("Invalid charge ratio (numerator: {numerator}, denominator: {denominator})")
+      },
+      | Self::NotClosed { session_id } => format!("Session {session_id} has not been closed yet"),
+      | Self::InvalidBillingPeriod { starts_at, ends_at } => {
+        format!("Billing period end {ends_at} must be after its start {starts_at}")
+      },
+      | Self::InvalidCalendarMonth { year, month } => format!("Calendar month {year}-{month:02} cannot be represented"),
+      | Self::ForeignSession { session_id, account_id } => {
+        format!("Session {session_id} does not belong to account {account_id}")
+      },
+      | Self::OutsideBillingPeriod { session_id, ended_at } => {
+        format!("Session {session_id} ended at {ended_at}, outside the billing period")
+      },
+      | Self::DuplicateStatementLine { session_id } => {
+        format!("Session {session_id} is already included in the statement")
+      },
+      | Self::StatementTotalOverflow { session_id } => {
+        format!("Adding session {session_id} makes the statement total exceed the representable limit")
+      },
+      | Self::UnsupportedDailySplit { session_id } => {
+        format!("The billing policy of session {session_id} cannot be split by day (charging curves are not supported)")
+      },
+      | Self::ReconnectNotEligible { session_id } => format!("Session {session_id} cannot be treated as a reconnect"),
+      | Self::EmptyAmendmentReason => "A bill amendment requires a reason".to_owned(),
+      | Self::InsufficientBalance { requested, available } => {
+        format!("Insufficient balance (requested: {requested} / available: {available})")
+      },
+      | Self::DuplicateHold { session_id } => format!("Session {session_id} already has a hold"),
+      | Self::HoldNotFound { session_id } => format!("No hold found for session {session_id}"),
+      | Self::HoldExceeded { session_id, projected, hold } => {
+        format!("Projected amount for session {session_id} exceeds its hold (projected: {projected} / hold: {hold})")
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests;
+
+/// JSON の数値で表せない `u128` は 10 進の文字列で表す。
+fn wide_number(value: u128) -> Value {
+  u64::try_from(value).map_or_else(|_| Value::String(value.to_string()), Value::from)
+}
+
+fn timestamp(at: OffsetDateTime) -> String {
+  at.format(&Rfc3339).unwrap_or_else(|_| at.to_string())
 }
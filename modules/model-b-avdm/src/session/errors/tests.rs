@@ -0,0 +1,45 @@
+use serde_json::json;
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+
+use super::SessionValueError;
+use crate::session::{AccountId, KwhMilli, Locale, SessionId, test_support::create_test_session};
+
+#[test]
+fn test_errors_expose_stable_codes_and_localized_messages() {
+  let (session, started_at) = create_test_session();
+  let closed = session.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  let error = closed.bill_snapshot(started_at + Duration::minutes(20), KwhMilli::try_new(20_000).unwrap()).unwrap_err();
+
+  assert_eq!(error.code(), "SESSION_ALREADY_CLOSED");
+  assert_eq!(error.details(), json!({ "session_id": "00000000-0000-0000-0000-000000000000" }));
+  assert_eq!(error.message(Locale::JaJp), error.to_string());
+  assert_eq!(error.message(Locale::EnUs), "Session 00000000-0000-0000-0000-000000000000 is already closed");
+
+  let error = SessionValueError::InsufficientBalance { requested: 500, available: 300 };
+  assert_eq!(error.code(), "WALLET_INSUFFICIENT_BALANCE");
+  assert_eq!(error.details(), json!({ "requested": 500, "available": 300 }));
+  assert_eq!(error.message(Locale::EnUs), "Insufficient balance (requested: 500 / available: 300)");
+}
+
+#[test]
+fn test_error_details_keep_numbers_and_format_identifiers() {
+  let error = SessionValueError::InvalidTimeline {
+    started_at: datetime!(2025-10-20 10:00 UTC),
+    ended_at:   datetime!(2025-10-20 09:00 UTC),
+  };
+  assert_eq!(error.details(), json!({ "started_at": "2025-10-20T10:00:00Z", "ended_at": "2025-10-20T09:00:00Z" }));
+
+  let error = SessionValueError::AmountOverflow { provided: u128::from(u64::MAX) + 1 };
+  assert_eq!(error.details(), json!({ "provided": "18446744073709551616" }));
+
+  let error = SessionValueError::ForeignSession {
+    session_id: SessionId::new(Uuid::from_u128(1)),
+    account_id: AccountId::new(Uuid::from_u128(2)),
+  };
+  assert_eq!(error.details()["account_id"], "00000000-0000-0000-0000-000000000002");
+  assert_eq!(
+    error.to_string(),
+    "セッション 00000000-0000-0000-0000-000000000001 はアカウント 00000000-0000-0000-0000-000000000002 に紐づいていません"
+  );
+}
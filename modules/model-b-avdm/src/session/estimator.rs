@@ -0,0 +1,123 @@
+use time::{Duration, OffsetDateTime};
+
+use super::{
+  MAX_KWH_MILLI,
+  bill::SessionBill,
+  billing_policy::BillingPolicy,
+  errors::SessionValueError,
+  grace_rule::GraceRule,
+  kwh_milli::KwhMilli,
+  money_yen::MoneyYen,
+  power_watts::PowerWatts,
+  rate::RateYenPerKwh,
+  tariff::Tariff,
+  timeline::SessionTimeline,
+  units::{Energy, Millis, WATT_HOUR, WATT_MILLISECOND},
+};
+
+/// 充電開始前に料金を見積もるサービス。
+///
+/// 実際のセッションと同じタイムライン・無料ルール・料金表を用いるため、
+/// 見積もりと停止時の請求は同じ入力に対して一致する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostEstimator {
+  tariff: Tariff,
+  policy: BillingPolicy,
+}
+
+impl CostEstimator {
+  /// 単価を指定して見積もりサービスを生成する（無料ルールは開始から5分）。
+  #[must_use]
+  pub fn new(rate: RateYenPerKwh) -> Self {
+    Self::with_policy(rate, BillingPolicy::default())
+  }
+
+  /// 単価と無料ルールを指定して見積もりサービスを生成する。
+  #[must_use]
+  pub fn with_grace(rate: RateYenPerKwh, grace: GraceRule) -> Self {
+    Self::with_policy(rate, BillingPolicy::with_grace(grace))
+  }
+
+  /// 単価と課金方針を指定して見積もりサービスを生成する。
+  #[must_use]
+  pub fn with_policy(rate: RateYenPerKwh, policy: BillingPolicy) -> Self {
+    Self::with_tariff(Tariff::flat(rate), policy)
+  }
+
+  /// 料金表と課金方針を指定して見積もりサービスを生成する。
+  #[must_use]
+  pub fn with_tariff(tariff: Tariff, policy: BillingPolicy) -> Self {
+    Self { tariff, policy }
+  }
+
+  /// 利用時間と総エネルギー量から請求を見積もる。
+  ///
+  /// # Errors
+  /// 利用時間が正でない場合は `SessionValueError::InvalidTimeline`、
+  /// 金額が上限を超える場合は `SessionValueError::AmountOutOfRange` を返します。
+  pub fn quote_energy(&self, duration: Duration, total_energy: KwhMilli) -> Result<SessionBill, SessionValueError> {
+    let started_at = OffsetDateTime::UNIX_EPOCH;
+    let timeline = SessionTimeline::between(started_at, started_at + duration)?;
+    let energy = self.policy.allocate(timeline, total_energy)?;
+    SessionBill::settle_with(energy, &self.tariff)
+  }
+
+  /// 利用時間と平均電力から請求を見積もる。
+  ///
+  /// # Errors
+  /// 利用時間が正でない場合は `SessionValueError::InvalidTimeline`、
+  /// エネルギー量や金額が上限を超える場合は対応する範囲エラーを返します。
+  pub fn quote_power(&self, duration: Duration, average_power: PowerWatts) -> Result<SessionBill, SessionValueError> {
+    let millis = Millis::new(u128::try_from(duration.whole_milliseconds()).unwrap_or(0));
+    let total_energy = KwhMilli::try_from_energy(average_power.power() * millis)?;
+    self.quote_energy(duration, total_energy)
+  }
+
+  /// 予算内で購入できる課金対象エネルギー量（無料分を除いた分）を求める。
+  ///
+  /// 金額は床で丸められるため、`budget` 円を超えない最大のエネルギー量を返す。
+  #[must_use]
+  pub fn energy_for_budget(&self, budget: MoneyYen) -> KwhMilli {
+    let affordable = |energy_milli: u64| {
+      let energy = KwhMilli::from_milli(energy_milli);
+      self.tariff.price_for(energy).is_ok_and(|(_, amount, _)| amount <= budget)
+    };
+    // 金額はエネルギーに対して単調非減少なので、予算内に収まる最大値を二分探索する
+    let (mut low, mut high) = (0, MAX_KWH_MILLI);
+    while low < high {
+      let mid = low + (high - low).div_ceil(2);
+      if affordable(mid) {
+        low = mid;
+      } else {
+        high = mid - 1;
+      }
+    }
+    KwhMilli::from_milli(low)
+  }
+
+  /// 平均電力で充電した場合に予算を使い切るまでの利用時間（無料分を含む）を求める。
+  ///
+  /// 平均電力で一様に供給される前提のため、課金方針の充電カーブは考慮しない。
+  #[must_use]
+  pub fn duration_for_budget(&self, budget: MoneyYen, average_power: PowerWatts) -> Duration {
+    let energy = self.energy_for_budget(budget).energy();
+    let power = average_power.power();
+    let grace = self.policy.grace();
+    // floor(P × t) <= E（Wh 単位）を満たす最大の時間 t
+    let duration_for = |energy: Energy<WATT_HOUR>| {
+      (energy + Energy::new(1)).convert::<WATT_MILLISECOND>().saturating_sub(Energy::new(1)) / power
+    };
+    let by_time = grace.grace_period().duration() + duration_for(energy);
+    let by_energy = duration_for(energy + grace.free_energy().energy());
+    let total = match grace {
+      | GraceRule::Time(_) => by_time,
+      | GraceRule::Energy(_) => by_energy,
+      // 課金対象はどちらか少ない方なので、いずれかが予算内に収まる限り継続できる
+      | GraceRule::TimeAndEnergy(..) => by_time.max(by_energy),
+    };
+    Duration::milliseconds(i64::try_from(total.value()).unwrap_or(i64::MAX))
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use time::Duration;
+
+use super::CostEstimator;
+use crate::session::{
+  BillingPolicy, KwhMilli, MoneyYen, PowerWatts, RateYenPerKwh, SessionValueError,
+  test_support::{create_test_session, limited_tariff},
+};
+
+#[test]
+fn test_estimate_matches_actual_session_bill() {
+  let (session, started_at) = create_test_session();
+  let estimator = CostEstimator::new(RateYenPerKwh::try_new(30).unwrap());
+  let total_energy = KwhMilli::try_new(100_000).unwrap();
+
+  let estimate = estimator.quote_energy(Duration::minutes(60), total_energy).unwrap();
+  let closed = session.stop(started_at + Duration::minutes(60), total_energy).unwrap();
+
+  assert_eq!(Some(&estimate), closed.statement());
+}
+
+#[test]
+fn test_estimate_from_average_power() {
+  let estimator = CostEstimator::new(RateYenPerKwh::try_new(50).unwrap());
+
+  // 7.2 kW で30分 -> 3.6 kWh、課金対象は 25/30 -> 3 kWh -> 150円
+  let bill = estimator.quote_power(Duration::minutes(30), PowerWatts::try_new(7_200).unwrap()).unwrap();
+  assert_eq!(u64::from(bill.total_energy()), 3_600);
+  assert_eq!(u64::from(bill.billable_energy()), 3_000);
+  assert_eq!(u64::from(bill.amount_due()), 150);
+
+  let result = estimator.quote_power(Duration::ZERO, PowerWatts::try_new(7_200).unwrap());
+  assert!(matches!(result, Err(SessionValueError::InvalidTimeline { .. })));
+}
+
+#[test]
+fn test_estimate_inverse_for_budget() {
+  let estimator = CostEstimator::new(RateYenPerKwh::try_new(50).unwrap());
+  let budget = MoneyYen::try_new(150).unwrap();
+  let power = PowerWatts::try_new(7_200).unwrap();
+
+  // 3.019 kWh * 50円/kWh = 150.95円 -> 150円、3.020 kWh だと 151円
+  let energy = estimator.energy_for_budget(budget);
+  assert_eq!(u64::from(energy), 3_019);
+
+  // 無料5分 + 課金対象時間。算出した時間で見積もると予算内に収まる
+  let duration = estimator.duration_for_budget(budget, power);
+  assert!(duration > Duration::minutes(30));
+  let bill = estimator.quote_power(duration, power).unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 150);
+  let over = estimator.quote_power(duration + Duration::seconds(1), power).unwrap();
+  assert!(u64::from(over.amount_due()) > 150);
+}
+
+#[test]
+fn test_estimator_budget_respects_price_cap() {
+  let estimator = CostEstimator::with_tariff(limited_tariff(), BillingPolicy::default());
+  // 上限料金以上の予算なら上限までのエネルギーを購入できる
+  assert_eq!(u64::from(estimator.energy_for_budget(MoneyYen::try_new(500).unwrap())), 1_000_000);
+  // 最低料金に届かない予算では課金対象エネルギーを購入できない
+  assert_eq!(estimator.energy_for_budget(MoneyYen::try_new(99).unwrap()), KwhMilli::zero());
+}
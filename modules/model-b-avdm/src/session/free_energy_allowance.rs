@@ -0,0 +1,33 @@
+use super::{chargeable_energy::ChargeableEnergy, errors::SessionValueError, kwh_milli::KwhMilli};
+
+/// 無料で提供するエネルギー量（例: 最初の 0.5 kWh 無料）を表す値オブジェクト。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FreeEnergyAllowance {
+  energy: KwhMilli,
+}
+
+impl FreeEnergyAllowance {
+  /// 無料エネルギー量を指定して生成する。
+  #[must_use]
+  pub const fn new(energy: KwhMilli) -> Self {
+    Self { energy }
+  }
+
+  /// 無料エネルギー量を返す。
+  #[must_use]
+  pub const fn energy(self) -> KwhMilli {
+    self.energy
+  }
+
+  /// 総エネルギーから無料分を差し引き、課金対象エネルギーを求める。
+  ///
+  /// # Errors
+  /// 課金対象が総エネルギーを超えることはないが、不変条件違反時は
+  /// `SessionValueError::EnergyOutOfRange` を返します。
+  pub fn allocate(self, total_energy: KwhMilli) -> Result<ChargeableEnergy, SessionValueError> {
+    ChargeableEnergy::new(total_energy, total_energy.saturating_sub(self.energy))
+  }
+}
+
+#[cfg(test)]
+mod tests;
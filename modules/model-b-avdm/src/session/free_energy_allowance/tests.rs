@@ -0,0 +1,58 @@
+use time::{Duration, OffsetDateTime};
+
+use super::FreeEnergyAllowance;
+use crate::session::{
+  BillingPolicy, CostEstimator, GracePeriod, GraceRule, KwhMilli, MoneyYen, PowerWatts, RateYenPerKwh, Session,
+  test_support::stop_with_policy,
+};
+
+fn half_kwh_free() -> FreeEnergyAllowance {
+  FreeEnergyAllowance::new(KwhMilli::try_new(500).unwrap())
+}
+
+fn stop_with_grace(grace: GraceRule, minutes: i64, energy_milli: u64) -> Session {
+  stop_with_policy(BillingPolicy::with_grace(grace), OffsetDateTime::now_utc(), minutes, energy_milli)
+}
+
+#[test]
+fn test_energy_allowance_replaces_time_grace() {
+  // 10分・10 kWh のうち最初の 0.5 kWh だけ無料 -> 9.5 kWh * 30円 = 285円
+  let closed = stop_with_grace(GraceRule::Energy(half_kwh_free()), 10, 10_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.billable_energy()), 9_500);
+  assert_eq!(u64::from(bill.amount_due()), 285);
+
+  // 無料枠に満たない場合は全量無料で、課金対象が総量を超えることはない
+  let closed = stop_with_grace(GraceRule::Energy(half_kwh_free()), 10, 300);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.total_energy()), 300);
+  assert_eq!(u64::from(bill.billable_energy()), 0);
+}
+
+#[test]
+fn test_energy_allowance_combined_with_time_grace() {
+  let grace = GraceRule::TimeAndEnergy(GracePeriod::from_minutes(5), half_kwh_free());
+
+  // 5分無料で 5 kWh 無料になるため、0.5 kWh 枠は追加で効かない
+  let closed = stop_with_grace(grace, 10, 10_000);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 5_000);
+
+  // 低出力では 5分で 0.1 kWh しか流れないため、0.5 kWh 枠の方が多く無料になる
+  let closed = stop_with_grace(grace, 10, 200);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 0);
+  let closed = stop_with_grace(grace, 60, 1_200);
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 700);
+}
+
+#[test]
+fn test_estimator_with_energy_allowance() {
+  let estimator = CostEstimator::with_grace(RateYenPerKwh::try_new(50).unwrap(), GraceRule::Energy(half_kwh_free()));
+  let power = PowerWatts::try_new(7_200).unwrap();
+  let budget = MoneyYen::try_new(150).unwrap();
+
+  let duration = estimator.duration_for_budget(budget, power);
+  let bill = estimator.quote_power(duration, power).unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 150);
+  let over = estimator.quote_power(duration + Duration::seconds(1), power).unwrap();
+  assert!(u64::from(over.amount_due()) > 150);
+}
@@ -1,4 +1,4 @@
-use super::MILLISECONDS_IN_MINUTE;
+use super::{MILLISECONDS_IN_MINUTE, units::Millis};
 
 /// 無料時間のウィンドウを表す値オブジェクト。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -23,6 +23,11 @@ impl GracePeriod {
     self.millis
   }
 
+  /// 単位付きの時間として返す。
+  pub(crate) const fn duration(self) -> Millis {
+    Millis::new(self.millis)
+  }
+
   /// 無料時間がゼロかどうかを判定する。
   #[must_use]
   pub const fn is_zero(self) -> bool {
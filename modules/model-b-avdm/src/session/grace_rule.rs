@@ -0,0 +1,74 @@
+use super::{
+  chargeable_energy::ChargeableEnergy, charging_curve::EnergyProfile, errors::SessionValueError,
+  free_energy_allowance::FreeEnergyAllowance, grace_period::GracePeriod, kwh_milli::KwhMilli,
+  timeline::SessionTimeline,
+};
+
+/// セッション開始から無料とする範囲の決め方。
+///
+/// 時間と無料エネルギーを併用する場合、どちらもセッション開始から数えるため
+/// 重複分は二重に無料にならず、より多くを無料にする側が採用される。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraceRule {
+  /// 開始から一定時間を無料にする。
+  Time(GracePeriod),
+  /// 開始から一定エネルギー量を無料にする。
+  Energy(FreeEnergyAllowance),
+  /// 時間と無料エネルギーの両方を適用する。
+  TimeAndEnergy(GracePeriod, FreeEnergyAllowance),
+}
+
+impl GraceRule {
+  /// 無料ルールを適用し、課金対象エネルギーを割り当てる。
+  ///
+  /// 無料時間内に供給されたエネルギーは `profile` の仮定に従って推定する。
+  ///
+  /// # Errors
+  /// 課金比率が不正な場合、`SessionValueError::InvalidChargeRatio` を返します。
+  pub fn allocate(
+    self,
+    timeline: SessionTimeline,
+    total_energy: KwhMilli,
+    profile: EnergyProfile,
+  ) -> Result<ChargeableEnergy, SessionValueError> {
+    let by_time = profile.allocate(timeline.consume_grace_period(self.grace_period()), total_energy)?;
+    let by_energy = FreeEnergyAllowance::new(self.free_energy()).allocate(total_energy)?;
+    ChargeableEnergy::new(total_energy, by_time.billable().min(by_energy.billable()))
+  }
+
+  /// 既に消費した時間とエネルギーを差し引いた、残りの無料ルールを求める。
+  #[must_use]
+  pub fn remaining_after(self, elapsed_millis: u128, consumed_energy: KwhMilli) -> Self {
+    let grace = GracePeriod::from_millis(self.grace_period().millis().saturating_sub(elapsed_millis));
+    let allowance = FreeEnergyAllowance::new(self.free_energy().saturating_sub(consumed_energy));
+    match self {
+      | Self::Time(_) => Self::Time(grace),
+      | Self::Energy(_) => Self::Energy(allowance),
+      | Self::TimeAndEnergy(..) => Self::TimeAndEnergy(grace, allowance),
+    }
+  }
+
+  /// 無料時間を返す（エネルギーのみのルールでは 0）。
+  #[must_use]
+  pub fn grace_period(self) -> GracePeriod {
+    match self {
+      | Self::Time(grace) | Self::TimeAndEnergy(grace, _) => grace,
+      | Self::Energy(_) => GracePeriod::from_millis(0),
+    }
+  }
+
+  /// 無料エネルギー量を返す（時間のみのルールでは 0）。
+  #[must_use]
+  pub fn free_energy(self) -> KwhMilli {
+    match self {
+      | Self::Energy(allowance) | Self::TimeAndEnergy(_, allowance) => allowance.energy(),
+      | Self::Time(_) => KwhMilli::zero(),
+    }
+  }
+}
+
+impl Default for GraceRule {
+  fn default() -> Self {
+    Self::Time(GracePeriod::from_minutes(super::FREE_MINUTES))
+  }
+}
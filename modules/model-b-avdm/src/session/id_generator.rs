@@ -0,0 +1,63 @@
+use std::num::NonZeroU128;
+
+use uuid::Uuid;
+
+use super::session_id::SessionId;
+
+/// 新しいセッションに割り当てる `SessionId` を払い出す戦略。
+pub trait IdGenerator {
+  /// 未使用の `SessionId` を払い出す（nil は払い出さない）。
+  fn next_id(&mut self) -> SessionId;
+}
+
+/// 時刻順に並ぶ UUIDv7 を払い出す本番用の生成器。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UuidV7Generator;
+
+impl IdGenerator for UuidV7Generator {
+  fn next_id(&mut self) -> SessionId {
+    SessionId::new(Uuid::now_v7())
+  }
+}
+
+/// 指定した値から 1 ずつ増える UUID を払い出す、テスト用の決定的な生成器。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequentialIdGenerator {
+  next: Option<NonZeroU128>,
+}
+
+impl SequentialIdGenerator {
+  /// `00000000-0000-0000-0000-000000000001` から払い出す生成器を生成する。
+  #[must_use]
+  pub fn new() -> Self {
+    Self::starting_at(NonZeroU128::MIN)
+  }
+
+  /// 指定した値から払い出す生成器を生成する。
+  #[must_use]
+  pub fn starting_at(start: NonZeroU128) -> Self {
+    Self { next: Some(start) }
+  }
+}
+
+impl Default for SequentialIdGenerator {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+  /// 次の値を払い出す。
+  ///
+  /// # Panics
+  /// `u128::MAX` を払い出した後に呼び出すと panic します（1 に戻すと払い出し済みの ID
+  /// と重複するため）。
+  fn next_id(&mut self) -> SessionId {
+    let next = self.next.expect("SequentialIdGenerator の払い出せる ID が尽きた");
+    self.next = next.checked_add(1);
+    SessionId::new(Uuid::from_u128(next.get()))
+  }
+}
+
+#[cfg(test)]
+mod tests;
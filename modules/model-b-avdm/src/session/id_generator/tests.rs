@@ -0,0 +1,44 @@
+use std::num::NonZeroU128;
+
+use uuid::Uuid;
+
+use super::{IdGenerator, SequentialIdGenerator, UuidV7Generator};
+use crate::session::{SessionId, SessionValueError};
+
+#[test]
+fn test_sequential_generator_is_deterministic_and_never_nil() {
+  let mut generator = SequentialIdGenerator::new();
+  let first = generator.next_id();
+  let second = generator.next_id();
+
+  assert!(!first.is_nil());
+  assert_eq!(first.to_string(), "00000000-0000-0000-0000-000000000001");
+  assert_eq!(second.to_string(), "00000000-0000-0000-0000-000000000002");
+  assert_eq!(SequentialIdGenerator::new().next_id(), first);
+}
+
+#[test]
+#[should_panic(expected = "払い出せる ID が尽きた")]
+fn test_sequential_generator_panics_instead_of_wrapping_around() {
+  let mut generator = SequentialIdGenerator::starting_at(NonZeroU128::MAX);
+  assert_eq!(generator.next_id().into_uuid(), Uuid::from_u128(u128::MAX));
+  generator.next_id();
+}
+
+#[test]
+fn test_uuid_v7_generator_issues_distinct_ordered_ids() {
+  let mut generator = UuidV7Generator;
+  let first = generator.next_id();
+  let second = generator.next_id();
+
+  assert_ne!(first, second);
+  assert!(first.to_string() < second.to_string());
+  assert_eq!(first.into_uuid().get_version_num(), 7);
+}
+
+#[test]
+fn test_nil_session_id_is_rejected_for_real_sessions() {
+  assert_eq!(SessionId::try_new(Uuid::nil()), Err(SessionValueError::NilSessionId));
+  assert_eq!("00000000-0000-0000-0000-000000000000".parse::<SessionId>(), Err(SessionValueError::NilSessionId));
+  assert_eq!(SessionValueError::NilSessionId.code(), "SESSION_ID_NIL");
+}
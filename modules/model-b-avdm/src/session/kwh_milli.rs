@@ -1,16 +1,31 @@
-use std::convert::{From, TryFrom};
-use std::fmt::{format, Formatter};
-use super::{MAX_KWH_MILLI, bounded::BoundedU64, errors::SessionValueError};
+use std::{
+  convert::{From, TryFrom},
+  fmt,
+  str::FromStr,
+};
+
+use super::{
+  MAX_KWH_MILLI,
+  bounded::{Bounded, BoundedError, bounded_value},
+  errors::SessionValueError,
+  locale::{Locale, format_milli, parse_grouped, parse_milli, strip_unit},
+  units::{Energy, WATT_HOUR},
+};
+
+/// `KwhMilli` が取り得る範囲（0〜`MAX_KWH_MILLI` ミリkWh）。
+pub type KwhMilliRange = Bounded<u64, 0, { MAX_KWH_MILLI as i128 }>;
 
 /// エネルギー量（ミリkWh単位）を表す値オブジェクト。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct KwhMilli(pub(super) u64);
+pub struct KwhMilli(pub(super) KwhMilliRange);
+
+bounded_value!(KwhMilli);
 
 impl KwhMilli {
   /// 上限付きエネルギー量を生成する。
   #[must_use]
-  pub fn new(value: BoundedU64<MAX_KWH_MILLI>) -> Self {
-    Self(value.get())
+  pub fn new(value: KwhMilliRange) -> Self {
+    Self(value)
   }
 
   /// 生の値からエネルギー量を生成する。
@@ -18,9 +33,7 @@ impl KwhMilli {
   /// # Errors
   /// 上限を超える値が渡された場合、`SessionValueError::EnergyOutOfRange` を返します。
   pub fn try_new(value: u64) -> Result<Self, SessionValueError> {
-    let bounded = BoundedU64::<MAX_KWH_MILLI>::new(value)
-      .ok_or(SessionValueError::EnergyOutOfRange { provided: value, max: MAX_KWH_MILLI })?;
-    Ok(Self::new(bounded))
+    KwhMilliRange::new(value).map(Self).map_err(Self::range_error)
   }
 
   /// エネルギー量 0 を表す定数生成を行う。
@@ -28,12 +41,11 @@ impl KwhMilli {
   /// # Returns
   /// 0 を表す `KwhMilli`。
   pub fn zero() -> Self {
-    Self(0)
+    Self::from_milli(0)
   }
 
   pub(crate) fn from_milli(value: u64) -> Self {
-    let bounded = BoundedU64::<MAX_KWH_MILLI>::new(value).expect("billed energy must be within total energy bounds");
-    Self::new(bounded)
+    Self(KwhMilliRange::new(value).expect("billed energy must be within total energy bounds"))
   }
 
   /// 符号付き整数からエネルギー量を生成する。
@@ -51,14 +63,49 @@ impl KwhMilli {
     Self::try_new(unsigned)
   }
 
-  pub(crate) fn into_u128_milli(self) -> u128 {
-    self.0 as u128
+  /// 単位付きのエネルギー量（Wh = ミリkWh）に変換する。
+  pub(crate) fn energy(self) -> Energy<WATT_HOUR> {
+    Energy::new(u128::from(self.0.get()))
+  }
+
+  /// 単位付きのエネルギー量から生成する（Wh 未満は床で丸める）。
+  ///
+  /// # Errors
+  /// 上限を超える値の場合、`SessionValueError::EnergyOutOfRange` を返します。
+  pub(crate) fn try_from_energy<const UNIT: u128>(energy: Energy<UNIT>) -> Result<Self, SessionValueError> {
+    let watt_hours = energy.convert::<WATT_HOUR>().value();
+    Self::try_new(u64::try_from(watt_hours).unwrap_or(u64::MAX))
+  }
+
+  /// 上限以下であることが分かっている単位付きのエネルギー量から生成する。
+  pub(crate) fn from_energy<const UNIT: u128>(energy: Energy<UNIT>) -> Self {
+    Self::from_milli(energy.convert::<WATT_HOUR>().value() as u64)
   }
 
-  /// 上限を考慮した加算を行う。
-  pub fn bounded_sum(self, other: Self) -> Result<Self, SessionValueError> {
-    let total = self.0.saturating_add(other.0);
-    Self::try_new(total)
+  pub(crate) fn saturating_sub(self, other: Self) -> Self {
+    self.checked_sub(other).unwrap_or_else(|_| Self::zero())
+  }
+
+  /// ロケールに応じた kWh 表記（例: `2.4kWh` / `2.4 kWh`）で文字列化する。
+  #[must_use]
+  pub fn to_locale_string(self, locale: Locale) -> String {
+    let value = format_milli(self.0.get());
+    match locale {
+      | Locale::JaJp => format!("{value}kWh"),
+      | Locale::EnUs => format!("{value} kWh"),
+    }
+  }
+
+  fn range_error(error: BoundedError) -> SessionValueError {
+    match error {
+      | BoundedError::BelowMinimum { value, .. } => {
+        SessionValueError::NegativeEnergy { provided: i64::try_from(value).unwrap_or(i64::MIN) }
+      },
+      | BoundedError::AboveMaximum { value, .. } => SessionValueError::EnergyOutOfRange {
+        provided: u64::try_from(value).unwrap_or(u64::MAX),
+        max:      MAX_KWH_MILLI,
+      },
+    }
   }
 }
 
@@ -72,6 +119,28 @@ impl TryFrom<u64> for KwhMilli {
 
 impl From<KwhMilli> for u64 {
   fn from(value: KwhMilli) -> Self {
-    value.0
+    value.0.get()
+  }
+}
+
+impl fmt::Display for KwhMilli {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.to_locale_string(Locale::default()))
+  }
+}
+
+/// `2.4 kWh` のような小数点以下 3 桁までの kWh 表記、または `2,400 Wh` のような Wh 表記を解析する。
+impl FromStr for KwhMilli {
+  type Err = SessionValueError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let trimmed = s.trim();
+    let milli = match strip_unit(trimmed, "kWh") {
+      | Some(kwh) => parse_milli(kwh),
+      | None => strip_unit(trimmed, "Wh").and_then(parse_grouped),
+    };
+    let milli =
+      milli.ok_or_else(|| SessionValueError::InvalidFormat { input: s.to_owned(), expected: "2.4 kWh" })?;
+    Self::try_new(milli)
   }
 }
@@ -0,0 +1,84 @@
+/// 値オブジェクトを文字列化する際の表記ロケール。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+  /// 日本語表記（例: `1,000円`、`50円/kWh`）。`Display` はこの表記を使う。
+  #[default]
+  JaJp,
+  /// 英語（米国）表記（例: `¥1,000`、`¥50/kWh`）。
+  EnUs,
+}
+
+/// 整数を 3 桁区切りで文字列化する。
+pub(crate) fn group_thousands(value: u64) -> String {
+  let digits = value.to_string();
+  let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+  for (index, digit) in digits.chars().enumerate() {
+    if index > 0 && (digits.len() - index).is_multiple_of(3) {
+      grouped.push(',');
+    }
+    grouped.push(digit);
+  }
+  grouped
+}
+
+/// ミリ単位の整数を、末尾の 0 を省いた小数表記（整数部は 3 桁区切り）で文字列化する。
+pub(crate) fn format_milli(value: u64) -> String {
+  let integer = group_thousands(value / 1_000);
+  let fraction = value % 1_000;
+  if fraction == 0 {
+    return integer;
+  }
+  let fraction = format!("{fraction:03}");
+  format!("{integer}.{}", fraction.trim_end_matches('0'))
+}
+
+/// 3 桁区切りを含み得る非負整数を解析する。
+///
+/// 区切りは整数部の右から 3 桁ごとに置かれている場合のみ受け付ける。
+pub(crate) fn parse_grouped(input: &str) -> Option<u64> {
+  if input.contains(',') {
+    let mut groups = input.split(',');
+    let head = groups.next()?;
+    if head.is_empty() || head.len() > 3 || groups.clone().any(|group| group.len() != 3) {
+      return None;
+    }
+  }
+  let digits: String = input.chars().filter(|c| *c != ',').collect();
+  if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  digits.parse().ok()
+}
+
+/// 小数点以下 3 桁までの小数を、ミリ単位の整数として解析する。
+pub(crate) fn parse_milli(input: &str) -> Option<u64> {
+  let (integer, fraction) = input.split_once('.').unwrap_or((input, ""));
+  if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) || (input.contains('.') && fraction.is_empty())
+  {
+    return None;
+  }
+  let integer = parse_grouped(integer)?;
+  let fraction: u64 = format!("{fraction:0<3}").parse().ok()?;
+  integer.checked_mul(1_000)?.checked_add(fraction)
+}
+
+/// 大文字小文字を区別せずに単位の接尾辞を取り除き、残りの数値部分を返す。
+pub(crate) fn strip_unit<'a>(input: &'a str, unit: &str) -> Option<&'a str> {
+  let split = input.len().checked_sub(unit.len())?;
+  if !input.is_char_boundary(split) || !input[split..].eq_ignore_ascii_case(unit) {
+    return None;
+  }
+  Some(input[..split].trim_end())
+}
+
+/// 円記号（`¥` / `￥`）の接頭辞、または `円` の接尾辞を取り除いた数値部分を返す。
+pub(crate) fn strip_yen(input: &str) -> &str {
+  let input = input.trim();
+  if let Some(rest) = input.strip_prefix('¥').or_else(|| input.strip_prefix('￥')) {
+    return rest.trim_start();
+  }
+  input.strip_suffix('円').map_or(input, str::trim_end)
+}
+
+#[cfg(test)]
+mod tests;
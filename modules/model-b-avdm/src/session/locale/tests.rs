@@ -0,0 +1,49 @@
+use uuid::Uuid;
+
+use super::Locale;
+use crate::session::{KwhMilli, MoneyYen, RateYenPerKwh, SessionId, SessionValueError};
+
+#[test]
+fn test_value_objects_parse_human_readable_strings() {
+  assert_eq!(u64::from("2.4 kWh".parse::<KwhMilli>().unwrap()), 2_400);
+  assert_eq!(u64::from("2,400 Wh".parse::<KwhMilli>().unwrap()), 2_400);
+  assert_eq!(u64::from("0.005kwh".parse::<KwhMilli>().unwrap()), 5);
+  assert_eq!(u64::from("¥1,000".parse::<MoneyYen>().unwrap()), 1_000);
+  assert_eq!(u64::from("1,000円".parse::<MoneyYen>().unwrap()), 1_000);
+  assert_eq!(u32::from("50円/kWh".parse::<RateYenPerKwh>().unwrap()), 50);
+  assert_eq!(u32::from("¥50/kWh".parse::<RateYenPerKwh>().unwrap()), 50);
+
+  let id = SessionId::new(Uuid::from_u128(0x0192_f5e4_7c3a_7000_8000_0000_0000_002a));
+  assert_eq!(id.to_string().parse::<SessionId>().unwrap(), id);
+}
+
+#[test]
+fn test_value_objects_reject_malformed_strings() {
+  for input in ["2.4", "2.4001 kWh", "2.4 Wh", "24,00 Wh", "kWh"] {
+    let result = input.parse::<KwhMilli>();
+    assert!(matches!(result, Err(SessionValueError::InvalidFormat { .. })), "{input}");
+  }
+  assert!(matches!("1,000.5円".parse::<MoneyYen>(), Err(SessionValueError::InvalidFormat { .. })));
+  assert!(matches!("50円".parse::<RateYenPerKwh>(), Err(SessionValueError::InvalidFormat { .. })));
+  assert!(matches!("not-a-uuid".parse::<SessionId>(), Err(SessionValueError::InvalidFormat { .. })));
+
+  // 書式は正しいがドメイン制約に反する場合はドメインエラーになる
+  assert!(matches!("1,000.001 kWh".parse::<KwhMilli>(), Err(SessionValueError::EnergyOutOfRange { .. })));
+  assert!(matches!("0円/kWh".parse::<RateYenPerKwh>(), Err(SessionValueError::NonPositiveRate)));
+}
+
+#[test]
+fn test_value_objects_format_per_locale() {
+  let energy = KwhMilli::try_new(12_400).unwrap();
+  assert_eq!(energy.to_string(), "12.4kWh");
+  assert_eq!(energy.to_locale_string(Locale::EnUs), "12.4 kWh");
+  assert_eq!(KwhMilli::try_new(1_000_000).unwrap().to_locale_string(Locale::EnUs), "1,000 kWh");
+
+  let amount = MoneyYen::try_new(123_456).unwrap();
+  assert_eq!(amount.to_string(), "123,456円");
+  assert_eq!(amount.to_locale_string(Locale::EnUs), "¥123,456");
+
+  let rate = RateYenPerKwh::try_new(50).unwrap();
+  assert_eq!(rate.to_locale_string(Locale::JaJp), "50円/kWh");
+  assert_eq!(rate.to_locale_string(Locale::EnUs), "¥50/kWh");
+}
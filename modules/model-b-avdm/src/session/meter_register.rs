@@ -0,0 +1,112 @@
+use std::num::NonZeroU64;
+
+use super::{errors::SessionValueError, kwh_milli::KwhMilli};
+
+/// 充電器が報告する積算電力量計の指示値（Wh）。
+///
+/// セッションのエネルギー量ではなく計器の絶対値であり、OCPP の `meterStart` / `meterStop`
+/// に対応する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MeterReading(u64);
+
+impl MeterReading {
+  /// Wh 単位の指示値から生成する。
+  #[must_use]
+  pub fn new(wh: u64) -> Self {
+    Self(wh)
+  }
+
+  /// `Wh` または `kWh` 単位の非負の 10 進表記から生成する（1 Wh 未満は切り捨て）。
+  ///
+  /// 充電器やメーターは指示値を `"2935.6"` のような文字列と単位の組で報告する。
+  ///
+  /// # Errors
+  /// 単位が `Wh` / `kWh` 以外、または値が非負の 10 進表記でない場合、
+  /// `SessionValueError::InvalidFormat` を返します。
+  pub fn parse_in(value: &str, unit: &str) -> Result<Self, SessionValueError> {
+    let invalid = || SessionValueError::InvalidFormat { input: format!("{value} {unit}"), expected: "2935.6 kWh" };
+    let scale = match unit {
+      | "Wh" => 0,
+      | "kWh" => 3,
+      | _ => return Err(invalid()),
+    };
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if integer.is_empty() || !integer.bytes().chain(fraction.bytes()).all(|byte| byte.is_ascii_digit()) {
+      return Err(invalid());
+    }
+    let fraction: String = fraction.chars().chain(std::iter::repeat('0')).take(scale).collect();
+    format!("{integer}{fraction}").parse().map(Self).map_err(|_| invalid())
+  }
+}
+
+impl From<u64> for MeterReading {
+  fn from(value: u64) -> Self {
+    Self(value)
+  }
+}
+
+impl From<MeterReading> for u64 {
+  fn from(value: MeterReading) -> Self {
+    value.0
+  }
+}
+
+/// 積算電力量計の桁あふれ（一周）の扱いを定める。
+///
+/// 周期を指定した場合、停止値が開始値を下回ると計器が一周したものとみなす。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MeterRegister {
+  modulus: Option<NonZeroU64>,
+}
+
+impl MeterRegister {
+  /// 一周しない計器を表す。停止値が開始値を下回ればエラーとなる。
+  #[must_use]
+  pub fn monotonic() -> Self {
+    Self { modulus: None }
+  }
+
+  /// `modulus` Wh で 0 に戻る計器を表す（指示値は `0..modulus`）。
+  #[must_use]
+  pub fn wrapping_at(modulus: NonZeroU64) -> Self {
+    Self { modulus: Some(modulus) }
+  }
+
+  /// 一周の周期を返す。
+  #[must_use]
+  pub fn modulus(&self) -> Option<NonZeroU64> {
+    self.modulus
+  }
+
+  /// 指示値が計器の表示範囲内であることを検証する。
+  ///
+  /// # Errors
+  /// 指示値が周期以上の場合、`SessionValueError::MeterReadingOutOfRange` を返します。
+  pub fn validate(&self, reading: MeterReading) -> Result<MeterReading, SessionValueError> {
+    match self.modulus {
+      | Some(modulus) if reading.0 >= modulus.get() => {
+        Err(SessionValueError::MeterReadingOutOfRange { reading: reading.0, modulus: modulus.get() })
+      },
+      | _ => Ok(reading),
+    }
+  }
+
+  /// 開始値と停止値の差からエネルギー量を求める。
+  ///
+  /// # Errors
+  /// - 指示値が周期以上の場合、`SessionValueError::MeterReadingOutOfRange` を返します。
+  /// - 一周しない計器で停止値が開始値を下回る場合、`SessionValueError::MeterRegisterDecreased`
+  ///   を返します。
+  /// - 差が上限を超える場合、`SessionValueError::EnergyOutOfRange` を返します。
+  pub fn energy_between(&self, start: MeterReading, stop: MeterReading) -> Result<KwhMilli, SessionValueError> {
+    let (start, stop) = (self.validate(start)?.0, self.validate(stop)?.0);
+    let delta = match (stop.checked_sub(start), self.modulus) {
+      | (Some(delta), _) => delta,
+      // 開始値 < 周期 かつ 停止値 < 開始値 のため、結果は周期未満に収まる
+      | (None, Some(modulus)) => modulus.get() - start + stop,
+      | (None, None) => return Err(SessionValueError::MeterRegisterDecreased { start, stop }),
+    };
+    // 1 Wh = 1 ミリkWh
+    KwhMilli::try_new(delta)
+  }
+}
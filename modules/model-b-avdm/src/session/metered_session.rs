@@ -0,0 +1,95 @@
+use time::OffsetDateTime;
+
+use super::{
+  active_session::ActiveSession,
+  bill::SessionBill,
+  closed_session::ClosedSession,
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  meter_register::{MeterReading, MeterRegister},
+  session_id::SessionId,
+};
+
+/// 計器の開始値とともに開始した課金進行中のセッション。
+///
+/// 充電器は計器の絶対値を報告するため、停止時・スナップショット時の指示値から
+/// 開始値との差を取ってエネルギー量を求める。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeteredSession {
+  session:     ActiveSession,
+  register:    MeterRegister,
+  meter_start: MeterReading,
+}
+
+impl MeteredSession {
+  /// 計器の開始値を記録してセッションを開始する。
+  ///
+  /// # Errors
+  /// 開始値が計器の周期以上の場合、`SessionValueError::MeterReadingOutOfRange` を返します。
+  pub fn start(
+    session: ActiveSession,
+    register: MeterRegister,
+    meter_start: MeterReading,
+  ) -> Result<Self, SessionValueError> {
+    let meter_start = register.validate(meter_start)?;
+    Ok(Self { session, register, meter_start })
+  }
+
+  /// 計器の停止値でセッションを停止し、請求を確定させる。
+  ///
+  /// # Errors
+  /// - 指示値が不正な場合、`MeterRegister::energy_between` のエラーを返します。
+  /// - タイムラインや金額が不正な場合、対応する `SessionValueError` を返します。
+  pub fn stop(self, ended_at: OffsetDateTime, meter_stop: MeterReading) -> Result<ClosedSession, SessionValueError> {
+    let energy = self.energy_until(meter_stop)?;
+    self.session.stop(ended_at, energy)
+  }
+
+  /// 現在の指示値での課金スナップショットを取得する。
+  ///
+  /// # Errors
+  /// - 指示値が不正な場合、`MeterRegister::energy_between` のエラーを返します。
+  /// - タイムラインや金額が不正な場合、対応する `SessionValueError` を返します。
+  pub fn bill_snapshot(
+    &self,
+    ended_at: OffsetDateTime,
+    meter_now: MeterReading,
+  ) -> Result<SessionBill, SessionValueError> {
+    self.session.bill_snapshot(ended_at, self.energy_until(meter_now)?)
+  }
+
+  /// 開始値から指定した指示値までのエネルギー量を返す。
+  ///
+  /// # Errors
+  /// 指示値が不正な場合、`MeterRegister::energy_between` のエラーを返します。
+  pub fn energy_until(&self, meter_now: MeterReading) -> Result<KwhMilli, SessionValueError> {
+    self.register.energy_between(self.meter_start, meter_now)
+  }
+
+  /// セッションを識別する。
+  #[must_use]
+  pub fn identity(&self) -> SessionId {
+    self.session.identity()
+  }
+
+  /// 計器の開始値を返す。
+  #[must_use]
+  pub fn meter_start(&self) -> MeterReading {
+    self.meter_start
+  }
+
+  /// 計器の桁あふれの扱いを返す。
+  #[must_use]
+  pub fn register(&self) -> MeterRegister {
+    self.register
+  }
+
+  /// 内部のセッションを返す。
+  #[must_use]
+  pub fn session(&self) -> &ActiveSession {
+    &self.session
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,73 @@
+use std::num::NonZeroU64;
+
+use time::{Duration, OffsetDateTime};
+
+use super::MeteredSession;
+use crate::session::{
+  ActiveSession, BillingPolicy, IdGenerator, KwhMilli, MeterReading, MeterRegister, RateYenPerKwh,
+  SequentialIdGenerator, SessionValueError, Tariff,
+};
+
+fn metered_session(register: MeterRegister, meter_start: u64) -> (MeteredSession, OffsetDateTime) {
+  let started_at = OffsetDateTime::now_utc();
+  let active = ActiveSession::new(
+    SequentialIdGenerator::new().next_id(),
+    started_at,
+    Tariff::flat(RateYenPerKwh::try_new(30).unwrap()),
+    BillingPolicy::default(),
+  );
+  (MeteredSession::start(active, register, MeterReading::new(meter_start)).unwrap(), started_at)
+}
+
+#[test]
+fn test_metered_session_bills_register_difference() {
+  let (session, started_at) = metered_session(MeterRegister::monotonic(), 120_000);
+
+  let closed = session.stop(started_at + Duration::minutes(10), MeterReading::new(130_000)).unwrap();
+
+  assert_eq!(closed.bill().total_energy(), KwhMilli::try_new(10_000).unwrap());
+}
+
+#[test]
+fn test_metered_session_rejects_decreasing_register() {
+  let (session, started_at) = metered_session(MeterRegister::monotonic(), 5_000);
+
+  assert_eq!(
+    session.stop(started_at + Duration::minutes(10), MeterReading::new(4_999)),
+    Err(SessionValueError::MeterRegisterDecreased { start: 5_000, stop: 4_999 })
+  );
+}
+
+#[test]
+fn test_metered_session_handles_register_wrap_around() {
+  let register = MeterRegister::wrapping_at(NonZeroU64::new(100_000).unwrap());
+  let (session, started_at) = metered_session(register, 99_000);
+
+  // 99,000 → 100,000(=0) → 1,500 で 2,500 Wh
+  assert_eq!(session.energy_until(MeterReading::new(1_500)), Ok(KwhMilli::try_new(2_500).unwrap()));
+  let closed = session.stop(started_at + Duration::minutes(10), MeterReading::new(1_500)).unwrap();
+  assert_eq!(closed.bill().total_energy(), KwhMilli::try_new(2_500).unwrap());
+}
+
+#[test]
+fn test_meter_readings_parse_from_reported_decimals() {
+  assert_eq!(MeterReading::parse_in("2935.6", "kWh"), Ok(MeterReading::new(2_935_600)));
+  assert_eq!(MeterReading::parse_in("2935.6789", "kWh"), Ok(MeterReading::new(2_935_678)));
+  assert_eq!(MeterReading::parse_in("1500", "Wh"), Ok(MeterReading::new(1_500)));
+  assert!(matches!(MeterReading::parse_in("-1", "Wh"), Err(SessionValueError::InvalidFormat { .. })));
+  assert!(matches!(MeterReading::parse_in("1", "MWh"), Err(SessionValueError::InvalidFormat { .. })));
+}
+
+#[test]
+fn test_meter_readings_beyond_modulus_are_rejected() {
+  let register = MeterRegister::wrapping_at(NonZeroU64::new(100_000).unwrap());
+
+  assert_eq!(
+    register.energy_between(MeterReading::new(0), MeterReading::new(100_000)),
+    Err(SessionValueError::MeterReadingOutOfRange { reading: 100_000, modulus: 100_000 })
+  );
+  assert_eq!(
+    MeterRegister::monotonic().energy_between(MeterReading::new(0), MeterReading::new(1_000_001)),
+    Err(SessionValueError::EnergyOutOfRange { provided: 1_000_001, max: 1_000_000 })
+  );
+}
@@ -1,22 +1,37 @@
-use std::convert::{From, TryFrom, TryInto};
+use std::{
+  convert::{From, TryFrom, TryInto},
+  fmt,
+  str::FromStr,
+};
 
-use super::{MAX_YEN, bounded::BoundedU64, errors::SessionValueError};
+use super::{
+  MAX_YEN,
+  bounded::{Bounded, BoundedError, bounded_value},
+  errors::SessionValueError,
+  locale::{Locale, group_thousands, parse_grouped, strip_yen},
+  units::{Money, YEN},
+};
+
+/// `MoneyYen` が取り得る範囲（0〜`MAX_YEN` 円）。
+pub type MoneyYenRange = Bounded<u64, 0, { MAX_YEN as i128 }>;
 
 /// 料金の金額（円）を 0 以上の整数で保持するドメイン値オブジェクト。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct MoneyYen(pub(super) u64);
+pub struct MoneyYen(pub(super) MoneyYenRange);
+
+bounded_value!(MoneyYen);
 
 impl MoneyYen {
   /// 上限付きの金額を生成する。
   #[must_use]
-  pub fn new(value: BoundedU64<MAX_YEN>) -> Self {
-    Self(value.get())
+  pub fn new(value: MoneyYenRange) -> Self {
+    Self(value)
   }
 
   /// 0 円を生成する。
   #[must_use]
   pub fn zero() -> Self {
-    Self(0)
+    Self::from_yen(0)
   }
 
   /// 生の値から金額を生成する。
@@ -24,9 +39,12 @@ impl MoneyYen {
   /// # Errors
   /// 上限を超える金額が渡された場合、`SessionValueError::AmountOutOfRange` を返します。
   pub fn try_new(value: u64) -> Result<Self, SessionValueError> {
-    let bounded = BoundedU64::<MAX_YEN>::new(value)
-      .ok_or(SessionValueError::AmountOutOfRange { provided: value, max: MAX_YEN })?;
-    Ok(Self::new(bounded))
+    MoneyYenRange::new(value).map(Self).map_err(Self::range_error)
+  }
+
+  /// 上限以下であることが分かっている金額から生成する。
+  pub(crate) fn from_yen(value: u64) -> Self {
+    Self(MoneyYenRange::new(value).expect("amount must be within the bounds of its source amounts"))
   }
 
   /// `u128` から金額を生成するヘルパー。
@@ -41,36 +59,49 @@ impl MoneyYen {
       Err(SessionValueError::AmountOutOfRange { provided: value as u64, max: MAX_YEN })
     } else {
       let value_u64: u64 = value.try_into().map_err(|_| SessionValueError::AmountOverflow { provided: value })?;
-      let bounded = BoundedU64::<MAX_YEN>::new(value_u64)
-        .ok_or(SessionValueError::AmountOutOfRange { provided: value_u64, max: MAX_YEN })?;
-      Ok(Self::new(bounded))
+      Self::try_new(value_u64)
     }
   }
 
-  /// 加算を行う。
-  pub fn add(self, other: Self) -> Result<Self, SessionValueError> {
-    let sum = self
-      .0
-      .checked_add(other.0)
-      .ok_or(SessionValueError::AmountOverflow { provided: (self.0 as u128) + (other.0 as u128) })?;
-    Self::try_new(sum)
-  }
-
-  /// 上限チェック付きの加算を行う。
-  pub fn saturating_add(self, other: Self) -> Result<Self, SessionValueError> {
-    self.add(other)
+  /// 単位付きの金額から生成する（円未満は床で丸める）。
+  ///
+  /// # Errors
+  /// 上限を超える金額の場合、`SessionValueError::AmountOutOfRange` を返します。
+  pub(crate) fn try_from_money<const UNIT: u128>(money: Money<UNIT>) -> Result<Self, SessionValueError> {
+    Self::try_from_u128(money.convert::<YEN>().value())
   }
 
   /// 金額が正かどうかを判定する。
   #[must_use]
   pub fn is_positive(self) -> bool {
-    self.0 > 0
+    self.0.get() > 0
   }
 
   /// 金額がゼロかどうかを判定する。
   #[must_use]
   pub fn is_zero(self) -> bool {
-    self.0 == 0
+    self.0.get() == 0
+  }
+
+  /// ロケールに応じた金額表記（例: `1,000円` / `¥1,000`）で文字列化する。
+  #[must_use]
+  pub fn to_locale_string(self, locale: Locale) -> String {
+    let value = group_thousands(self.0.get());
+    match locale {
+      | Locale::JaJp => format!("{value}円"),
+      | Locale::EnUs => format!("¥{value}"),
+    }
+  }
+
+  fn range_error(error: BoundedError) -> SessionValueError {
+    match error {
+      | BoundedError::BelowMinimum { value, .. } => {
+        SessionValueError::NegativeAmount { provided: i64::try_from(value).unwrap_or(i64::MIN) }
+      },
+      | BoundedError::AboveMaximum { value, .. } => {
+        SessionValueError::AmountOutOfRange { provided: u64::try_from(value).unwrap_or(u64::MAX), max: MAX_YEN }
+      },
+    }
   }
 }
 
@@ -84,6 +115,23 @@ impl TryFrom<u64> for MoneyYen {
 
 impl From<MoneyYen> for u64 {
   fn from(value: MoneyYen) -> Self {
-    value.0
+    value.0.get()
+  }
+}
+
+impl fmt::Display for MoneyYen {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.to_locale_string(Locale::default()))
+  }
+}
+
+/// `¥1,000`・`1,000円`・`1000` のような円表記を解析する。
+impl FromStr for MoneyYen {
+  type Err = SessionValueError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let yen = parse_grouped(strip_yen(s))
+      .ok_or_else(|| SessionValueError::InvalidFormat { input: s.to_owned(), expected: "¥1,000" })?;
+    Self::try_new(yen)
   }
 }
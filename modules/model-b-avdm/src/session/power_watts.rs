@@ -0,0 +1,45 @@
+use std::{
+  convert::{From, TryFrom},
+  num::NonZeroU32,
+};
+
+use super::{errors::SessionValueError, units::Watts};
+
+/// 平均充電電力（W）を表す値オブジェクト。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PowerWatts(pub(super) NonZeroU32);
+
+impl PowerWatts {
+  /// 非ゼロの電力を生成する。
+  #[must_use]
+  pub fn new(value: NonZeroU32) -> Self {
+    Self(value)
+  }
+
+  /// 生の値から電力を生成する。
+  ///
+  /// # Errors
+  /// 0 が指定された場合、`SessionValueError::NonPositivePower` を返します。
+  pub fn try_new(value: u32) -> Result<Self, SessionValueError> {
+    NonZeroU32::new(value).map(Self::new).ok_or(SessionValueError::NonPositivePower)
+  }
+
+  /// 単位付きの電力に変換する。
+  pub(crate) fn power(self) -> Watts {
+    Watts::new(u128::from(self.0.get()))
+  }
+}
+
+impl TryFrom<u32> for PowerWatts {
+  type Error = SessionValueError;
+
+  fn try_from(value: u32) -> Result<Self, Self::Error> {
+    Self::try_new(value)
+  }
+}
+
+impl From<PowerWatts> for u32 {
+  fn from(value: PowerWatts) -> Self {
+    value.0.get()
+  }
+}
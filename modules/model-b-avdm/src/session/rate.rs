@@ -1,9 +1,17 @@
 use std::{
   convert::{From, TryFrom},
+  fmt,
   num::NonZeroU32,
+  str::FromStr,
 };
 
-use super::{errors::SessionValueError, kwh_milli::KwhMilli, money_yen::MoneyYen};
+use super::{
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  locale::{Locale, group_thousands, parse_grouped, strip_unit, strip_yen},
+  money_yen::MoneyYen,
+  units::YenPerKwh,
+};
 
 /// kWh あたりの料金単価（円）を表す値オブジェクト。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -32,10 +40,22 @@ impl RateYenPerKwh {
   /// # Returns
   /// 金額オブジェクトを `Ok` で返します。
   pub fn quote_for(self, billed_energy: KwhMilli) -> Result<MoneyYen, SessionValueError> {
-    let billed_energy_milli = billed_energy.into_u128_milli();
-    let rate_per_kwh = self.0.get() as u128;
-    let amount = (billed_energy_milli * rate_per_kwh) / 1_000;
-    MoneyYen::try_from_u128(amount)
+    MoneyYen::try_from_money(billed_energy.energy() * self.price())
+  }
+
+  /// ロケールに応じた単価表記（例: `50円/kWh` / `¥50/kWh`）で文字列化する。
+  #[must_use]
+  pub fn to_locale_string(self, locale: Locale) -> String {
+    let value = group_thousands(u64::from(self.0.get()));
+    match locale {
+      | Locale::JaJp => format!("{value}円/kWh"),
+      | Locale::EnUs => format!("¥{value}/kWh"),
+    }
+  }
+
+  /// 単位付きの単価に変換する。
+  pub(crate) fn price(self) -> YenPerKwh {
+    YenPerKwh::new(u128::from(self.0.get()))
   }
 }
 
@@ -52,3 +72,23 @@ impl From<RateYenPerKwh> for u32 {
     value.0.get()
   }
 }
+
+impl fmt::Display for RateYenPerKwh {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.to_locale_string(Locale::default()))
+  }
+}
+
+/// `50円/kWh`・`¥50/kWh` のような単価表記を解析する。
+impl FromStr for RateYenPerKwh {
+  type Err = SessionValueError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let yen = strip_unit(s.trim(), "/kWh")
+      .map(strip_yen)
+      .and_then(parse_grouped)
+      .and_then(|yen| u32::try_from(yen).ok())
+      .ok_or_else(|| SessionValueError::InvalidFormat { input: s.to_owned(), expected: "50円/kWh" })?;
+    Self::try_new(yen)
+  }
+}
@@ -0,0 +1,171 @@
+use time::{Duration, OffsetDateTime};
+
+use super::{
+  base::Session, bill::SessionBill, billing_policy::BillingPolicy, chargeable_energy::ChargeableEnergy,
+  connector_id::ConnectorId, driver_id::DriverId, errors::SessionValueError, session_id::SessionId, tariff::Tariff,
+  timeline::SessionTimeline,
+};
+
+/// 抜き差しによる再接続を同一セッションとみなす猶予を表すポリシー。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+  window: Duration,
+}
+
+impl ReconnectPolicy {
+  /// 前回停止から再開までの猶予を指定してポリシーを生成する。
+  #[must_use]
+  pub fn new(window: Duration) -> Self {
+    Self { window }
+  }
+
+  /// 分単位で猶予を指定してポリシーを生成する。
+  #[must_use]
+  pub fn from_minutes(minutes: i64) -> Self {
+    Self::new(Duration::minutes(minutes))
+  }
+
+  /// 猶予を返す。
+  #[must_use]
+  pub fn window(&self) -> Duration {
+    self.window
+  }
+}
+
+/// 同一ドライバー・同一コネクタでの再接続をまとめた論理セッション。
+///
+/// 無料ルールは論理セッション全体で一度だけ適用され、再接続後のセッションには
+/// 消費済みの時間・エネルギーを差し引いた残りだけが与えられる。
+/// 請求は各セッションの請求を足し合わせず、合算した課金対象エネルギーに
+/// 料金表を一度だけ適用して求める。元のセッションは監査用にそのまま保持する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalSession {
+  driver_id:      DriverId,
+  connector_id:   ConnectorId,
+  tariff:         Tariff,
+  policy:         BillingPolicy,
+  elapsed_millis: u128,
+  last_ended_at:  OffsetDateTime,
+  segments:       Vec<Session>,
+  energy:         ChargeableEnergy,
+  bill:           SessionBill,
+}
+
+impl LogicalSession {
+  /// 停止済みセッションから論理セッションを開始する。
+  ///
+  /// # Errors
+  /// セッションが停止していない場合、`SessionValueError::NotClosed` を返します。
+  pub fn begin(driver_id: DriverId, connector_id: ConnectorId, closed: Session) -> Result<Self, SessionValueError> {
+    let Some(segment) = closed.as_closed() else {
+      return Err(SessionValueError::NotClosed { session_id: closed.identity() });
+    };
+    Ok(Self {
+      driver_id,
+      connector_id,
+      tariff: segment.tariff().clone(),
+      policy: segment.policy(),
+      elapsed_millis: SessionTimeline::between(segment.started_at(), segment.ended_at())?.elapsed_millis(),
+      last_ended_at: segment.ended_at(),
+      energy: segment.bill().chargeable_energy(),
+      bill: segment.bill().clone(),
+      segments: vec![closed],
+    })
+  }
+
+  /// 新たなセッションの開始が、この論理セッションへの再接続に当たるかを判定する。
+  #[must_use]
+  pub fn is_reconnect(
+    &self,
+    policy: ReconnectPolicy,
+    driver_id: DriverId,
+    connector_id: ConnectorId,
+    started_at: OffsetDateTime,
+  ) -> bool {
+    let gap = started_at - self.last_ended_at;
+    self.driver_id == driver_id && self.connector_id == connector_id && !gap.is_negative() && gap <= policy.window()
+  }
+
+  /// 再接続として新たなセッションを開始する。
+  ///
+  /// 料金表と課金方針は最初のセッションを引き継ぎ、無料ルールは残り分のみとなる。
+  ///
+  /// # Errors
+  /// 再接続に当たらない場合、`SessionValueError::ReconnectNotEligible` を返します。
+  pub fn start_reconnect(
+    &self,
+    policy: ReconnectPolicy,
+    id: SessionId,
+    driver_id: DriverId,
+    connector_id: ConnectorId,
+    started_at: OffsetDateTime,
+  ) -> Result<Session, SessionValueError> {
+    if !self.is_reconnect(policy, driver_id, connector_id, started_at) {
+      return Err(SessionValueError::ReconnectNotEligible { session_id: id });
+    }
+    let remaining = self.policy.remaining_after(self.elapsed_millis, self.bill.total_energy());
+    Ok(Session::new_active_with_tariff(id, started_at, self.tariff.clone(), remaining))
+  }
+
+  /// 再接続後に停止したセッションを取り込み、合算したエネルギーで請求を算出し直す。
+  ///
+  /// # Errors
+  /// - セッションが停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - `start_reconnect` で開始された再接続セッションでない場合、
+  ///   `SessionValueError::ReconnectNotEligible` を返します。
+  /// - 合算値が上限を超えた場合、`SessionValueError` の範囲エラーを返します。
+  pub fn absorb(
+    &mut self,
+    policy: ReconnectPolicy,
+    driver_id: DriverId,
+    connector_id: ConnectorId,
+    closed: Session,
+  ) -> Result<(), SessionValueError> {
+    let Some(segment) = closed.as_closed() else {
+      return Err(SessionValueError::NotClosed { session_id: closed.identity() });
+    };
+    let eligible = self.is_reconnect(policy, driver_id, connector_id, segment.started_at())
+      && *segment.tariff() == self.tariff
+      && segment.policy() == self.policy.remaining_after(self.elapsed_millis, self.bill.total_energy());
+    if !eligible {
+      return Err(SessionValueError::ReconnectNotEligible { session_id: segment.identity() });
+    }
+
+    let elapsed_millis = SessionTimeline::between(segment.started_at(), segment.ended_at())?.elapsed_millis();
+    let ended_at = segment.ended_at();
+    let energy = self.energy.combine(segment.bill().chargeable_energy())?;
+    self.bill = SessionBill::settle_with(energy, &self.tariff)?;
+    self.energy = energy;
+    self.elapsed_millis += elapsed_millis;
+    self.last_ended_at = ended_at;
+    self.segments.push(closed);
+    Ok(())
+  }
+
+  /// 論理セッション全体の請求を返す。
+  #[must_use]
+  pub fn bill(&self) -> &SessionBill {
+    &self.bill
+  }
+
+  /// 監査用に、論理セッションを構成する元のセッションを開始順に返す。
+  #[must_use]
+  pub fn segments(&self) -> &[Session] {
+    &self.segments
+  }
+
+  /// ドライバーを返す。
+  #[must_use]
+  pub fn driver_id(&self) -> DriverId {
+    self.driver_id
+  }
+
+  /// コネクタを返す。
+  #[must_use]
+  pub fn connector_id(&self) -> ConnectorId {
+    self.connector_id
+  }
+}
+
+#[cfg(test)]
+mod tests;
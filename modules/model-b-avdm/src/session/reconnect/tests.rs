@@ -0,0 +1,124 @@
+use time::{Duration, OffsetDateTime, macros::datetime};
+use uuid::Uuid;
+
+use super::{LogicalSession, ReconnectPolicy};
+use crate::session::{
+  BillingPolicy, ConnectorId, DriverId, KwhMilli, RateYenPerKwh, Session, SessionId, SessionValueError,
+  test_support::{create_test_session, limited_tariff, stop_with_tariff, two_tier_tariff},
+};
+
+fn first_plug_in() -> (LogicalSession, OffsetDateTime, DriverId, ConnectorId) {
+  let driver = DriverId::new(Uuid::from_u128(7));
+  let connector = ConnectorId::new(1);
+  let (session, started_at) = create_test_session();
+  // 3分で 3 kWh -> 無料時間内
+  let ended_at = started_at + Duration::minutes(3);
+  let closed = session.stop(ended_at, KwhMilli::try_new(3_000).unwrap()).unwrap();
+  (LogicalSession::begin(driver, connector, closed).unwrap(), ended_at, driver, connector)
+}
+
+#[test]
+fn test_reconnect_applies_grace_only_once() {
+  let policy = ReconnectPolicy::from_minutes(2);
+  let (mut logical, unplugged_at, driver, connector) = first_plug_in();
+
+  // 1分後に差し直し、10分で 10 kWh。残りの無料時間は2分だけ
+  let started_at = unplugged_at + Duration::minutes(1);
+  let replug =
+    logical.start_reconnect(policy, SessionId::new(Uuid::from_u128(2)), driver, connector, started_at).unwrap();
+  let closed = replug.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  assert_eq!(u64::from(closed.statement().unwrap().billable_energy()), 8_000);
+
+  logical.absorb(policy, driver, connector, closed).unwrap();
+  assert_eq!(logical.segments().len(), 2);
+  assert_eq!(u64::from(logical.bill().total_energy()), 13_000);
+  assert_eq!(u64::from(logical.bill().billable_energy()), 8_000);
+  assert_eq!(u64::from(logical.bill().amount_due()), 240);
+}
+
+#[test]
+fn test_reconnect_prices_combined_energy_across_tiers() {
+  let policy = ReconnectPolicy::from_minutes(2);
+  let driver = DriverId::new(Uuid::from_u128(7));
+  let connector = ConnectorId::new(1);
+  let started_at = datetime!(2025-10-20 10:00 UTC);
+  let session = Session::new_active_with_tariff(
+    SessionId::new(Uuid::from_u128(1)),
+    started_at,
+    two_tier_tariff(),
+    BillingPolicy::default(),
+  );
+  // 60分で 30 kWh → 課金対象 27.5 kWh
+  let unplugged_at = started_at + Duration::minutes(60);
+  let closed = session.stop(unplugged_at, KwhMilli::try_new(30_000).unwrap()).unwrap();
+  let mut logical = LogicalSession::begin(driver, connector, closed).unwrap();
+
+  // 無料時間は使い切っているため、再接続後の 10 kWh はすべて課金対象
+  let replugged_at = unplugged_at + Duration::minutes(1);
+  let replug =
+    logical.start_reconnect(policy, SessionId::new(Uuid::from_u128(2)), driver, connector, replugged_at).unwrap();
+  let closed = replug.stop(replugged_at + Duration::minutes(20), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  // 単独では 1 段目から数え直すため 300円になる
+  assert_eq!(u64::from(closed.statement().unwrap().amount_due()), 300);
+  logical.absorb(policy, driver, connector, closed).unwrap();
+
+  // 合算 37.5 kWh = 20 kWh × 30円 + 17.5 kWh × 20円（750円 + 300円 にはならない）
+  let lines: Vec<_> =
+    logical.bill().lines().iter().map(|line| (u64::from(line.energy()), u32::from(line.rate()))).collect();
+  assert_eq!(lines, vec![(20_000, 30), (17_500, 20)]);
+  assert_eq!(u64::from(logical.bill().amount_due()), 950);
+}
+
+#[test]
+fn test_reconnect_requires_same_plug_within_window() {
+  let policy = ReconnectPolicy::from_minutes(2);
+  let (logical, unplugged_at, driver, connector) = first_plug_in();
+  let id = SessionId::new(Uuid::from_u128(2));
+
+  let late = logical.start_reconnect(policy, id, driver, connector, unplugged_at + Duration::minutes(3));
+  assert!(matches!(late, Err(SessionValueError::ReconnectNotEligible { .. })));
+
+  let other_connector = logical.start_reconnect(policy, id, driver, ConnectorId::new(2), unplugged_at);
+  assert!(matches!(other_connector, Err(SessionValueError::ReconnectNotEligible { .. })));
+}
+
+#[test]
+fn test_reconnect_rejects_session_with_fresh_grace() {
+  let policy = ReconnectPolicy::from_minutes(2);
+  let (mut logical, unplugged_at, driver, connector) = first_plug_in();
+
+  // 通常通り開始されたセッションは無料時間が全量残っているため取り込めない
+  let started_at = unplugged_at + Duration::minutes(1);
+  let rate = RateYenPerKwh::try_new(30).unwrap();
+  let fresh = Session::new_active(SessionId::new(Uuid::from_u128(2)), started_at, rate);
+  let closed = fresh.stop(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+
+  let result = logical.absorb(policy, driver, connector, closed);
+  assert!(matches!(result, Err(SessionValueError::ReconnectNotEligible { .. })));
+  assert_eq!(logical.segments().len(), 1);
+}
+
+#[test]
+fn test_reconnect_applies_price_limits_once() {
+  let policy = ReconnectPolicy::from_minutes(2);
+  let driver = DriverId::new(Uuid::from_u128(7));
+  let connector = ConnectorId::new(1);
+  // 課金対象 27.5 kWh = 825円 → 上限 500円
+  let first = stop_with_tariff(limited_tariff(), 60, 30_000);
+  let unplugged_at = first.as_closed().unwrap().ended_at();
+  let mut logical = LogicalSession::begin(driver, connector, first).unwrap();
+
+  let replugged_at = unplugged_at + Duration::minutes(1);
+  let replug =
+    logical.start_reconnect(policy, SessionId::new(Uuid::from_u128(2)), driver, connector, replugged_at).unwrap();
+  // 単独では 1 kWh = 30円 → 最低料金 100円
+  let closed = replug.stop(replugged_at + Duration::minutes(10), KwhMilli::try_new(1_000).unwrap()).unwrap();
+  assert!(closed.statement().unwrap().minimum_fee_applied());
+  logical.absorb(policy, driver, connector, closed).unwrap();
+
+  // 合算 28.5 kWh = 855円に上限料金を一度だけ適用する（500円 + 100円 にはならない）
+  let bill = logical.bill();
+  assert_eq!(u64::from(bill.amount_due()), 500);
+  assert!(bill.price_cap_applied());
+  assert!(!bill.minimum_fee_applied());
+}
@@ -1,16 +1,33 @@
+use std::{fmt, str::FromStr};
+
+use super::errors::SessionValueError;
+
 /// セッション識別子を UUID で表す。
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SessionId(pub(super) uuid::Uuid);
 
 impl SessionId {
   /// UUID から新しい `SessionId` を生成する。
   ///
+  /// nil も検証せずに通す。実際のセッションに割り当てる値には `try_new` を使う。
+  ///
   /// # Returns
   /// 生成された `SessionId`。
   pub fn new(id: uuid::Uuid) -> Self {
     Self(id)
   }
 
+  /// 実際のセッションに割り当てる `SessionId` を生成する。
+  ///
+  /// # Errors
+  /// nil UUID の場合、`SessionValueError::NilSessionId` を返します。
+  pub fn try_new(id: uuid::Uuid) -> Result<Self, SessionValueError> {
+    if id.is_nil() {
+      return Err(SessionValueError::NilSessionId);
+    }
+    Ok(Self(id))
+  }
+
   /// 内部の UUID を取り出す。
   ///
   /// # Returns
@@ -33,3 +50,22 @@ impl From<SessionId> for uuid::Uuid {
     value.0
   }
 }
+
+impl fmt::Display for SessionId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.hyphenated().fmt(f)
+  }
+}
+
+/// ハイフン区切りの UUID 表記を解析する。外部から受け取る値なので nil は拒否する。
+impl FromStr for SessionId {
+  type Err = SessionValueError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let id = uuid::Uuid::parse_str(s.trim()).map_err(|_| SessionValueError::InvalidFormat {
+      input:    s.to_owned(),
+      expected: "0192f5e4-7c3a-7000-8000-000000000000",
+    })?;
+    Self::try_new(id)
+  }
+}
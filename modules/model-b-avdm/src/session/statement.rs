@@ -0,0 +1,235 @@
+use time::{Date, Month, OffsetDateTime, Time, UtcOffset};
+
+use super::{
+  account::{Account, AccountId},
+  base::Session,
+  bill::SessionBill,
+  errors::SessionValueError,
+  session_id::SessionId,
+};
+
+/// 請求期間（開始を含み終了を含まない半開区間）を表す値オブジェクト。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BillingPeriod {
+  starts_at: OffsetDateTime,
+  ends_at:   OffsetDateTime,
+}
+
+impl BillingPeriod {
+  /// 開始・終了時刻から請求期間を生成する。
+  ///
+  /// # Errors
+  /// 終了時刻が開始時刻以前の場合、`SessionValueError::InvalidBillingPeriod` を返します。
+  pub fn new(starts_at: OffsetDateTime, ends_at: OffsetDateTime) -> Result<Self, SessionValueError> {
+    if ends_at <= starts_at {
+      return Err(SessionValueError::InvalidBillingPeriod { starts_at, ends_at });
+    }
+    Ok(Self { starts_at, ends_at })
+  }
+
+  /// 指定オフセットにおける暦月 1 か月分の請求期間を生成する。
+  ///
+  /// # Errors
+  /// 暦月が表現できない場合、`SessionValueError::InvalidCalendarMonth` を返します。
+  pub fn monthly(year: i32, month: Month, offset: UtcOffset) -> Result<Self, SessionValueError> {
+    let invalid = || SessionValueError::InvalidCalendarMonth { year, month: month as u8 };
+    let (next_year, next_month) =
+      if month == Month::December { (year + 1, Month::January) } else { (year, month.next()) };
+    let first_day = Date::from_calendar_date(year, month, 1).map_err(|_| invalid())?;
+    let next_first_day = Date::from_calendar_date(next_year, next_month, 1).map_err(|_| invalid())?;
+    Self::new(
+      first_day.with_time(Time::MIDNIGHT).assume_offset(offset),
+      next_first_day.with_time(Time::MIDNIGHT).assume_offset(offset),
+    )
+  }
+
+  /// 指定時刻が請求期間に含まれるかを判定する。
+  #[must_use]
+  pub fn contains(&self, at: OffsetDateTime) -> bool {
+    self.starts_at <= at && at < self.ends_at
+  }
+
+  /// 期間の開始時刻を返す。
+  #[must_use]
+  pub fn starts_at(&self) -> OffsetDateTime {
+    self.starts_at
+  }
+
+  /// 期間の終了時刻（この時刻を含まない）を返す。
+  #[must_use]
+  pub fn ends_at(&self) -> OffsetDateTime {
+    self.ends_at
+  }
+}
+
+/// 明細に計上された 1 セッション分の行。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementLine {
+  session_id: SessionId,
+  started_at: OffsetDateTime,
+  ended_at:   OffsetDateTime,
+  bill:       SessionBill,
+}
+
+impl StatementLine {
+  /// 対象セッションIDを返す。
+  #[must_use]
+  pub fn session_id(&self) -> SessionId {
+    self.session_id
+  }
+
+  /// セッション開始時刻を返す。
+  #[must_use]
+  pub fn started_at(&self) -> OffsetDateTime {
+    self.started_at
+  }
+
+  /// セッション終了時刻を返す。
+  #[must_use]
+  pub fn ended_at(&self) -> OffsetDateTime {
+    self.ended_at
+  }
+
+  /// セッションの確定請求を返す。
+  #[must_use]
+  pub fn bill(&self) -> &SessionBill {
+    &self.bill
+  }
+}
+
+/// 明細の合計値。
+///
+/// 1 セッションの上限（`KwhMilli` / `MoneyYen`）は月次の合計には当てはまらないため、
+/// 素の `u64` で保持し、加算はすべて検査付きで行う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementTotal {
+  total_energy_milli:    u64,
+  billable_energy_milli: u64,
+  amount_due_yen:        u64,
+}
+
+impl StatementTotal {
+  /// 総エネルギー消費量をミリkWh単位で返す。
+  #[must_use]
+  pub fn total_energy_milli(&self) -> u64 {
+    self.total_energy_milli
+  }
+
+  /// 課金対象エネルギー量をミリkWh単位で返す。
+  #[must_use]
+  pub fn billable_energy_milli(&self) -> u64 {
+    self.billable_energy_milli
+  }
+
+  /// 請求合計額を円単位で返す。
+  #[must_use]
+  pub fn amount_due_yen(&self) -> u64 {
+    self.amount_due_yen
+  }
+
+  fn checked_add(self, bill: &SessionBill) -> Option<Self> {
+    Some(Self {
+      total_energy_milli:    self.total_energy_milli.checked_add(u64::from(bill.total_energy()))?,
+      billable_energy_milli: self.billable_energy_milli.checked_add(u64::from(bill.billable_energy()))?,
+      amount_due_yen:        self.amount_due_yen.checked_add(u64::from(bill.amount_due()))?,
+    })
+  }
+}
+
+/// アカウント単位で請求期間内のセッション請求を集約した明細。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountStatement {
+  account_id: AccountId,
+  period:     BillingPeriod,
+  lines:      Vec<StatementLine>,
+  total:      StatementTotal,
+}
+
+impl AccountStatement {
+  /// 請求先アカウントを返す。
+  #[must_use]
+  pub fn account_id(&self) -> AccountId {
+    self.account_id
+  }
+
+  /// 請求期間を返す。
+  #[must_use]
+  pub fn period(&self) -> BillingPeriod {
+    self.period
+  }
+
+  /// 終了時刻順に並んだ明細行を返す。
+  #[must_use]
+  pub fn lines(&self) -> &[StatementLine] {
+    &self.lines
+  }
+
+  /// 期間内の合計値を返す。
+  #[must_use]
+  pub fn total(&self) -> StatementTotal {
+    self.total
+  }
+}
+
+/// 停止済みセッションを検証しながら月次明細を組み立てるビルダー。
+#[derive(Debug, Clone)]
+pub struct StatementBuilder {
+  account: Account,
+  period:  BillingPeriod,
+  lines:   Vec<StatementLine>,
+  total:   StatementTotal,
+}
+
+impl StatementBuilder {
+  /// アカウントと請求期間を指定してビルダーを生成する。
+  #[must_use]
+  pub fn new(account: Account, period: BillingPeriod) -> Self {
+    Self { account, period, lines: Vec::new(), total: StatementTotal::default() }
+  }
+
+  /// 停止済みセッションを明細に追加する。
+  ///
+  /// # Errors
+  /// - セッションが停止していない場合、`SessionValueError::NotClosed` を返します。
+  /// - 別アカウントのセッションの場合、`SessionValueError::ForeignSession` を返します。
+  /// - 終了時刻が請求期間外の場合、`SessionValueError::OutsideBillingPeriod` を返します。
+  /// - 同一セッションを重複して追加した場合、`SessionValueError::DuplicateStatementLine`
+  ///   を返します。
+  /// - 合計値が `u64` で表現できない場合、`SessionValueError::StatementTotalOverflow` を返します。
+  pub fn add_session(&mut self, session: &Session) -> Result<(), SessionValueError> {
+    let Some(closed) = session.as_closed() else {
+      return Err(SessionValueError::NotClosed { session_id: session.identity() });
+    };
+    let id = closed.identity();
+    if !self.account.owns(id) {
+      return Err(SessionValueError::ForeignSession { session_id: id, account_id: self.account.identity() });
+    }
+    if !self.period.contains(closed.ended_at()) {
+      return Err(SessionValueError::OutsideBillingPeriod { session_id: id, ended_at: closed.ended_at() });
+    }
+    if self.lines.iter().any(|line| line.session_id == id) {
+      return Err(SessionValueError::DuplicateStatementLine { session_id: id });
+    }
+
+    self.total =
+      self.total.checked_add(closed.bill()).ok_or(SessionValueError::StatementTotalOverflow { session_id: id })?;
+    self.lines.push(StatementLine {
+      session_id: id,
+      started_at: closed.started_at(),
+      ended_at:   closed.ended_at(),
+      bill:       closed.bill().clone(),
+    });
+    Ok(())
+  }
+
+  /// 明細を確定する。
+  #[must_use]
+  pub fn build(self) -> AccountStatement {
+    let mut lines = self.lines;
+    lines.sort_by_key(StatementLine::ended_at);
+    AccountStatement { account_id: self.account.identity(), period: self.period, lines, total: self.total }
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,94 @@
+use time::{Duration, Month, UtcOffset, macros::datetime};
+use uuid::Uuid;
+
+use super::{BillingPeriod, StatementBuilder};
+use crate::session::{
+  Account, AccountId, Session, SessionValueError,
+  test_support::{closed_session, create_test_session},
+};
+
+fn fleet_account(sessions: &[&Session]) -> Account {
+  let mut account = Account::new(AccountId::new(Uuid::from_u128(100)));
+  for session in sessions {
+    account.link(session.identity());
+  }
+  account
+}
+
+#[test]
+fn test_statement_aggregates_closed_sessions_in_period() {
+  let jst = UtcOffset::from_hms(9, 0, 0).unwrap();
+  let period = BillingPeriod::monthly(2025, Month::October, jst).unwrap();
+  let later = closed_session(2, datetime!(2025-10-20 12:00 +09:00), 60, 100_000);
+  let earlier = closed_session(1, datetime!(2025-10-01 00:00 +09:00), 10, 10_000);
+  let mut builder = StatementBuilder::new(fleet_account(&[&earlier, &later]), period);
+
+  builder.add_session(&later).unwrap();
+  builder.add_session(&earlier).unwrap();
+  let statement = builder.build();
+
+  // 明細は終了時刻順に並ぶ
+  let ids: Vec<_> = statement.lines().iter().map(|line| line.session_id()).collect();
+  assert_eq!(ids, vec![earlier.identity(), later.identity()]);
+  // 10 kWh + 100 kWh
+  assert_eq!(statement.total().total_energy_milli(), 110_000);
+  // 5,000 + 91,666 milli-kWh
+  assert_eq!(statement.total().billable_energy_milli(), 96_666);
+  // 150円 + 2,749円
+  assert_eq!(statement.total().amount_due_yen(), 2_899);
+}
+
+#[test]
+fn test_statement_total_is_not_bound_by_per_session_limits() {
+  let period = BillingPeriod::monthly(2025, Month::October, UtcOffset::UTC).unwrap();
+  let sessions: Vec<_> = (1..=3)
+    .map(|id| closed_session(id, datetime!(2025-10-01 00:00 UTC) + Duration::days(id as i64), 600, 600_000))
+    .collect();
+  let mut builder = StatementBuilder::new(fleet_account(&sessions.iter().collect::<Vec<_>>()), period);
+
+  for session in &sessions {
+    builder.add_session(session).unwrap();
+  }
+  let total = builder.build().total();
+
+  // 1 セッションの上限 1,000 kWh を超える 1,800 kWh を合計できる
+  assert_eq!(total.total_energy_milli(), 1_800_000);
+}
+
+#[test]
+fn test_statement_rejects_foreign_session() {
+  let period = BillingPeriod::monthly(2025, Month::October, UtcOffset::UTC).unwrap();
+  let own = closed_session(1, datetime!(2025-10-01 00:00 UTC), 10, 10_000);
+  let foreign = closed_session(2, datetime!(2025-10-02 00:00 UTC), 10, 10_000);
+  let mut builder = StatementBuilder::new(fleet_account(&[&own]), period);
+
+  let result = builder.add_session(&foreign);
+  assert!(matches!(result, Err(SessionValueError::ForeignSession { .. })));
+}
+
+#[test]
+fn test_statement_rejects_session_outside_period_or_duplicated() {
+  let jst = UtcOffset::from_hms(9, 0, 0).unwrap();
+  let period = BillingPeriod::monthly(2025, Month::October, jst).unwrap();
+  // JST では 11月1日 00:05 に終了しており、10月分には含まれない
+  let november = closed_session(1, datetime!(2025-10-31 23:55 +09:00), 10, 10_000);
+  let october = closed_session(2, datetime!(2025-10-31 23:40 +09:00), 10, 10_000);
+  let mut builder = StatementBuilder::new(fleet_account(&[&november, &october]), period);
+
+  let result = builder.add_session(&november);
+  assert!(matches!(result, Err(SessionValueError::OutsideBillingPeriod { .. })));
+
+  builder.add_session(&october).unwrap();
+  let result = builder.add_session(&october);
+  assert!(matches!(result, Err(SessionValueError::DuplicateStatementLine { .. })));
+}
+
+#[test]
+fn test_statement_rejects_active_session() {
+  let (session, _) = create_test_session();
+  let period = BillingPeriod::monthly(2025, Month::October, UtcOffset::UTC).unwrap();
+  let mut builder = StatementBuilder::new(fleet_account(&[&session]), period);
+
+  let result = builder.add_session(&session);
+  assert!(matches!(result, Err(SessionValueError::NotClosed { .. })));
+}
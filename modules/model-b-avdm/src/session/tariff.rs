@@ -0,0 +1,193 @@
+use super::{
+  bill::BillLine,
+  errors::SessionValueError,
+  kwh_milli::KwhMilli,
+  money_yen::MoneyYen,
+  rate::RateYenPerKwh,
+  units::{MILLI_YEN, Money, YEN},
+};
+
+/// 段階料金の 1 段を表す値オブジェクト。
+///
+/// `starts_at` 以上、次の段の `starts_at` 未満の課金対象エネルギーに `rate` を適用する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TariffTier {
+  starts_at: KwhMilli,
+  rate:      RateYenPerKwh,
+}
+
+impl TariffTier {
+  /// 適用開始エネルギー量と単価から段を生成する。
+  #[must_use]
+  pub fn new(starts_at: KwhMilli, rate: RateYenPerKwh) -> Self {
+    Self { starts_at, rate }
+  }
+
+  /// 適用開始エネルギー量を返す。
+  #[must_use]
+  pub fn starts_at(&self) -> KwhMilli {
+    self.starts_at
+  }
+
+  /// 単価を返す。
+  #[must_use]
+  pub fn rate(&self) -> RateYenPerKwh {
+    self.rate
+  }
+}
+
+/// 課金対象エネルギーに適用する料金表。
+///
+/// 段は適用開始エネルギー量の昇順に並び、最初の段は 0 から始まる。
+/// 単一の段のみの場合は従来の単価制と同じ結果になる。
+/// 最低料金・上限料金は段ごとの算出後に請求額全体へ適用する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tariff {
+  tiers:       Vec<TariffTier>,
+  minimum_fee: Option<MoneyYen>,
+  price_cap:   Option<MoneyYen>,
+}
+
+/// 請求額に適用された料金の下限・上限。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PriceLimit {
+  /// 最低料金まで引き上げた。
+  MinimumFee,
+  /// 上限料金まで引き下げた。
+  PriceCap,
+}
+
+impl Tariff {
+  /// 単一単価の料金表を生成する。
+  #[must_use]
+  pub fn flat(rate: RateYenPerKwh) -> Self {
+    Self { tiers: vec![TariffTier::new(KwhMilli::zero(), rate)], minimum_fee: None, price_cap: None }
+  }
+
+  /// 段階料金の料金表を生成する。
+  ///
+  /// # Errors
+  /// - 段が空の場合、`SessionValueError::EmptyTariff` を返します。
+  /// - 最初の段が 0 から始まらない、または適用開始量が昇順でない場合、
+  ///   `SessionValueError::InvalidTierThreshold` を返します。
+  pub fn tiered(tiers: Vec<TariffTier>) -> Result<Self, SessionValueError> {
+    let Some(first) = tiers.first() else {
+      return Err(SessionValueError::EmptyTariff);
+    };
+    if first.starts_at != KwhMilli::zero() {
+      return Err(SessionValueError::InvalidTierThreshold { index: 0, threshold: u64::from(first.starts_at) });
+    }
+    for (index, pair) in tiers.windows(2).enumerate() {
+      if pair[1].starts_at <= pair[0].starts_at {
+        return Err(SessionValueError::InvalidTierThreshold {
+          index:     index + 1,
+          threshold: u64::from(pair[1].starts_at),
+        });
+      }
+    }
+    Ok(Self { tiers, minimum_fee: None, price_cap: None })
+  }
+
+  /// 課金対象エネルギーがあるセッションに適用する最低料金を設定する。
+  ///
+  /// # Errors
+  /// 上限料金より高い場合、`SessionValueError::InvalidPriceLimits` を返します。
+  pub fn with_minimum_fee(self, minimum_fee: MoneyYen) -> Result<Self, SessionValueError> {
+    Self::validate_limits(Some(minimum_fee), self.price_cap)?;
+    Ok(Self { minimum_fee: Some(minimum_fee), ..self })
+  }
+
+  /// 1 セッションあたりの上限料金を設定する。
+  ///
+  /// # Errors
+  /// 最低料金より低い場合、`SessionValueError::InvalidPriceLimits` を返します。
+  pub fn with_price_cap(self, price_cap: MoneyYen) -> Result<Self, SessionValueError> {
+    Self::validate_limits(self.minimum_fee, Some(price_cap))?;
+    Ok(Self { price_cap: Some(price_cap), ..self })
+  }
+
+  /// 段を昇順で返す。
+  #[must_use]
+  pub fn tiers(&self) -> &[TariffTier] {
+    &self.tiers
+  }
+
+  /// 最低料金を返す。
+  #[must_use]
+  pub fn minimum_fee(&self) -> Option<MoneyYen> {
+    self.minimum_fee
+  }
+
+  /// 上限料金を返す。
+  #[must_use]
+  pub fn price_cap(&self) -> Option<MoneyYen> {
+    self.price_cap
+  }
+
+  /// 課金対象エネルギーを段ごとに振り分け、明細行と金額を算出する。
+  ///
+  /// 各段の小計はミリ円単位で保持し、合計に対して一度だけ床で丸める。
+  ///
+  /// # Errors
+  /// 算出結果が金額上限を超えた場合、`SessionValueError::AmountOutOfRange` を返します。
+  pub fn quote_for(&self, billed_energy: KwhMilli) -> Result<(Vec<BillLine>, MoneyYen), SessionValueError> {
+    let (lines, subtotal) = self.price_lines(billed_energy);
+    Ok((lines, MoneyYen::try_from_money(subtotal)?))
+  }
+
+  /// 段ごとの算出に最低料金・上限料金を適用した請求額を求める。
+  ///
+  /// 上限料金は金額の範囲検査より先に適用するため、上限料金があれば
+  /// 段ごとの合計が金額上限を超えるエネルギー量でも上限料金で請求できる。
+  /// 最低料金は課金対象エネルギーがある（無料で終わらなかった）場合にのみ適用する。
+  pub(crate) fn price_for(
+    &self,
+    billed_energy: KwhMilli,
+  ) -> Result<(Vec<BillLine>, MoneyYen, Option<PriceLimit>), SessionValueError> {
+    let (lines, subtotal) = self.price_lines(billed_energy);
+    let floored = subtotal.convert::<YEN>().value();
+    if let Some(cap) = self.price_cap.filter(|cap| floored > u128::from(u64::from(*cap))) {
+      return Ok((lines, cap, Some(PriceLimit::PriceCap)));
+    }
+    let quoted = MoneyYen::try_from_money(subtotal)?;
+    match self.minimum_fee {
+      | Some(minimum) if billed_energy != KwhMilli::zero() && quoted < minimum => {
+        Ok((lines, minimum, Some(PriceLimit::MinimumFee)))
+      },
+      | _ => Ok((lines, quoted, None)),
+    }
+  }
+
+  fn price_lines(&self, billed_energy: KwhMilli) -> (Vec<BillLine>, Money<MILLI_YEN>) {
+    let billed = u64::from(billed_energy);
+    let mut lines = Vec::new();
+    for (index, tier) in self.tiers.iter().enumerate() {
+      let starts_at = u64::from(tier.starts_at);
+      if billed <= starts_at && index > 0 {
+        break;
+      }
+      let ends_at = self.tiers.get(index + 1).map_or(billed, |next| u64::from(next.starts_at).min(billed));
+      lines.push(BillLine::new(KwhMilli::from_milli(ends_at - starts_at), tier.rate));
+    }
+    let subtotal = lines.iter().map(BillLine::amount).sum();
+    (lines, subtotal)
+  }
+
+  fn validate_limits(minimum_fee: Option<MoneyYen>, price_cap: Option<MoneyYen>) -> Result<(), SessionValueError> {
+    match (minimum_fee, price_cap) {
+      | (Some(minimum), Some(cap)) if minimum > cap => {
+        Err(SessionValueError::InvalidPriceLimits { minimum: u64::from(minimum), cap: u64::from(cap) })
+      },
+      | _ => Ok(()),
+    }
+  }
+}
+
+impl From<RateYenPerKwh> for Tariff {
+  fn from(value: RateYenPerKwh) -> Self {
+    Self::flat(value)
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,128 @@
+use std::num::NonZeroU32;
+
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+
+use super::{Tariff, TariffTier};
+use crate::session::{
+  BillingPolicy, KwhMilli, MoneyYen, RateYenPerKwh, Session, SessionId, SessionValueError,
+  test_support::{limited_tariff, stop_with_tariff, two_tier_tariff},
+};
+
+#[test]
+fn test_tiered_tariff_emits_line_per_tier() {
+  let started_at = datetime!(2025-10-20 10:00 UTC);
+  let session = Session::new_active_with_tariff(
+    SessionId::new(Uuid::nil()),
+    started_at,
+    two_tier_tariff(),
+    BillingPolicy::default(),
+  );
+  // 60分で 30 kWh → 無料5分を除いた課金対象は 27.5 kWh
+  let closed = session.stop(started_at + Duration::minutes(60), KwhMilli::try_new(30_000).unwrap()).unwrap();
+
+  let bill = closed.statement().unwrap();
+  let lines: Vec<_> = bill.lines().iter().map(|line| (u64::from(line.energy()), u32::from(line.rate()))).collect();
+  assert_eq!(lines, vec![(20_000, 30), (7_500, 20)]);
+  assert_eq!(u64::from(bill.amount_due()), 750);
+}
+
+#[test]
+fn test_tiered_tariff_rounds_once_after_summing_tiers() {
+  let rate = RateYenPerKwh::new(NonZeroU32::new(33).unwrap());
+  let tariff = Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), rate),
+    TariffTier::new(KwhMilli::try_new(500).unwrap(), rate),
+  ])
+  .unwrap();
+
+  // 16.5円 + 16.83円 = 33.33円 → 33円（行ごとに丸めると 32円になる）
+  let (lines, amount) = tariff.quote_for(KwhMilli::try_new(1_010).unwrap()).unwrap();
+  assert_eq!(lines.len(), 2);
+  assert_eq!(u64::from(amount), 33);
+  // 単一段は従来の単価制と同じ結果になる
+  let (_, flat) = Tariff::flat(rate).quote_for(KwhMilli::try_new(1_010).unwrap()).unwrap();
+  assert_eq!(flat, amount);
+}
+
+#[test]
+fn test_tiered_tariff_rejects_invalid_thresholds() {
+  let rate = RateYenPerKwh::new(NonZeroU32::new(30).unwrap());
+  assert!(matches!(Tariff::tiered(vec![]), Err(SessionValueError::EmptyTariff)));
+
+  let result = Tariff::tiered(vec![TariffTier::new(KwhMilli::try_new(1_000).unwrap(), rate)]);
+  assert!(matches!(result, Err(SessionValueError::InvalidTierThreshold { index: 0, threshold: 1_000 })));
+
+  let result = Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), rate),
+    TariffTier::new(KwhMilli::try_new(5_000).unwrap(), rate),
+    TariffTier::new(KwhMilli::try_new(5_000).unwrap(), rate),
+  ]);
+  assert!(matches!(result, Err(SessionValueError::InvalidTierThreshold { index: 2, threshold: 5_000 })));
+}
+
+#[test]
+fn test_minimum_fee_applies_once_grace_is_exceeded() {
+  // 課金対象 1 kWh = 30円 → 最低料金 100円
+  let closed = stop_with_tariff(limited_tariff(), 10, 2_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 100);
+  assert!(bill.minimum_fee_applied());
+  assert!(!bill.price_cap_applied());
+
+  // 無料時間内で終わったセッションには適用しない
+  let closed = stop_with_tariff(limited_tariff(), 3, 2_000);
+  let bill = closed.statement().unwrap();
+  assert!(bill.amount_due().is_zero());
+  assert!(!bill.minimum_fee_applied());
+}
+
+#[test]
+fn test_price_cap_limits_amount_due() {
+  // 課金対象 27.5 kWh = 825円 → 上限 500円
+  let closed = stop_with_tariff(limited_tariff(), 60, 30_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 500);
+  assert!(bill.price_cap_applied());
+  assert!(!bill.minimum_fee_applied());
+  // 明細行は上限適用前のエネルギー量を保持する
+  assert_eq!(u64::from(bill.lines()[0].energy()), 27_500);
+
+  // 上限以内なら通常どおり
+  let closed = stop_with_tariff(limited_tariff(), 15, 15_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 300);
+  assert!(!bill.price_cap_applied());
+}
+
+#[test]
+fn test_price_cap_applies_before_amount_range_check() {
+  let rate = RateYenPerKwh::new(NonZeroU32::new(2_000).unwrap());
+  let capped = Tariff::flat(rate).with_price_cap(MoneyYen::try_new(10_000).unwrap()).unwrap();
+
+  // 課金対象 550 kWh × 2,000円 = 1,100,000円は金額上限を超えるが、上限料金で請求できる
+  let closed = stop_with_tariff(capped, 60, 600_000);
+  let bill = closed.statement().unwrap();
+  assert_eq!(u64::from(bill.amount_due()), 10_000);
+  assert!(bill.price_cap_applied());
+
+  // 上限料金がなければ範囲エラーになる
+  let started_at = datetime!(2025-10-20 10:00 UTC);
+  let session = Session::new_active_with_tariff(
+    SessionId::new(Uuid::nil()),
+    started_at,
+    Tariff::flat(rate),
+    BillingPolicy::default(),
+  );
+  let result = session.stop(started_at + Duration::minutes(60), KwhMilli::try_new(600_000).unwrap());
+  assert!(matches!(result, Err(SessionValueError::AmountOutOfRange { .. })));
+}
+
+#[test]
+fn test_price_limits_reject_minimum_above_cap() {
+  let tariff = Tariff::flat(RateYenPerKwh::new(NonZeroU32::new(30).unwrap()))
+    .with_price_cap(MoneyYen::try_new(500).unwrap())
+    .unwrap();
+  let result = tariff.with_minimum_fee(MoneyYen::try_new(600).unwrap());
+  assert!(matches!(result, Err(SessionValueError::InvalidPriceLimits { minimum: 600, cap: 500 })));
+}
@@ -0,0 +1,68 @@
+//! セッション関連のテストで共有するヘルパー。
+
+use std::num::NonZeroU32;
+
+use time::{Duration, OffsetDateTime, macros::datetime};
+use uuid::Uuid;
+
+use super::{BillingPolicy, KwhMilli, MoneyYen, RateYenPerKwh, Session, SessionId, Tariff, TariffTier};
+
+pub(crate) fn create_test_session() -> (Session, OffsetDateTime) {
+  let session_id = SessionId::new(Uuid::nil());
+  let started_at = OffsetDateTime::now_utc();
+  let rate = RateYenPerKwh::new(NonZeroU32::new(30).unwrap()); // 30円/kWh
+  let session = Session::new_active(session_id, started_at, rate);
+  (session, started_at)
+}
+
+/// 指定した料金表と課金方針で開始し、`minutes` 分後に停止したセッションを返す。
+pub(crate) fn stop_session(
+  id: u128,
+  started_at: OffsetDateTime,
+  tariff: Tariff,
+  policy: BillingPolicy,
+  minutes: i64,
+  energy_milli: u64,
+) -> Session {
+  let session = Session::new_active_with_tariff(SessionId::new(Uuid::from_u128(id)), started_at, tariff, policy);
+  session.stop(started_at + Duration::minutes(minutes), KwhMilli::try_new(energy_milli).unwrap()).unwrap()
+}
+
+/// 30円/kWh の均一料金表。
+pub(crate) fn flat_tariff() -> Tariff {
+  Tariff::flat(RateYenPerKwh::new(NonZeroU32::new(30).unwrap()))
+}
+
+pub(crate) fn closed_session(id: u128, started_at: OffsetDateTime, minutes: i64, energy_milli: u64) -> Session {
+  stop_session(id, started_at, flat_tariff(), BillingPolicy::default(), minutes, energy_milli)
+}
+
+pub(crate) fn stop_with_policy(
+  policy: BillingPolicy,
+  started_at: OffsetDateTime,
+  minutes: i64,
+  energy_milli: u64,
+) -> Session {
+  stop_session(1, started_at, flat_tariff(), policy, minutes, energy_milli)
+}
+
+pub(crate) fn two_tier_tariff() -> Tariff {
+  // 最初の 20 kWh は 30円/kWh、それ以降は 20円/kWh
+  Tariff::tiered(vec![
+    TariffTier::new(KwhMilli::zero(), RateYenPerKwh::new(NonZeroU32::new(30).unwrap())),
+    TariffTier::new(KwhMilli::try_new(20_000).unwrap(), RateYenPerKwh::new(NonZeroU32::new(20).unwrap())),
+  ])
+  .unwrap()
+}
+
+pub(crate) fn limited_tariff() -> Tariff {
+  flat_tariff()
+    .with_minimum_fee(MoneyYen::try_new(100).unwrap())
+    .unwrap()
+    .with_price_cap(MoneyYen::try_new(500).unwrap())
+    .unwrap()
+}
+
+pub(crate) fn stop_with_tariff(tariff: Tariff, minutes: i64, energy_milli: u64) -> Session {
+  stop_session(0, datetime!(2025-10-20 10:00 UTC), tariff, BillingPolicy::default(), minutes, energy_milli)
+}
@@ -3,15 +3,7 @@ use std::num::NonZeroU32;
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
-use super::{KwhMilli, RateYenPerKwh, Session, SessionId, SessionValueError};
-
-fn create_test_session() -> (Session, OffsetDateTime) {
-  let session_id = SessionId::new(Uuid::nil());
-  let started_at = OffsetDateTime::now_utc();
-  let rate = RateYenPerKwh::new(NonZeroU32::new(30).unwrap()); // 30円/kWh
-  let session = Session::new_active(session_id, started_at, rate);
-  (session, started_at)
-}
+use super::{KwhMilli, RateYenPerKwh, Session, SessionId, SessionValueError, test_support::create_test_session};
 
 #[test]
 fn test_new_active_creates_session() {
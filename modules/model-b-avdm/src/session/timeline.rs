@@ -25,6 +25,12 @@ impl SessionTimeline {
     Ok(Self { elapsed_millis })
   }
 
+  /// 経過ミリ秒を返す。
+  #[must_use]
+  pub fn elapsed_millis(&self) -> u128 {
+    self.elapsed_millis
+  }
+
   /// 無料時間を適用し、課金ウィンドウを得る。
   #[must_use]
   pub fn consume_grace_period(&self, grace: GracePeriod) -> ChargeableWindow {
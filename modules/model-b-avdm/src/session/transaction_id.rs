@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use super::{errors::SessionValueError, session_id::SessionId};
+
+/// OCPP 1.6 の `transactionId`（中央システムが払い出す整数）を表す値オブジェクト。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OcppTransactionId(i32);
+
+impl OcppTransactionId {
+  /// 整数から取引IDを生成する。
+  #[must_use]
+  pub fn new(value: i32) -> Self {
+    Self(value)
+  }
+}
+
+impl From<i32> for OcppTransactionId {
+  fn from(value: i32) -> Self {
+    Self(value)
+  }
+}
+
+impl From<OcppTransactionId> for i32 {
+  fn from(value: OcppTransactionId) -> Self {
+    value.0
+  }
+}
+
+/// `SessionId` と外部システムの取引IDとの 1 対 1 の対応表。
+///
+/// 実際のセッションとの対応付けなので、nil の `SessionId` は登録できない。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionIdMap {
+  by_session:     HashMap<SessionId, OcppTransactionId>,
+  by_transaction: HashMap<OcppTransactionId, SessionId>,
+  next:           i32,
+}
+
+impl TransactionIdMap {
+  /// 空の対応表を生成する（払い出しは 1 から）。
+  #[must_use]
+  pub fn new() -> Self {
+    Self { next: 1, ..Self::default() }
+  }
+
+  /// セッションに取引IDを払い出す。払い出し済みであれば同じ取引IDを返す。
+  ///
+  /// # Errors
+  /// - セッションIDが nil の場合、`SessionValueError::NilSessionId` を返します。
+  /// - 払い出せる取引IDが尽きた場合、`SessionValueError::TransactionIdExhausted` を返します。
+  pub fn assign(&mut self, session_id: SessionId) -> Result<OcppTransactionId, SessionValueError> {
+    if let Some(existing) = self.transaction_for(session_id) {
+      return Ok(existing);
+    }
+    let mut candidate = self.next.max(1);
+    while self.by_transaction.contains_key(&OcppTransactionId(candidate)) {
+      candidate = candidate.checked_add(1).ok_or(SessionValueError::TransactionIdExhausted)?;
+    }
+    self.link(session_id, OcppTransactionId(candidate))?;
+    self.next = candidate.saturating_add(1);
+    Ok(OcppTransactionId(candidate))
+  }
+
+  /// 外部で払い出された取引IDをセッションに対応付ける。
+  ///
+  /// # Errors
+  /// - セッションIDが nil の場合、`SessionValueError::NilSessionId` を返します。
+  /// - セッションまたは取引IDが既に別の相手と対応付けられている場合、
+  ///   `SessionValueError::TransactionIdConflict` を返します。
+  pub fn link(&mut self, session_id: SessionId, transaction_id: OcppTransactionId) -> Result<(), SessionValueError> {
+    if session_id.is_nil() {
+      return Err(SessionValueError::NilSessionId);
+    }
+    let session_taken = self.by_session.get(&session_id).is_some_and(|linked| *linked != transaction_id);
+    let transaction_taken = self.by_transaction.get(&transaction_id).is_some_and(|linked| *linked != session_id);
+    if session_taken || transaction_taken {
+      return Err(SessionValueError::TransactionIdConflict { session_id, transaction_id: transaction_id.0 });
+    }
+    self.by_session.insert(session_id, transaction_id);
+    self.by_transaction.insert(transaction_id, session_id);
+    Ok(())
+  }
+
+  /// 取引IDに対応するセッションIDを返す。
+  #[must_use]
+  pub fn session_for(&self, transaction_id: OcppTransactionId) -> Option<SessionId> {
+    self.by_transaction.get(&transaction_id).copied()
+  }
+
+  /// セッションIDに対応する取引IDを返す。
+  #[must_use]
+  pub fn transaction_for(&self, session_id: SessionId) -> Option<OcppTransactionId> {
+    self.by_session.get(&session_id).copied()
+  }
+}
+
+#[cfg(test)]
+mod tests;
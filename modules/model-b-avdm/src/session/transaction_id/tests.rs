@@ -0,0 +1,39 @@
+use uuid::Uuid;
+
+use super::{OcppTransactionId, TransactionIdMap};
+use crate::session::{IdGenerator, SequentialIdGenerator, SessionId, SessionValueError};
+
+#[test]
+fn test_transaction_id_map_assigns_sequential_ids_idempotently() {
+  let mut generator = SequentialIdGenerator::new();
+  let (first, second) = (generator.next_id(), generator.next_id());
+  let mut map = TransactionIdMap::new();
+
+  let first_tx = map.assign(first).unwrap();
+  let second_tx = map.assign(second).unwrap();
+
+  assert_eq!(i32::from(first_tx), 1);
+  assert_eq!(i32::from(second_tx), 2);
+  assert_eq!(map.assign(first), Ok(first_tx));
+  assert_eq!(map.session_for(second_tx), Some(second));
+  assert_eq!(map.transaction_for(first), Some(first_tx));
+  assert_eq!(map.session_for(OcppTransactionId::new(99)), None);
+}
+
+#[test]
+fn test_transaction_id_map_rejects_nil_and_conflicting_links() {
+  let mut generator = SequentialIdGenerator::new();
+  let (first, second) = (generator.next_id(), generator.next_id());
+  let mut map = TransactionIdMap::new();
+
+  assert_eq!(map.assign(SessionId::new(Uuid::nil())), Err(SessionValueError::NilSessionId));
+
+  map.link(first, OcppTransactionId::new(1)).unwrap();
+  assert_eq!(
+    map.link(second, OcppTransactionId::new(1)),
+    Err(SessionValueError::TransactionIdConflict { session_id: second, transaction_id: 1 })
+  );
+  assert!(map.link(first, OcppTransactionId::new(2)).is_err());
+  // 外部で払い出された 1 を避けて払い出す
+  assert_eq!(map.assign(second), Ok(OcppTransactionId::new(2)));
+}
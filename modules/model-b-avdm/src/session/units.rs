@@ -0,0 +1,176 @@
+use std::{
+  iter::Sum,
+  ops::{Add, Div, Mul},
+};
+
+/// 1 W・ms を基準としたエネルギー単位の大きさ。
+pub(crate) const WATT_MILLISECOND: u128 = 1;
+/// 1 Wh（= 1 ミリkWh）あたりの W・ms。
+pub(crate) const WATT_HOUR: u128 = 3_600_000;
+/// 1 kWh あたりの W・ms。
+pub(crate) const KILOWATT_HOUR: u128 = 1_000 * WATT_HOUR;
+/// 1 ミリ円を基準とした金額単位の大きさ。
+pub(crate) const MILLI_YEN: u128 = 1;
+/// 1 円あたりのミリ円。
+pub(crate) const YEN: u128 = 1_000;
+
+/// 単位 `UNIT`（W・ms の倍数）で表したエネルギー量。
+///
+/// 単位の異なる値同士は型が異なるため、換算は `convert` を経由した場合にのみ行われる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Energy<const UNIT: u128>(u128);
+
+impl<const UNIT: u128> Energy<UNIT> {
+  pub(crate) const fn new(value: u128) -> Self {
+    Self(value)
+  }
+
+  pub(crate) const fn value(self) -> u128 {
+    self.0
+  }
+
+  /// 別の単位に換算する（小さい単位から大きい単位へは床で丸める）。
+  pub(crate) const fn convert<const TO: u128>(self) -> Energy<TO> {
+    Energy(self.0 * UNIT / TO)
+  }
+
+  /// `numerator / denominator` 倍した値を床で丸めて返す。
+  pub(crate) const fn scale(self, numerator: u128, denominator: u128) -> Self {
+    Self(self.0 * numerator / denominator)
+  }
+
+  /// 差を返す（`rhs` の方が大きい場合は `None`）。
+  pub(crate) const fn checked_sub(self, rhs: Self) -> Option<Self> {
+    match self.0.checked_sub(rhs.0) {
+      | Some(value) => Some(Self(value)),
+      | None => None,
+    }
+  }
+
+  /// 差を返す（`rhs` の方が大きい場合は 0）。
+  pub(crate) const fn saturating_sub(self, rhs: Self) -> Self {
+    Self(self.0.saturating_sub(rhs.0))
+  }
+}
+
+impl<const UNIT: u128> Add for Energy<UNIT> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self(self.0 + rhs.0)
+  }
+}
+
+/// エネルギー ÷ 時間 = 電力（W、床）。時間が 0 の場合は `None` になる。
+impl<const UNIT: u128> Div<Millis> for Energy<UNIT> {
+  type Output = Option<Watts>;
+
+  fn div(self, rhs: Millis) -> Self::Output {
+    self.convert::<WATT_MILLISECOND>().0.checked_div(rhs.0).map(Watts)
+  }
+}
+
+/// エネルギー ÷ 電力 = 時間（ms、床）。
+impl<const UNIT: u128> Div<Watts> for Energy<UNIT> {
+  type Output = Millis;
+
+  fn div(self, rhs: Watts) -> Self::Output {
+    Millis(self.0 * UNIT / rhs.0)
+  }
+}
+
+/// エネルギー × 単価 = 金額（ミリ円、床）。
+impl<const UNIT: u128> Mul<YenPerKwh> for Energy<UNIT> {
+  type Output = Money<MILLI_YEN>;
+
+  fn mul(self, rhs: YenPerKwh) -> Self::Output {
+    Money(self.0 * UNIT * rhs.0 * YEN / KILOWATT_HOUR)
+  }
+}
+
+/// 単位 `UNIT`（ミリ円の倍数）で表した金額。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Money<const UNIT: u128>(u128);
+
+impl<const UNIT: u128> Money<UNIT> {
+  pub(crate) const fn value(self) -> u128 {
+    self.0
+  }
+
+  /// 別の単位に換算する（小さい単位から大きい単位へは床で丸める）。
+  pub(crate) const fn convert<const TO: u128>(self) -> Money<TO> {
+    Money(self.0 * UNIT / TO)
+  }
+}
+
+impl<const UNIT: u128> Add for Money<UNIT> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self(self.0 + rhs.0)
+  }
+}
+
+impl<const UNIT: u128> Sum for Money<UNIT> {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+    iter.fold(Self(0), Add::add)
+  }
+}
+
+/// ミリ秒単位の時間。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Millis(u128);
+
+impl Millis {
+  pub(crate) const fn new(value: u128) -> Self {
+    Self(value)
+  }
+
+  pub(crate) const fn value(self) -> u128 {
+    self.0
+  }
+
+  pub(crate) const fn saturating_sub(self, rhs: Self) -> Self {
+    Self(self.0.saturating_sub(rhs.0))
+  }
+}
+
+impl Add for Millis {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    Self(self.0 + rhs.0)
+  }
+}
+
+/// W 単位の電力。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Watts(u128);
+
+impl Watts {
+  pub(crate) const fn new(value: u128) -> Self {
+    Self(value)
+  }
+}
+
+/// 電力 × 時間 = エネルギー（W・ms）。
+impl Mul<Millis> for Watts {
+  type Output = Energy<WATT_MILLISECOND>;
+
+  fn mul(self, rhs: Millis) -> Self::Output {
+    Energy(self.0 * rhs.0)
+  }
+}
+
+/// 円/kWh 単位の単価。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct YenPerKwh(u128);
+
+impl YenPerKwh {
+  pub(crate) const fn new(value: u128) -> Self {
+    Self(value)
+  }
+}
+
+#[cfg(test)]
+mod tests;
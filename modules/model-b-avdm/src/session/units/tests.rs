@@ -0,0 +1,21 @@
+use super::{Energy, KILOWATT_HOUR, MILLI_YEN, Millis, WATT_HOUR, WATT_MILLISECOND, Watts, YEN, YenPerKwh};
+
+#[test]
+fn test_units_scale_between_milli_and_kilo() {
+  // 7 kW × 30 分 = 3.5 kWh
+  let delivered = Watts::new(7_000) * Millis::new(30 * 60_000);
+  assert_eq!(delivered.convert::<WATT_HOUR>(), Energy::new(3_500));
+  assert_eq!(delivered.convert::<KILOWATT_HOUR>(), Energy::new(3));
+  assert_eq!(delivered / Millis::new(30 * 60_000), Some(Watts::new(7_000)));
+  assert_eq!(delivered / Millis::new(0), None);
+  assert_eq!(Energy::<WATT_HOUR>::new(1).checked_sub(Energy::new(2)), None);
+  assert_eq!(Energy::<WATT_HOUR>::new(1).saturating_sub(Energy::new(2)), Energy::new(0));
+  assert_eq!(delivered / Watts::new(7_000), Millis::new(30 * 60_000));
+
+  // 3.5 kWh × 33円/kWh = 115.5円 → 115円（床）
+  let amount = Energy::<WATT_HOUR>::new(3_500) * YenPerKwh::new(33);
+  assert_eq!(amount.value(), 115_500);
+  assert_eq!(amount.convert::<YEN>().value(), 115);
+  assert_eq!(amount.convert::<YEN>().convert::<MILLI_YEN>().value(), 115_000);
+  assert_eq!(Energy::<WATT_MILLISECOND>::new(3_599_999).convert::<WATT_HOUR>(), Energy::new(0));
+}
@@ -0,0 +1,260 @@
+use thiserror::Error;
+use time::OffsetDateTime;
+
+use super::{
+  base::Session, bill::SessionBill, errors::SessionValueError, kwh_milli::KwhMilli, money_yen::MoneyYen,
+  session_id::SessionId,
+};
+
+/// セッション開始時に確保した与信枠。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WalletHold {
+  session_id: SessionId,
+  amount:     MoneyYen,
+}
+
+/// 前払い残高とセッションごとの与信枠（ホールド）を管理するウォレット。
+///
+/// 残高のうち与信枠として確保された分は他のセッションに使えず、
+/// 停止時に確定金額（与信枠が上限）だけが引き落とされ、残りは解放される。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrepaidWallet {
+  balance: MoneyYen,
+  holds:   Vec<WalletHold>,
+}
+
+impl PrepaidWallet {
+  /// 初期残高を指定してウォレットを生成する。
+  #[must_use]
+  pub fn new(balance: MoneyYen) -> Self {
+    Self { balance, holds: Vec::new() }
+  }
+
+  /// 残高をチャージする。
+  ///
+  /// # Errors
+  /// チャージ後の残高が上限を超える場合、`SessionValueError::AmountOutOfRange` を返します。
+  pub fn top_up(&mut self, amount: MoneyYen) -> Result<(), SessionValueError> {
+    self.balance = self.balance.checked_add(amount)?;
+    Ok(())
+  }
+
+  /// 与信枠を含む残高を返す。
+  #[must_use]
+  pub fn balance(&self) -> MoneyYen {
+    self.balance
+  }
+
+  /// 与信枠として確保済みの金額を返す。
+  #[must_use]
+  pub fn held(&self) -> MoneyYen {
+    // 与信枠の合計は常に残高以下に保たれるため上限を超えない
+    MoneyYen::from_yen(self.holds.iter().map(|hold| u64::from(hold.amount)).sum())
+  }
+
+  /// 新たな与信枠に使える金額を返す。
+  #[must_use]
+  pub fn available(&self) -> MoneyYen {
+    MoneyYen::from_yen(u64::from(self.balance) - u64::from(self.held()))
+  }
+
+  /// アクティブなセッションに与信枠を確保して開始する。
+  ///
+  /// # Errors
+  /// - セッションが停止済みの場合、`SessionValueError::AlreadyClosed` を返します。
+  /// - 同一セッションに与信枠が確保済みの場合、`SessionValueError::DuplicateHold` を返します。
+  /// - 利用可能残高が不足する場合、`SessionValueError::InsufficientBalance` を返します。
+  pub fn start_session(&mut self, session: Session, hold: MoneyYen) -> Result<HeldSession, SessionValueError> {
+    let session_id = session.identity();
+    if session.statement().is_some() {
+      return Err(SessionValueError::AlreadyClosed { session_id });
+    }
+    if self.find_hold(session_id).is_some() {
+      return Err(SessionValueError::DuplicateHold { session_id });
+    }
+    let available = self.available();
+    if hold > available {
+      return Err(SessionValueError::InsufficientBalance {
+        requested: u64::from(hold),
+        available: u64::from(available),
+      });
+    }
+
+    self.holds.push(WalletHold { session_id, amount: hold });
+    Ok(HeldSession { session, hold })
+  }
+
+  /// セッションを停止し、確定金額を引き落として残りの与信枠を解放する。
+  ///
+  /// 確定金額が与信枠を超えた場合は強制停止として扱い、与信枠の全額だけを引き落として
+  /// 超過分を `WalletSettlement::shortfall` で報告する。
+  ///
+  /// # Errors
+  /// 失敗した場合はセッションと与信枠をそのまま残し、`HeldSession` を `WalletCaptureError`
+  /// に入れて返します。原因は `WalletCaptureError::error` で確認できます。
+  /// - 与信枠が見つからない（解放済みを含む）場合、`SessionValueError::HoldNotFound`。
+  /// - 停止処理自体が失敗した場合、その `SessionValueError`。
+  pub fn capture(
+    &mut self,
+    held: HeldSession,
+    ended_at: OffsetDateTime,
+    total_energy: KwhMilli,
+  ) -> Result<WalletSettlement, WalletCaptureError> {
+    let session_id = held.session.identity();
+    let Some(index) = self.find_hold(session_id) else {
+      return Err(WalletCaptureError { held: Box::new(held), error: SessionValueError::HoldNotFound { session_id } });
+    };
+    let session = match held.session.clone().stop(ended_at, total_energy) {
+      | Ok(session) => session,
+      | Err(error) => return Err(WalletCaptureError { held: Box::new(held), error }),
+    };
+
+    self.holds.remove(index);
+    let amount_due = session.statement().map_or(MoneyYen::zero(), |bill| bill.amount_due());
+    let captured = amount_due.min(held.hold);
+    // 引き落とし額は与信枠以下で、与信枠は常に残高以下に保たれている
+    self.balance = MoneyYen::from_yen(u64::from(self.balance) - u64::from(captured));
+    Ok(WalletSettlement {
+      session,
+      captured,
+      released: MoneyYen::from_yen(u64::from(held.hold) - u64::from(captured)),
+      shortfall: MoneyYen::from_yen(u64::from(amount_due) - u64::from(captured)),
+    })
+  }
+
+  fn find_hold(&self, session_id: SessionId) -> Option<usize> {
+    self.holds.iter().position(|hold| hold.session_id == session_id)
+  }
+}
+
+/// 与信枠を確保した状態で進行中のセッション。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeldSession {
+  session: Session,
+  hold:    MoneyYen,
+}
+
+impl HeldSession {
+  /// 指定時点での課金スナップショットと与信枠の残りを取得する。
+  ///
+  /// # Errors
+  /// - 見込み金額が与信枠を超えた場合、`SessionValueError::HoldExceeded` を返します。
+  ///   この場合セッションを継続せず停止させる必要があります。
+  /// - 課金計算に失敗した場合、その `SessionValueError` を返します。
+  pub fn bill_snapshot(
+    &self,
+    ended_at: OffsetDateTime,
+    total_energy: KwhMilli,
+  ) -> Result<HeldSnapshot, SessionValueError> {
+    let bill = self.session.bill_snapshot(ended_at, total_energy)?;
+    let headroom = self.headroom_for(bill.amount_due())?;
+    Ok(HeldSnapshot { bill, headroom })
+  }
+
+  /// 保持しているセッションを返す。
+  #[must_use]
+  pub fn session(&self) -> &Session {
+    &self.session
+  }
+
+  /// 確保した与信枠を返す。
+  #[must_use]
+  pub fn hold(&self) -> MoneyYen {
+    self.hold
+  }
+
+  fn headroom_for(&self, projected: MoneyYen) -> Result<MoneyYen, SessionValueError> {
+    if projected > self.hold {
+      return Err(SessionValueError::HoldExceeded {
+        session_id: self.session.identity(),
+        projected:  u64::from(projected),
+        hold:       u64::from(self.hold),
+      });
+    }
+    self.hold.checked_sub(projected)
+  }
+}
+
+/// 引き落としに失敗したことを表すエラー。
+///
+/// 与信枠は確保したまま残るため、`into_held` で取り戻したセッションで再度精算できる。
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{error}")]
+pub struct WalletCaptureError {
+  held:  Box<HeldSession>,
+  #[source]
+  error: SessionValueError,
+}
+
+impl WalletCaptureError {
+  /// 失敗の原因を返す。
+  #[must_use]
+  pub fn error(&self) -> &SessionValueError {
+    &self.error
+  }
+
+  /// 精算できなかったセッションを取り戻す。
+  #[must_use]
+  pub fn into_held(self) -> HeldSession {
+    *self.held
+  }
+}
+
+/// 与信枠内での課金スナップショット。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeldSnapshot {
+  bill:     SessionBill,
+  headroom: MoneyYen,
+}
+
+impl HeldSnapshot {
+  /// 見込みの請求を返す。
+  #[must_use]
+  pub fn bill(&self) -> &SessionBill {
+    &self.bill
+  }
+
+  /// 与信枠の残り（あといくら課金できるか）を返す。
+  #[must_use]
+  pub fn headroom(&self) -> MoneyYen {
+    self.headroom
+  }
+}
+
+/// 停止時の引き落とし結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletSettlement {
+  session:   Session,
+  captured:  MoneyYen,
+  released:  MoneyYen,
+  shortfall: MoneyYen,
+}
+
+impl WalletSettlement {
+  /// 停止済みセッションを返す。
+  #[must_use]
+  pub fn session(&self) -> &Session {
+    &self.session
+  }
+
+  /// 引き落とした金額（確定請求額と与信枠のうち小さい方）を返す。
+  #[must_use]
+  pub fn captured(&self) -> MoneyYen {
+    self.captured
+  }
+
+  /// 解放した与信枠の金額を返す。
+  #[must_use]
+  pub fn released(&self) -> MoneyYen {
+    self.released
+  }
+
+  /// 確定請求額のうち与信枠を超えて引き落とせなかった金額を返す。
+  #[must_use]
+  pub fn shortfall(&self) -> MoneyYen {
+    self.shortfall
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+use time::Duration;
+
+use super::PrepaidWallet;
+use crate::session::{KwhMilli, MoneyYen, SessionValueError, test_support::create_test_session};
+
+#[test]
+fn test_wallet_hold_reports_headroom_and_captures_amount_due() {
+  let (session, started_at) = create_test_session();
+  let mut wallet = PrepaidWallet::new(MoneyYen::try_new(1_000).unwrap());
+
+  let held = wallet.start_session(session, MoneyYen::try_new(500).unwrap()).unwrap();
+  assert_eq!(u64::from(wallet.available()), 500);
+
+  // 10分後、10 kWh -> 150円、与信枠の残りは350円
+  let snapshot = held.bill_snapshot(started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  assert_eq!(u64::from(snapshot.bill().amount_due()), 150);
+  assert_eq!(u64::from(snapshot.headroom()), 350);
+
+  let retry = held.clone();
+  let settlement =
+    wallet.capture(held, started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap()).unwrap();
+  assert_eq!(u64::from(settlement.captured()), 150);
+  assert_eq!(u64::from(settlement.released()), 350);
+  assert!(settlement.shortfall().is_zero());
+  assert!(settlement.session().statement().is_some());
+  assert_eq!(u64::from(wallet.balance()), 850);
+  assert_eq!(u64::from(wallet.available()), 850);
+
+  // 精算済みの与信枠は再利用できない
+  let result = wallet.capture(retry, started_at + Duration::minutes(10), KwhMilli::try_new(10_000).unwrap());
+  assert!(matches!(result.unwrap_err().error(), SessionValueError::HoldNotFound { .. }));
+}
+
+#[test]
+fn test_wallet_force_stops_beyond_hold_and_reports_shortfall() {
+  let (session, started_at) = create_test_session();
+  let mut wallet = PrepaidWallet::new(MoneyYen::try_new(1_000).unwrap());
+  let held = wallet.start_session(session, MoneyYen::try_new(100).unwrap()).unwrap();
+
+  // 10分後、10 kWh -> 150円は与信枠100円を超えるため、継続せず停止させる
+  let ended_at = started_at + Duration::minutes(10);
+  let total_energy = KwhMilli::try_new(10_000).unwrap();
+  let result = held.bill_snapshot(ended_at, total_energy);
+  assert!(matches!(result, Err(SessionValueError::HoldExceeded { projected: 150, hold: 100, .. })));
+
+  // 精算では与信枠の全額だけを引き落とし、超過分を不足額として報告する
+  let settlement = wallet.capture(held, ended_at, total_energy).unwrap();
+  assert_eq!(u64::from(settlement.session().statement().unwrap().amount_due()), 150);
+  assert_eq!(u64::from(settlement.captured()), 100);
+  assert!(settlement.released().is_zero());
+  assert_eq!(u64::from(settlement.shortfall()), 50);
+  assert_eq!(u64::from(wallet.balance()), 900);
+  assert!(wallet.held().is_zero());
+}
+
+#[test]
+fn test_wallet_keeps_hold_and_session_when_stop_fails() {
+  let (session, started_at) = create_test_session();
+  let mut wallet = PrepaidWallet::new(MoneyYen::try_new(1_000).unwrap());
+  let held = wallet.start_session(session, MoneyYen::try_new(100).unwrap()).unwrap();
+
+  let failure = wallet.capture(held, started_at - Duration::minutes(1), KwhMilli::try_new(1_000).unwrap()).unwrap_err();
+  assert!(matches!(failure.error(), SessionValueError::InvalidTimeline { .. }));
+  assert_eq!(u64::from(wallet.balance()), 1_000);
+  assert_eq!(u64::from(wallet.held()), 100);
+
+  // 取り戻したセッションで改めて精算できる
+  let settlement =
+    wallet.capture(failure.into_held(), started_at + Duration::minutes(10), KwhMilli::try_new(1_000).unwrap()).unwrap();
+  assert_eq!(u64::from(settlement.captured()), 15);
+  assert!(wallet.held().is_zero());
+}
+
+#[test]
+fn test_wallet_rejects_hold_beyond_available_balance() {
+  let (session, _) = create_test_session();
+  let mut wallet = PrepaidWallet::new(MoneyYen::try_new(300).unwrap());
+
+  let result = wallet.start_session(session, MoneyYen::try_new(500).unwrap());
+  assert!(matches!(result, Err(SessionValueError::InsufficientBalance { requested: 500, available: 300 })));
+}
@@ -1,13 +1,15 @@
-use model_b_avdm::session::{KwhMilli, RateYenPerKwh, Session, SessionId, SessionValueError};
+use model_b_avdm::session::{
+  ActiveSession, BillingPolicy, ClosedSession, IdGenerator, KwhMilli, RateYenPerKwh, SessionValueError, Tariff,
+  UuidV7Generator,
+};
 use time::{Duration, OffsetDateTime};
-use uuid::Uuid;
 
 use crate::{BillingResult, BillingSession, ClosedBillingSession};
 
 /// AVDM モデルを `BillingSession` として扱うためのアダプタ。
 #[derive(Debug)]
 pub struct ModelBSession {
-  inner: Session,
+  inner: ActiveSession,
 }
 
 /// model-b のドメインエラーと技術的エラーをまとめた型。
@@ -36,7 +38,8 @@ impl BillingSession for ModelBSession {
   fn start(start_epoch_ms: i64, rate_yen_per_kwh: u32) -> Result<Self, Self::Error> {
     let started_at = ms_to_offset_datetime(start_epoch_ms)?;
     let rate = RateYenPerKwh::try_new(rate_yen_per_kwh)?;
-    let session = Session::new_active(SessionId::new(Uuid::nil()), started_at, rate);
+    let session =
+      ActiveSession::new(UuidV7Generator.next_id(), started_at, Tariff::flat(rate), BillingPolicy::default());
     Ok(Self { inner: session })
   }
 
@@ -57,37 +60,24 @@ impl BillingSession for ModelBSession {
     let energy = KwhMilli::try_from_i64(energy_milli)?;
     let ended_at = ms_to_offset_datetime(end_epoch_ms)?;
     let session = self.inner.stop(ended_at, energy)?;
-    let result = match &session {
-      | Session::Closed { bill, .. } => BillingResult::from_model_b(bill.billable_energy(), bill.amount_due()),
-      | Session::Active { .. } => panic!("expected closed session"),
-    };
-    Ok((result, ClosedModelBSession::new(session)))
+    let bill = session.bill();
+    let result = BillingResult::from_model_b(bill.billable_energy(), bill.amount_due());
+    Ok((result, ClosedModelBSession { inner: session }))
   }
 }
 
 /// 停止済み AVDM セッションを `ClosedBillingSession` として扱うラッパー。
 #[derive(Debug)]
 pub struct ClosedModelBSession {
-  inner: Session,
-}
-
-impl ClosedModelBSession {
-  fn new(inner: Session) -> Self {
-    match inner {
-      | Session::Closed { .. } => Self { inner },
-      | Session::Active { .. } => panic!("expected closed session"),
-    }
-  }
+  inner: ClosedSession,
 }
 
 impl ClosedBillingSession for ClosedModelBSession {
   type Error = ModelBError;
 
+  /// `ClosedSession` は課金操作を持たないため、常に停止済みエラーを返す。
   fn bill_after_stop(&self, _end_epoch_ms: i64, _energy_milli: i64) -> Result<BillingResult, Self::Error> {
-    match &self.inner {
-      | Session::Closed { id, .. } => Err(ModelBError::Domain(SessionValueError::AlreadyClosed { session_id: *id })),
-      | Session::Active { .. } => panic!("closed session wrapper must hold a closed session"),
-    }
+    Err(ModelBError::Domain(SessionValueError::AlreadyClosed { session_id: self.inner.identity() }))
   }
 }
 